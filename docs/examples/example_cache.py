@@ -0,0 +1,5 @@
+import ormsgpack
+ormsgpack.cache_configure(size=4096, associativity=4)
+data = ormsgpack.packb([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}])
+ormsgpack.unpackb(data)
+ormsgpack.cache_info()
@@ -0,0 +1,5 @@
+import ormsgpack, decimal
+ormsgpack.packb(decimal.Decimal("1.10"))
+ormsgpack.unpackb(_, type=decimal.Decimal)
+ormsgpack.packb(decimal.Decimal("1.10"), option=ormsgpack.OPT_DECIMAL_AS_EXT)
+ormsgpack.unpackb(_, option=ormsgpack.OPT_DECIMAL_AS_EXT)
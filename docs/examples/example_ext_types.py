@@ -0,0 +1,5 @@
+import ormsgpack, decimal
+ext_types = {decimal.Decimal: (0, lambda obj: str(obj).encode())}
+ext_hooks = {0: lambda data: decimal.Decimal(data.decode())}
+ormsgpack.packb([decimal.Decimal("3.14")], ext_types=ext_types)
+ormsgpack.unpackb(_, ext_hooks=ext_hooks)
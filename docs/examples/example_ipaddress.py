@@ -0,0 +1,3 @@
+import ormsgpack, ipaddress
+ormsgpack.packb(ipaddress.ip_network("192.0.2.0/24"))
+ormsgpack.unpackb(_)
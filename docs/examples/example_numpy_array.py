@@ -0,0 +1,4 @@
+import ormsgpack
+data = ormsgpack.packb([[1, 2, 3], [4, 5, 6]])
+ormsgpack.unpackb(data, option=ormsgpack.OPT_ARRAY_AS_NUMPY)
+ormsgpack.unpackb(ormsgpack.packb([0.5, 1.5]), option=ormsgpack.OPT_ARRAY_AS_NUMPY)
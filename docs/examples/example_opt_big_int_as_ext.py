@@ -0,0 +1,3 @@
+import ormsgpack
+ormsgpack.packb(2**65, option=ormsgpack.OPT_BIG_INT_AS_EXT)
+ormsgpack.unpackb(_, option=ormsgpack.OPT_BIG_INT_AS_EXT)
@@ -0,0 +1,3 @@
+import ormsgpack
+ormsgpack.packb({"b", "c", "a"}, option=ormsgpack.OPT_SORT_KEYS)
+ormsgpack.unpackb(_)
@@ -0,0 +1,5 @@
+import ormsgpack, datetime
+ormsgpack.packb(datetime.timedelta(days=1, hours=2, minutes=30))
+ormsgpack.unpackb(_, type=datetime.timedelta)
+ormsgpack.packb(datetime.timedelta(seconds=1.5), option=ormsgpack.OPT_TIMEDELTA_AS_SECONDS)
+ormsgpack.unpackb(_, type=datetime.timedelta)
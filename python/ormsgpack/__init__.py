@@ -1,7 +1,16 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 from .ormsgpack import (
+    OPT_ARRAY_AS_NUMPY,
+    OPT_ARRAY_AS_TUPLE,
+    OPT_BIG_INT_AS_EXT,
+    OPT_BIN_AS_BYTEARRAY,
+    OPT_BIN_AS_MEMORYVIEW,
+    OPT_COMPLEX_AS_EXT,
     OPT_DATETIME_AS_TIMESTAMP_EXT,
+    OPT_DECIMAL_AS_EXT,
+    OPT_FORBID_DUPLICATE_KEYS,
+    OPT_FORBID_TRAILING_DATA,
     OPT_NAIVE_UTC,
     OPT_NON_STR_KEYS,
     OPT_OMIT_MICROSECONDS,
@@ -9,6 +18,7 @@ from .ormsgpack import (
     OPT_PASSTHROUGH_DATACLASS,
     OPT_PASSTHROUGH_DATETIME,
     OPT_PASSTHROUGH_ENUM,
+    OPT_PASSTHROUGH_NUMBER,
     OPT_PASSTHROUGH_SUBCLASS,
     OPT_PASSTHROUGH_TUPLE,
     OPT_PASSTHROUGH_UUID,
@@ -16,25 +26,70 @@ from .ormsgpack import (
     OPT_SERIALIZE_NUMPY,
     OPT_SERIALIZE_PYDANTIC,
     OPT_SORT_KEYS,
+    OPT_TIMEDELTA_AS_MICROSECONDS,
+    OPT_TIMEDELTA_AS_SECONDS,
+    OPT_TIMESTAMP_AS_INT,
+    OPT_TIMESTAMP_FALLBACK,
     OPT_UTC_Z,
+    Codec,
     Ext,
     Fragment,
     MsgpackDecodeError,
+    MsgpackDecodeLimitError,
     MsgpackEncodeError,
+    Timestamp,
+    Unpacker,
+    View,
     __version__,
+    cache_clear,
+    cache_configure,
+    cache_info,
+    pack,
+    pack_frame,
     packb,
+    packb_into,
+    unpack,
+    unpack_frame,
+    unpack_frame_with_offset,
     unpackb,
+    unpackb_path,
+    unpackb_with_offset,
 )
 
 __all__ = (
     "__version__",
+    "cache_clear",
+    "cache_configure",
+    "cache_info",
+    "pack",
+    "pack_frame",
     "packb",
+    "packb_into",
+    "unpack",
+    "unpack_frame",
+    "unpack_frame_with_offset",
     "unpackb",
+    "unpackb_path",
+    "unpackb_with_offset",
+    "Codec",
     "Ext",
     "Fragment",
     "MsgpackDecodeError",
+    "MsgpackDecodeLimitError",
     "MsgpackEncodeError",
+    "Timestamp",
+    "Unpacker",
+    "View",
+    "OPT_ARRAY_AS_NUMPY",
+    "OPT_ARRAY_AS_TUPLE",
+    "OPT_BIG_INT_AS_EXT",
+    "OPT_BIN_AS_BYTEARRAY",
+    "OPT_BIN_AS_MEMORYVIEW",
+    "OPT_COMPLEX_AS_EXT",
     "OPT_DATETIME_AS_TIMESTAMP_EXT",
+    "OPT_DECIMAL_AS_EXT",
+    "OPT_FORBID_DUPLICATE_KEYS",
+    "OPT_FORBID_TRAILING_DATA",
     "OPT_NAIVE_UTC",
     "OPT_NON_STR_KEYS",
     "OPT_OMIT_MICROSECONDS",
@@ -42,6 +97,7 @@ __all__ = (
     "OPT_PASSTHROUGH_DATACLASS",
     "OPT_PASSTHROUGH_DATETIME",
     "OPT_PASSTHROUGH_ENUM",
+    "OPT_PASSTHROUGH_NUMBER",
     "OPT_PASSTHROUGH_SUBCLASS",
     "OPT_PASSTHROUGH_TUPLE",
     "OPT_PASSTHROUGH_UUID",
@@ -49,5 +105,9 @@ __all__ = (
     "OPT_SERIALIZE_NUMPY",
     "OPT_SERIALIZE_PYDANTIC",
     "OPT_SORT_KEYS",
+    "OPT_TIMEDELTA_AS_MICROSECONDS",
+    "OPT_TIMEDELTA_AS_SECONDS",
+    "OPT_TIMESTAMP_AS_INT",
+    "OPT_TIMESTAMP_FALLBACK",
     "OPT_UTC_Z",
 )
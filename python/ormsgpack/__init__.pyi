@@ -1,38 +1,319 @@
-from collections.abc import Callable
-from typing import Any
+from collections.abc import Callable, Iterator
+from typing import IO, Any, Literal, TypedDict
 
 __version__: str
 
+class _CacheInfo(TypedDict):
+    hits: int
+    misses: int
+    evictions: int
+    entries: int
+    size: int
+    max_key_len: int
+    associativity: int
+
+def cache_clear() -> None: ...
+def cache_configure(
+    *,
+    size: int | None = None,
+    max_key_len: int | None = None,
+    associativity: int | None = None,
+) -> None: ...
+def cache_info() -> _CacheInfo: ...
+def pack(
+    obj: Any,
+    fp: IO[bytes],
+    /,
+    *,
+    default: Callable[[Any], Any] | None = ...,
+    ext_types: dict[type, tuple[int, Callable[[Any], bytes]]] | None = ...,
+    option: int | None = None,
+) -> None: ...
+def pack_frame(
+    obj: Any,
+    /,
+    *,
+    checksum: bool = True,
+    default: Callable[[Any], Any] | None = ...,
+    ext_types: dict[type, tuple[int, Callable[[Any], bytes]]] | None = ...,
+    option: int | None = None,
+) -> bytes: ...
 def packb(
     obj: Any,
     /,
     default: Callable[[Any], Any] | None = ...,
     option: int | None = None,
+    *,
+    ext_types: dict[type, tuple[int, Callable[[Any], bytes]]] | None = ...,
 ) -> bytes: ...
+def packb_into(
+    obj: Any,
+    buffer: bytearray | memoryview,
+    /,
+    offset: int = 0,
+    *,
+    default: Callable[[Any], Any] | None = ...,
+    ext_types: dict[type, tuple[int, Callable[[Any], bytes]]] | None = ...,
+    option: int | None = None,
+) -> int: ...
+def unpack(
+    fp: IO[bytes],
+    /,
+    *,
+    ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+    option: int | None = ...,
+) -> Any: ...
+def unpack_frame(
+    obj: bytes | bytearray | memoryview,
+    /,
+    *,
+    checksum: bool = True,
+    ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+    option: int | None = ...,
+) -> Any: ...
+def unpack_frame_with_offset(
+    obj: bytes | bytearray | memoryview,
+    /,
+    start: int = 0,
+    *,
+    checksum: bool = True,
+    ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+    option: int | None = ...,
+) -> tuple[Any, int] | None: ...
 def unpackb(
     obj: bytes | bytearray | memoryview,
     /,
     *,
     ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
     option: int | None = ...,
 ) -> Any: ...
+def unpackb_path(
+    obj: bytes | bytearray | memoryview,
+    path: list[str | int] | tuple[str | int, ...],
+    /,
+    *,
+    ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+    option: int | None = ...,
+) -> Any: ...
+def unpackb_with_offset(
+    obj: bytes | bytearray | memoryview,
+    /,
+    start: int = 0,
+    *,
+    ext_hook: Callable[[int, bytes], Any] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+    object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+    map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+    max_array_len: int | None = ...,
+    max_bin_len: int | None = ...,
+    max_depth: int | None = ...,
+    max_elements: int | None = ...,
+    max_ext_len: int | None = ...,
+    max_map_len: int | None = ...,
+    max_str_len: int | None = ...,
+    raw: bool = ...,
+    type: Any = ...,
+    unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+    option: int | None = ...,
+) -> tuple[Any, int]: ...
+
+class MsgpackDecodeError(ValueError):
+    pos: int | None
+    path: list[Any] | None
 
-class MsgpackDecodeError(ValueError): ...
+class MsgpackDecodeLimitError(MsgpackDecodeError): ...
 class MsgpackEncodeError(TypeError): ...
 
+class Codec:
+    def __init__(
+        self,
+        *,
+        default: Callable[[Any], Any] | None = ...,
+        ext_types: dict[type, tuple[int, Callable[[Any], bytes]]] | None = ...,
+        ext_hook: Callable[[int, bytes], Any] | None = ...,
+        ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+        object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+        object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+        map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+        max_array_len: int | None = ...,
+        max_bin_len: int | None = ...,
+        max_depth: int | None = ...,
+        max_elements: int | None = ...,
+        max_ext_len: int | None = ...,
+        max_map_len: int | None = ...,
+        max_str_len: int | None = ...,
+        raw: bool = ...,
+        type: Any = ...,
+        unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+        pack_option: int | None = ...,
+        unpack_option: int | None = ...,
+    ) -> None: ...
+    def packb(self, obj: Any, /) -> bytes: ...
+    def unpackb(self, obj: bytes | bytearray | memoryview, /) -> Any: ...
+
 class Ext:
     def __init__(self, tag: int, data: bytes) -> None: ...
 
 class Fragment:
     def __init__(self, data: bytes) -> None: ...
 
+class Timestamp:
+    seconds: int
+    nanoseconds: int
+    def __init__(self, seconds: int, nanoseconds: int = 0, /) -> None: ...
+    def __lt__(self, other: Timestamp) -> bool: ...
+    def __le__(self, other: Timestamp) -> bool: ...
+    def __gt__(self, other: Timestamp) -> bool: ...
+    def __ge__(self, other: Timestamp) -> bool: ...
+
+class Unpacker(Iterator[Any]):
+    def __init__(
+        self,
+        *,
+        ext_hook: Callable[[int, bytes], Any] | None = ...,
+        ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+        object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+        object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+        map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+        max_array_len: int | None = ...,
+        max_bin_len: int | None = ...,
+        max_depth: int | None = ...,
+        max_elements: int | None = ...,
+        max_ext_len: int | None = ...,
+        max_map_len: int | None = ...,
+        max_str_len: int | None = ...,
+        raw: bool = ...,
+        type: Any = ...,
+        unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+        option: int | None = ...,
+    ) -> None: ...
+    def feed(self, data: bytes | bytearray | memoryview, /) -> Unpacker: ...
+    def __iter__(self) -> Unpacker: ...
+    def __next__(self) -> Any: ...
+
+class View:
+    def __init__(
+        self,
+        obj: bytes | bytearray | memoryview,
+        /,
+        *,
+        ext_hook: Callable[[int, bytes], Any] | None = ...,
+        ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+    ext_hooks: dict[int, Callable[[bytes], Any]] | None = ...,
+        object_hook: Callable[[dict[Any, Any]], Any] | None = ...,
+        object_pairs_hook: Callable[[list[tuple[Any, Any]]], Any] | None = ...,
+        map_type: Callable[[dict[Any, Any]], Any] | None = ...,
+        max_array_len: int | None = ...,
+        max_bin_len: int | None = ...,
+        max_depth: int | None = ...,
+        max_elements: int | None = ...,
+        max_ext_len: int | None = ...,
+        max_map_len: int | None = ...,
+        max_str_len: int | None = ...,
+        raw: bool = ...,
+        unicode_errors: Literal["strict", "replace", "surrogateescape"] | None = ...,
+        option: int | None = ...,
+    ) -> None: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, key: Any, /) -> Any: ...
+    def __iter__(self) -> Iterator[Any]: ...
+    def __contains__(self, value: object, /) -> bool: ...
+    def __bytes__(self) -> bytes: ...
+    def get(self, key: Any, default: Any = None, /) -> Any: ...
+    def items(self) -> list[tuple[Any, Any]]: ...
+    def keys(self) -> list[Any]: ...
+    def values(self) -> list[Any]: ...
+
+OPT_ARRAY_AS_NUMPY: int
+OPT_ARRAY_AS_TUPLE: int
+OPT_BIG_INT_AS_EXT: int
+OPT_BIN_AS_BYTEARRAY: int
+OPT_BIN_AS_MEMORYVIEW: int
+OPT_COMPLEX_AS_EXT: int
 OPT_DATETIME_AS_TIMESTAMP_EXT: int
+OPT_DECIMAL_AS_EXT: int
+OPT_FORBID_DUPLICATE_KEYS: int
+OPT_FORBID_TRAILING_DATA: int
 OPT_NAIVE_UTC: int
 OPT_OMIT_MICROSECONDS: int
 OPT_PASSTHROUGH_BIG_INT: int
 OPT_PASSTHROUGH_DATACLASS: int
 OPT_PASSTHROUGH_DATETIME: int
 OPT_PASSTHROUGH_ENUM: int
+OPT_PASSTHROUGH_NUMBER: int
 OPT_PASSTHROUGH_SUBCLASS: int
 OPT_PASSTHROUGH_TUPLE: int
 OPT_PASSTHROUGH_UUID: int
@@ -41,4 +322,8 @@ OPT_SERIALIZE_NUMPY: int
 OPT_SERIALIZE_PYDANTIC: int
 OPT_NON_STR_KEYS: int
 OPT_SORT_KEYS: int
+OPT_TIMEDELTA_AS_MICROSECONDS: int
+OPT_TIMEDELTA_AS_SECONDS: int
+OPT_TIMESTAMP_AS_INT: int
+OPT_TIMESTAMP_FALLBACK: int
 OPT_UTC_Z: int
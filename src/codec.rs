@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::deserialize::{deserialize, Config};
+use crate::ffi::*;
+use crate::opt::*;
+use crate::serialize::serialize_with_capacity;
+use crate::state::State;
+use crate::{
+    parse_config_kwarg, parse_ext_types_arg, parse_option_arg, raise_deserialize_exception,
+    raise_packb_exception, raise_unpackb_exception,
+};
+use pyo3::ffi::*;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_uint, c_void};
+use std::ptr::{null_mut, NonNull};
+
+const PACKB_DOC: &CStr = c"packb(obj, /)\n--\n\nSerialize Python objects to msgpack.";
+const UNPACKB_DOC: &CStr = c"unpackb(obj, /)\n--\n\nDeserialize msgpack to Python objects.";
+
+// The output is not initially allocated with more than this length, so that
+// a single large object does not make the following calls overallocate.
+const MAX_LENGTH_HINT: usize = 1048576;
+
+#[repr(C)]
+pub struct PyCodec {
+    pub ob_base: PyObject,
+    pub state: *mut State,
+    pub default: Option<NonNull<PyObject>>,
+    pub ext_types: Option<NonNull<PyObject>>,
+    pub config: Config,
+    pub pack_opts: Opt,
+    pub unpack_opts: Opt,
+    /// The length of the output of the last `packb` call, with which the
+    /// output of the next one is initially allocated.
+    pub length_hint: usize,
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_new(
+    subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut State = PyType_GetModuleState(subtype).cast();
+    if Py_SIZE(args) != 0 {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"Codec.__new__() accepts no positional arguments".as_ptr(),
+        );
+        return null_mut();
+    }
+    let mut default: Option<NonNull<PyObject>> = None;
+    let mut ext_types: Option<NonNull<PyObject>> = None;
+    let mut config = Config::default();
+    let mut pack_opts: Opt = 0;
+    let mut unpack_opts: Opt = 0;
+    if !kwds.is_null() {
+        for (key, value) in PyDictIter::from_pyobject(kwds) {
+            if PyUnicode_Compare(key.as_ptr(), (*state).default_str) == 0 {
+                if value.as_ptr() != Py_None() {
+                    default = Some(value);
+                }
+            } else if PyUnicode_Compare(key.as_ptr(), (*state).ext_types_str) == 0 {
+                match parse_ext_types_arg(value.as_ptr()) {
+                    Ok(val) => ext_types = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid ext_types"),
+                }
+            } else if PyUnicode_Compare(key.as_ptr(), (*state).pack_option_str) == 0 {
+                match parse_option_arg(value.as_ptr(), PACKB_OPT_MASK) {
+                    Ok(val) => pack_opts = val as Opt,
+                    Err(()) => return raise_packb_exception(state, "Invalid pack_option"),
+                }
+            } else if PyUnicode_Compare(key.as_ptr(), (*state).unpack_option_str) == 0 {
+                match parse_option_arg(value.as_ptr(), UNPACKB_OPT_MASK) {
+                    Ok(val) => unpack_opts = val as Opt,
+                    Err(()) => return raise_unpackb_exception(state, "Invalid unpack_option"),
+                }
+            } else {
+                match parse_config_kwarg(state, &mut config, key.as_ptr(), value.as_ptr()) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        PyErr_SetString(
+                            PyExc_TypeError,
+                            c"Codec.__new__() got an unexpected keyword argument".as_ptr(),
+                        );
+                        return null_mut();
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let obj = (*subtype).tp_alloc.unwrap()(subtype, 0);
+    if obj.is_null() {
+        return null_mut();
+    }
+    let codec = obj.cast::<PyCodec>();
+    for obj in [default, ext_types].into_iter().flatten() {
+        Py_INCREF(obj.as_ptr());
+    }
+    config.incref();
+    (*codec).state = state;
+    (*codec).default = default;
+    (*codec).ext_types = ext_types;
+    (*codec).config = config;
+    (*codec).pack_opts = pack_opts;
+    (*codec).unpack_opts = unpack_opts;
+    (*codec).length_hint = 0;
+    obj
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_traverse(
+    op: *mut PyObject,
+    visit: visitproc,
+    arg: *mut c_void,
+) -> c_int {
+    let codec = op.cast::<PyCodec>();
+    let ret = visit(ob_type!(op).cast::<PyObject>(), arg);
+    if ret != 0 {
+        return ret;
+    }
+    for obj in [(*codec).default, (*codec).ext_types].into_iter().flatten() {
+        let ret = visit(obj.as_ptr(), arg);
+        if ret != 0 {
+            return ret;
+        }
+    }
+    (*codec).config.traverse(visit, arg)
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_clear(op: *mut PyObject) -> c_int {
+    let codec = op.cast::<PyCodec>();
+    for obj in [(*codec).default.take(), (*codec).ext_types.take()]
+        .into_iter()
+        .flatten()
+    {
+        Py_DECREF(obj.as_ptr());
+    }
+    (*codec).config.clear();
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_dealloc(op: *mut PyObject) {
+    let tp = ob_type!(op);
+    PyObject_GC_UnTrack(op.cast::<c_void>());
+    codec_clear(op);
+    (*tp).tp_free.unwrap()(op.cast::<c_void>());
+    Py_DECREF(tp.cast::<PyObject>());
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_packb(op: *mut PyObject, obj: *mut PyObject) -> *mut PyObject {
+    let codec = op.cast::<PyCodec>();
+    let length_hint = {
+        let mut critical_section = CriticalSection::new();
+        critical_section.begin(op);
+        (*codec).length_hint
+    };
+    match serialize_with_capacity(
+        obj,
+        (*codec).state,
+        (*codec).default,
+        (*codec).ext_types,
+        (*codec).pack_opts,
+        length_hint.min(MAX_LENGTH_HINT),
+    ) {
+        Ok(val) => {
+            let mut critical_section = CriticalSection::new();
+            critical_section.begin(op);
+            (*codec).length_hint = Py_SIZE(val.as_ptr()) as usize;
+            val.as_ptr()
+        }
+        Err(err) => raise_packb_exception((*codec).state, &err),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn codec_unpackb(op: *mut PyObject, obj: *mut PyObject) -> *mut PyObject {
+    let codec = op.cast::<PyCodec>();
+    match deserialize(obj, (*codec).state, &(*codec).config, (*codec).unpack_opts) {
+        Ok(val) => val.as_ptr(),
+        Err(err) => raise_deserialize_exception((*codec).state, &err),
+    }
+}
+
+pub unsafe fn create_codec_type(module: *mut PyObject) -> *mut PyTypeObject {
+    let methods: Box<[PyMethodDef; 3]> = Box::new([
+        PyMethodDef {
+            ml_name: c"packb".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: codec_packb,
+            },
+            ml_flags: METH_O,
+            ml_doc: PACKB_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"unpackb".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: codec_unpackb,
+            },
+            ml_flags: METH_O,
+            ml_doc: UNPACKB_DOC.as_ptr(),
+        },
+        PyMethodDef::zeroed(),
+    ]);
+    let mut slots: [PyType_Slot; 6] = [
+        PyType_Slot {
+            slot: Py_tp_new,
+            pfunc: codec_new as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_dealloc,
+            pfunc: codec_dealloc as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_traverse,
+            pfunc: codec_traverse as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_clear,
+            pfunc: codec_clear as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_methods,
+            pfunc: Box::into_raw(methods).cast::<c_void>(),
+        },
+        PyType_Slot {
+            slot: 0,
+            pfunc: null_mut(),
+        },
+    ];
+    let mut spec = PyType_Spec {
+        name: c"ormsgpack.Codec".as_ptr(),
+        basicsize: std::mem::size_of::<PyCodec>() as c_int,
+        itemsize: 0,
+        flags: (Py_TPFLAGS_DEFAULT | Py_TPFLAGS_HAVE_GC) as c_uint,
+        slots: slots.as_mut_ptr(),
+    };
+    PyType_FromModuleAndSpec(module, &mut spec, null_mut()).cast::<PyTypeObject>()
+}
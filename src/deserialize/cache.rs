@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use crate::deserialize::UnicodeErrors;
 use crate::ffi::*;
 use ahash::RandomState;
 use simdutf8::basic::{from_utf8, Utf8Error};
@@ -9,17 +10,25 @@ use std::ptr::NonNull;
 #[cfg(Py_GIL_DISABLED)]
 use std::sync::Mutex;
 
-#[repr(transparent)]
+pub const DEFAULT_SIZE: usize = 512;
+pub const DEFAULT_MAX_KEY_LEN: usize = 64;
+pub const DEFAULT_ASSOCIATIVITY: usize = 1;
+pub const MAX_SIZE: usize = 1 << 20;
+
 struct CachedKey {
     ptr: *mut pyo3::ffi::PyObject,
+    hash: u64,
 }
 
 unsafe impl Send for CachedKey {}
 unsafe impl Sync for CachedKey {}
 
 impl CachedKey {
-    fn new(ptr: *mut pyo3::ffi::PyObject) -> CachedKey {
-        CachedKey { ptr: ptr }
+    fn new(ptr: *mut pyo3::ffi::PyObject, hash: u64) -> CachedKey {
+        CachedKey {
+            ptr: ptr,
+            hash: hash,
+        }
     }
 
     fn get(&mut self) -> *mut pyo3::ffi::PyObject {
@@ -34,20 +43,66 @@ impl Drop for CachedKey {
     }
 }
 
-pub struct KeyMap<const C: usize> {
+pub enum ConfigureError {
+    InvalidSize,
+    InvalidAssociativity,
+    SizeNotMultiple,
+}
+
+pub struct KeyMapInfo {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub size: usize,
+    pub max_key_len: usize,
+    pub associativity: usize,
+}
+
+struct Entries {
+    /// The cached keys, grouped in sets of `associativity` entries. The
+    /// occupied entries of a set come first, most recently used first.
+    slots: Vec<Option<CachedKey>>,
+    /// The number of sets, kept to map a hash to a set without a division.
+    sets: usize,
+    associativity: usize,
+    max_key_len: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl Entries {
+    fn new(size: usize, max_key_len: usize, associativity: usize) -> Self {
+        let mut slots = Vec::with_capacity(size);
+        for _ in 0..size {
+            slots.push(None);
+        }
+        Entries {
+            slots: slots,
+            sets: size / associativity,
+            associativity: associativity,
+            max_key_len: max_key_len,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+}
+
+/// A set-associative cache of the str objects of map keys. A key that is not
+/// cached replaces the least recently used key of its set.
+pub struct KeyMap {
     #[cfg(Py_GIL_DISABLED)]
-    entries: Mutex<Vec<Option<CachedKey>>>,
+    entries: Mutex<Entries>,
     #[cfg(not(Py_GIL_DISABLED))]
-    entries: Vec<Option<CachedKey>>,
+    entries: Entries,
     hash_builder: RandomState,
 }
 
-impl<const C: usize> KeyMap<C> {
+impl KeyMap {
     pub fn new() -> Self {
-        let mut entries = Vec::with_capacity(C);
-        for _ in 0..C {
-            entries.push(None);
-        }
+        let entries = Entries::new(DEFAULT_SIZE, DEFAULT_MAX_KEY_LEN, DEFAULT_ASSOCIATIVITY);
         KeyMap {
             #[cfg(Py_GIL_DISABLED)]
             entries: Mutex::new(entries),
@@ -57,29 +112,136 @@ impl<const C: usize> KeyMap<C> {
         }
     }
 
-    pub fn get(&mut self, key: &[u8]) -> Result<NonNull<pyo3::ffi::PyObject>, Utf8Error> {
+    /// Returns the str object for `key`. Keys that are longer than the
+    /// maximum key length are not cached. Keys that are not valid UTF-8 are
+    /// decoded with the error handler of `errors`, if any, and not cached.
+    pub fn get(
+        &mut self,
+        key: &[u8],
+        errors: UnicodeErrors,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Utf8Error> {
+        #[cfg(Py_GIL_DISABLED)]
+        let mut guard = self.entries.lock().unwrap();
+        #[cfg(Py_GIL_DISABLED)]
+        let entries = &mut *guard;
+        #[cfg(not(Py_GIL_DISABLED))]
+        let entries = &mut self.entries;
+        if unlikely!(key.len() > entries.max_key_len || entries.slots.is_empty()) {
+            return create_key(key, errors);
+        }
         let hash = {
             let mut hasher = self.hash_builder.build_hasher();
             hasher.write(key);
             hasher.finish()
-        } as usize;
-        let index = hash % C;
-        #[cfg(Py_GIL_DISABLED)]
-        let mut entries = self.entries.lock().unwrap();
-        #[cfg(not(Py_GIL_DISABLED))]
-        let entries = &mut self.entries;
-        let entry = match &mut entries[index] {
-            Some(v) if unicode_to_str(v.ptr).unwrap().as_bytes() == key => v,
-            _ => {
-                let pykey = unicode_from_str(from_utf8(key)?);
-                hash_str(pykey);
-                entries[index] = Some(CachedKey::new(pykey));
-                match &mut entries[index] {
-                    Some(v) => v,
-                    _ => unreachable!(),
+        };
+        let ways = entries.associativity;
+        // Scales the hash to the number of sets with a multiplication, which
+        // is much cheaper than the remainder of a division.
+        let start = ((u128::from(hash) * entries.sets as u128) >> 64) as usize * ways;
+        let set = &mut entries.slots[start..start + ways];
+        let mut found = None;
+        for (i, slot) in set.iter().enumerate() {
+            match slot {
+                Some(v) if v.hash == hash && unicode_to_str(v.ptr).unwrap().as_bytes() == key => {
+                    found = Some(i);
+                    break;
                 }
+                Some(_) => {}
+                None => break,
             }
+        }
+        if let Some(i) = found {
+            entries.hits += 1;
+            set[..=i].rotate_right(1);
+        } else {
+            let pykey = match from_utf8(key) {
+                Ok(value) => unicode_from_str(value),
+                Err(_) => return create_key(key, errors),
+            };
+            hash_str(pykey);
+            entries.misses += 1;
+            if set[ways - 1].is_some() {
+                entries.evictions += 1;
+            }
+            set.rotate_right(1);
+            set[0] = Some(CachedKey::new(pykey, hash));
+        }
+        match &mut set[0] {
+            Some(v) => unsafe { Ok(NonNull::new_unchecked(v.get())) },
+            None => unreachable!(),
+        }
+    }
+
+    /// Removes all the cached keys and resets the statistics.
+    pub fn clear(&mut self) {
+        let res = self.configure(None, None, None);
+        debug_assert!(res.is_ok());
+    }
+
+    /// Replaces the cache with an empty one of `size` entries, grouped in
+    /// sets of `associativity` entries. The settings that are `None` keep
+    /// their current value. `size` must be a multiple of `associativity`.
+    pub fn configure(
+        &mut self,
+        size: Option<usize>,
+        max_key_len: Option<usize>,
+        associativity: Option<usize>,
+    ) -> Result<(), ConfigureError> {
+        let old = {
+            #[cfg(Py_GIL_DISABLED)]
+            let mut guard = self.entries.lock().unwrap();
+            #[cfg(Py_GIL_DISABLED)]
+            let entries = &mut *guard;
+            #[cfg(not(Py_GIL_DISABLED))]
+            let entries = &mut self.entries;
+            let size = size.unwrap_or(entries.slots.len());
+            let max_key_len = max_key_len.unwrap_or(entries.max_key_len);
+            let associativity = associativity.unwrap_or(entries.associativity);
+            if size > MAX_SIZE {
+                return Err(ConfigureError::InvalidSize);
+            }
+            if associativity == 0 {
+                return Err(ConfigureError::InvalidAssociativity);
+            }
+            if size % associativity != 0 {
+                return Err(ConfigureError::SizeNotMultiple);
+            }
+            let new = Entries::new(size, max_key_len, associativity);
+            std::mem::replace(entries, new)
         };
-        unsafe { Ok(NonNull::new_unchecked(entry.get())) }
+        // The cached keys are released after the lock.
+        drop(old);
+        Ok(())
     }
+
+    pub fn info(&mut self) -> KeyMapInfo {
+        #[cfg(Py_GIL_DISABLED)]
+        let entries = self.entries.lock().unwrap();
+        #[cfg(not(Py_GIL_DISABLED))]
+        let entries = &self.entries;
+        KeyMapInfo {
+            hits: entries.hits,
+            misses: entries.misses,
+            evictions: entries.evictions,
+            entries: entries.slots.iter().filter(|slot| slot.is_some()).count(),
+            size: entries.slots.len(),
+            max_key_len: entries.max_key_len,
+            associativity: entries.associativity,
+        }
+    }
+}
+
+fn create_key(
+    key: &[u8],
+    errors: UnicodeErrors,
+) -> Result<NonNull<pyo3::ffi::PyObject>, Utf8Error> {
+    let pykey = match from_utf8(key) {
+        Ok(value) => unicode_from_str(value),
+        Err(err) => {
+            let handler = errors.handler().ok_or(err)?;
+            unicode_from_invalid_utf8(key, handler)
+        }
+    };
+    hash_str(pykey);
+    unsafe { Ok(NonNull::new_unchecked(pykey)) }
 }
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr::NonNull;
+
+/// The callables and settings that drive deserialization, besides the option
+/// flags.
+#[derive(Clone, Copy, Default)]
+pub struct Config {
+    pub ext_hook: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub ext_hooks: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub map_type: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub object_hook: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub object_pairs_hook: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub type_: Option<NonNull<pyo3::ffi::PyObject>>,
+    pub limits: Limits,
+    pub raw: bool,
+    pub unicode_errors: UnicodeErrors,
+}
+
+/// The handling of string objects that are not valid UTF-8.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum UnicodeErrors {
+    #[default]
+    Strict,
+    Replace,
+    SurrogateEscape,
+}
+
+impl UnicodeErrors {
+    /// Returns the name of the Python error handler, or `None` if invalid
+    /// strings are rejected.
+    pub fn handler(self) -> Option<&'static CStr> {
+        match self {
+            UnicodeErrors::Strict => None,
+            UnicodeErrors::Replace => Some(c"replace"),
+            UnicodeErrors::SurrogateEscape => Some(c"surrogateescape"),
+        }
+    }
+}
+
+/// Upper bounds on the size of the deserialized objects, for untrusted input.
+#[derive(Clone, Copy)]
+pub struct Limits {
+    pub max_array_len: u32,
+    pub max_bin_len: u32,
+    pub max_depth: u32,
+    pub max_elements: u64,
+    pub max_ext_len: u32,
+    pub max_map_len: u32,
+    pub max_str_len: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_array_len: u32::MAX,
+            max_bin_len: u32::MAX,
+            max_depth: u32::MAX,
+            max_elements: u64::MAX,
+            max_ext_len: u32::MAX,
+            max_map_len: u32::MAX,
+            max_str_len: u32::MAX,
+        }
+    }
+}
+
+impl Config {
+    /// Takes a strong reference to every object, for a config that outlives
+    /// the call that created it.
+    pub fn incref(&self) {
+        for obj in self.objects().into_iter().flatten() {
+            unsafe { pyo3::ffi::Py_INCREF(obj.as_ptr()) };
+        }
+    }
+
+    /// Calls `visit` on every object, for the `tp_traverse` slot of a
+    /// garbage-collected type holding the config.
+    pub unsafe fn traverse(&self, visit: pyo3::ffi::visitproc, arg: *mut c_void) -> c_int {
+        for obj in self.objects().into_iter().flatten() {
+            let ret = visit(obj.as_ptr(), arg);
+            if ret != 0 {
+                return ret;
+            }
+        }
+        0
+    }
+
+    /// Releases the references taken by `incref` and forgets the objects,
+    /// for the `tp_clear` slot of a garbage-collected type holding the
+    /// config.
+    pub fn clear(&mut self) {
+        let objects = self.objects();
+        self.ext_hook = None;
+        self.ext_hooks = None;
+        self.map_type = None;
+        self.object_hook = None;
+        self.object_pairs_hook = None;
+        self.type_ = None;
+        for obj in objects.into_iter().flatten() {
+            unsafe { pyo3::ffi::Py_DECREF(obj.as_ptr()) };
+        }
+    }
+
+    fn objects(&self) -> [Option<NonNull<pyo3::ffi::PyObject>>; 6] {
+        [
+            self.ext_hook,
+            self.ext_hooks,
+            self.map_type,
+            self.object_hook,
+            self.object_pairs_hook,
+            self.type_,
+        ]
+    }
+}
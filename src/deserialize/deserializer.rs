@@ -1,69 +1,385 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::deserialize::DeserializeError;
+use crate::deserialize::number::{complex_from_ext_data, decimal_from_ext_data};
+use crate::deserialize::numpy::{create_ndarray, Number, NumericArray, NumericView};
+use crate::deserialize::{typed, Config, DeserializeError, Scanner, StreamReader};
 use crate::exc::*;
 use crate::ffi::*;
-use crate::io::Read;
-use crate::msgpack::{read_timestamp, Marker};
+use crate::frame;
+use crate::io::{Read, ReadBuffer};
+use crate::msgpack::{
+    read_timestamp, Marker, PathItem, Validator, BIG_INT_EXT_TYPE, COMPLEX_EXT_TYPE,
+    DECIMAL_EXT_TYPE,
+};
 use crate::opt::*;
 use crate::state::State;
+use crate::timestamp::{timestamp_from_parts, NANOSECONDS_PER_SECOND};
 use chrono::{Datelike, Timelike};
 use simdutf8::basic::{from_utf8, Utf8Error};
 use std::borrow::Cow;
+use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::ptr::NonNull;
 
-const RECURSION_LIMIT: u16 = 1024;
+pub const RECURSION_LIMIT: u16 = 1024;
+
+/// Returns the number of nanoseconds since the epoch of a timestamp, as an
+/// `int`.
+fn timestamp_to_int(seconds: i64, nanoseconds: u32) -> NonNull<pyo3::ffi::PyObject> {
+    let value = i128::from(seconds) * i128::from(NANOSECONDS_PER_SECOND) + i128::from(nanoseconds);
+    unsafe {
+        let obj = match i64::try_from(value) {
+            Ok(value) => pyo3::ffi::PyLong_FromLongLong(value),
+            Err(_) => {
+                let seconds = pyo3::ffi::PyLong_FromLongLong(seconds);
+                let factor = pyo3::ffi::PyLong_FromLong(NANOSECONDS_PER_SECOND.into());
+                let product = pyo3::ffi::PyNumber_Multiply(seconds, factor);
+                let nanoseconds = pyo3::ffi::PyLong_FromLong(nanoseconds.into());
+                let obj = pyo3::ffi::PyNumber_Add(product, nanoseconds);
+                pyo3::ffi::Py_DECREF(seconds);
+                pyo3::ffi::Py_DECREF(factor);
+                pyo3::ffi::Py_DECREF(product);
+                pyo3::ffi::Py_DECREF(nanoseconds);
+                obj
+            }
+        };
+        NonNull::new_unchecked(obj)
+    }
+}
+
+pub fn input_as_bytes(
+    ptr: *mut pyo3::ffi::PyObject,
+) -> Result<&'static [u8], DeserializeError<'static>> {
+    let obj_type_ptr = ob_type!(ptr);
+    if obj_type_ptr == &raw mut pyo3::ffi::PyBytes_Type {
+        Ok(unsafe { pybytes_as_bytes(ptr) })
+    } else if obj_type_ptr == &raw mut pyo3::ffi::PyMemoryView_Type {
+        match unsafe { pymemoryview_as_bytes(ptr) } {
+            Some(buffer) => Ok(buffer),
+            None => Err(DeserializeError::new(Cow::Borrowed(
+                "Input type memoryview must be a C contiguous buffer",
+            ))),
+        }
+    } else if obj_type_ptr == &raw mut pyo3::ffi::PyByteArray_Type {
+        Ok(unsafe { pybytearray_as_bytes(ptr) })
+    } else {
+        Err(DeserializeError::new(Cow::Borrowed(
+            "Input must be bytes, bytearray, memoryview",
+        )))
+    }
+}
 
 pub fn deserialize(
     ptr: *mut pyo3::ffi::PyObject,
     state: *mut State,
-    ext_hook: Option<NonNull<pyo3::ffi::PyObject>>,
+    config: &Config,
     opts: Opt,
 ) -> Result<NonNull<pyo3::ffi::PyObject>, DeserializeError<'static>> {
-    let obj_type_ptr = ob_type!(ptr);
-    let contents: &[u8];
+    let contents = input_as_bytes(ptr)?;
+    let source = Source::new(ptr, contents);
+    let (value, _) = deserialize_slice(contents, source, state, config, opts)?;
+    Ok(value)
+}
 
-    if obj_type_ptr == &raw mut pyo3::ffi::PyBytes_Type {
-        contents = unsafe { pybytes_as_bytes(ptr) };
-    } else if obj_type_ptr == &raw mut pyo3::ffi::PyMemoryView_Type {
-        if let Some(buffer) = unsafe { pymemoryview_as_bytes(ptr) } {
-            contents = buffer;
+/// Deserializes the object starting at `start` and returns it along with the
+/// offset of the first byte after it.
+pub fn deserialize_with_offset(
+    ptr: *mut pyo3::ffi::PyObject,
+    start: usize,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<(NonNull<pyo3::ffi::PyObject>, usize), DeserializeError<'static>> {
+    let contents = input_as_bytes(ptr)?;
+    let data = match contents.get(start..) {
+        Some(data) => data,
+        None => return Err(DeserializeError::new(Cow::Borrowed("Invalid start"))),
+    };
+    let source = Source::new(ptr, contents);
+    let (value, remaining) = deserialize_slice(data, source, state, config, opts)?;
+    Ok((value, contents.len() - remaining))
+}
+
+/// Deserializes the object addressed by `path`, a list or tuple of map keys
+/// and array indices, skipping over the values that precede it.
+pub fn deserialize_path(
+    ptr: *mut pyo3::ffi::PyObject,
+    path: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<NonNull<pyo3::ffi::PyObject>, DeserializeError<'static>> {
+    let contents = input_as_bytes(ptr)?;
+    let ob_type = ob_type!(path);
+    if ob_type != &raw mut pyo3::ffi::PyList_Type && ob_type != &raw mut pyo3::ffi::PyTuple_Type {
+        return Err(DeserializeError::new(Cow::Borrowed(
+            "Path must be a list or tuple",
+        )));
+    }
+    let items = unsafe { pyo3::ffi::PySequence_Tuple(path) };
+    let res = seek_path(contents, items);
+    unsafe { pyo3::ffi::Py_DECREF(items) };
+    let data = res?;
+    let source = Source::new(ptr, contents);
+    let (value, _) = deserialize_slice(data, source, state, config, opts)?;
+    Ok(value)
+}
+
+/// Returns the data starting at the object addressed by `items`, a tuple.
+fn seek_path(
+    contents: &'static [u8],
+    items: *mut pyo3::ffi::PyObject,
+) -> Result<&'static [u8], DeserializeError<'static>> {
+    let mut validator = Validator::new(contents);
+    for i in 0..unsafe { pyo3::ffi::Py_SIZE(items) } {
+        let item = unsafe { pytuple_get_item(items, i) };
+        let ob_type = ob_type!(item);
+        let item = if ob_type == &raw mut pyo3::ffi::PyUnicode_Type {
+            match unicode_to_str(item) {
+                Ok(key) => PathItem::Key(key.as_bytes()),
+                Err(err) => return Err(DeserializeError::new(Cow::Owned(err.to_string()))),
+            }
+        } else if ob_type == &raw mut pyo3::ffi::PyLong_Type {
+            let index = unsafe { pyo3::ffi::PyLong_AsLongLong(item) };
+            if index == -1 && unsafe { !pyo3::ffi::PyErr_Occurred().is_null() } {
+                unsafe { pyo3::ffi::PyErr_Clear() };
+                return Err(DeserializeError::new(Cow::Borrowed(
+                    "Path item out of range",
+                )));
+            }
+            PathItem::Int(index)
         } else {
             return Err(DeserializeError::new(Cow::Borrowed(
-                "Input type memoryview must be a C contiguous buffer",
+                "Path items must be str or int",
             )));
+        };
+        match validator.seek(&item) {
+            Ok(true) => {}
+            Ok(false) => {
+                let mut message = String::from("Path not found: $");
+                for j in 0..=i {
+                    message.push('[');
+                    message.push_str(&unsafe { typed::object_repr(pytuple_get_item(items, j)) });
+                    message.push(']');
+                }
+                return Err(DeserializeError::new(Cow::Owned(message)));
+            }
+            Err(err) => return Err(DeserializeError::new(Cow::Owned(err.to_string()))),
+        }
+    }
+    Ok(validator.into_inner())
+}
+
+/// Deserializes the object starting at `start` in `contents`, the contents
+/// of `ptr`. If `key` is true, the object is deserialized as a map key.
+pub fn deserialize_at(
+    ptr: *mut pyo3::ffi::PyObject,
+    contents: &[u8],
+    start: usize,
+    key: bool,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<NonNull<pyo3::ffi::PyObject>, DeserializeError<'static>> {
+    let mut deserializer = Deserializer::new(&contents[start..], state, config, opts);
+    deserializer.origin = contents.as_ptr() as usize;
+    deserializer.source = Some(Source::new(ptr, contents));
+    if key {
+        if opts & NON_STR_KEYS != 0 {
+            deserializer.deserialize_map_key()
+        } else {
+            deserializer.deserialize_str_map_key()
         }
-    } else if obj_type_ptr == &raw mut pyo3::ffi::PyByteArray_Type {
-        contents = unsafe { pybytearray_as_bytes(ptr) };
     } else {
+        deserializer.deserialize_root()
+    }
+    .map_err(|err| deserializer.error(err))
+}
+
+/// Deserializes the payload of a frame, after checking its length and,
+/// optionally, its checksum.
+pub fn deserialize_frame(
+    ptr: *mut pyo3::ffi::PyObject,
+    checksum: bool,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<NonNull<pyo3::ffi::PyObject>, DeserializeError<'static>> {
+    let contents = input_as_bytes(ptr)?;
+    if frame_length(contents, checksum) != Some(contents.len()) {
+        return Err(DeserializeError::new(Cow::Borrowed("Invalid frame length")));
+    }
+    deserialize_frame_payload(ptr, contents, contents, checksum, state, config, opts)
+}
+
+/// Deserializes the payload of the frame that starts at `start` in the
+/// contents of `ptr`, and returns it along with the offset of the end of the
+/// frame. `None` is returned if the contents end before the frame.
+pub fn deserialize_frame_with_offset(
+    ptr: *mut pyo3::ffi::PyObject,
+    start: usize,
+    checksum: bool,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<Option<(NonNull<pyo3::ffi::PyObject>, usize)>, DeserializeError<'static>> {
+    let contents = input_as_bytes(ptr)?;
+    let data = match contents.get(start..) {
+        Some(data) => data,
+        None => return Err(DeserializeError::new(Cow::Borrowed("Invalid start"))),
+    };
+    let frame = match frame_length(data, checksum).and_then(|len| data.get(..len)) {
+        Some(frame) => frame,
+        None => return Ok(None),
+    };
+    let value = deserialize_frame_payload(ptr, contents, frame, checksum, state, config, opts)?;
+    Ok(Some((value, start + frame.len())))
+}
+
+/// Returns the length of the frame at the start of `data`, as given by its
+/// header, or `None` if `data` is shorter than the header.
+fn frame_length(data: &[u8], checksum: bool) -> Option<usize> {
+    let header = data.first_chunk::<{ frame::HEADER_LENGTH }>()?;
+    let trailer_len = if checksum { frame::CHECKSUM_LENGTH } else { 0 };
+    Some(frame::HEADER_LENGTH + u32::from_be_bytes(*header) as usize + trailer_len)
+}
+
+/// Deserializes the payload of `frame`, a complete frame in `contents`, the
+/// contents of `ptr`, after checking its checksum.
+fn deserialize_frame_payload(
+    ptr: *mut pyo3::ffi::PyObject,
+    contents: &[u8],
+    frame: &[u8],
+    checksum: bool,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<NonNull<pyo3::ffi::PyObject>, DeserializeError<'static>> {
+    let trailer_len = if checksum { frame::CHECKSUM_LENGTH } else { 0 };
+    let payload = &frame[frame::HEADER_LENGTH..frame.len() - trailer_len];
+    let trailer = &frame[frame.len() - trailer_len..];
+    if checksum && frame::crc32(payload).to_be_bytes() != trailer {
         return Err(DeserializeError::new(Cow::Borrowed(
-            "Input must be bytes, bytearray, memoryview",
+            "Invalid frame checksum",
         )));
     }
+    let source = Source::new(ptr, contents);
+    let (value, _) =
+        deserialize_slice(payload, source, state, config, opts | FORBID_TRAILING_DATA)?;
+    Ok(value)
+}
 
-    let mut deserializer = Deserializer::new(contents, state, ext_hook, opts);
+fn deserialize_slice(
+    data: &[u8],
+    source: Source,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<(NonNull<pyo3::ffi::PyObject>, usize), DeserializeError<'static>> {
+    let mut deserializer = Deserializer::new(data, state, config, opts);
+    deserializer.origin = source.data as usize;
+    deserializer.source = Some(source);
     deserializer
-        .deserialize()
-        .map_err(|e| DeserializeError::new(Cow::Owned(e.to_string())))
+        .deserialize_root()
+        .and_then(|value| {
+            if opts & FORBID_TRAILING_DATA != 0 && !deserializer.data.eof() {
+                unsafe { pyo3::ffi::Py_DECREF(value.as_ptr()) };
+                return Err(Error::TrailingData);
+            }
+            Ok((value, deserializer.data.len()))
+        })
+        .map_err(|err| deserializer.error(err))
+}
+
+/// Deserializes the next object in `buffer`. If the buffer does not hold a
+/// complete object, the buffer position is left unchanged and `None` is
+/// returned. `scanner` keeps the progress of the completeness check between
+/// calls.
+pub fn deserialize_from_buffer(
+    buffer: &mut ReadBuffer,
+    scanner: &mut Scanner,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<Option<NonNull<pyo3::ffi::PyObject>>, DeserializeError<'static>> {
+    if !scanner.scan(buffer.remaining(), &config.limits) {
+        return Ok(None);
+    }
+    scanner.reset();
+    let start = buffer.position();
+    let mut deserializer = Deserializer::new(&mut *buffer, state, config, opts);
+    match deserializer.deserialize_root() {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::UnexpectedEof) => {
+            drop(deserializer);
+            buffer.seek(start);
+            Ok(None)
+        }
+        Err(err) => Err(deserializer.error(err)),
+    }
+}
+
+/// Deserializes the next object from a binary file object. `Err(None)` is
+/// returned if `fp.readinto` raised an exception, which is left set.
+pub fn deserialize_from_stream(
+    reader: &mut StreamReader,
+    state: *mut State,
+    config: &Config,
+    opts: Opt,
+) -> Result<NonNull<pyo3::ffi::PyObject>, Option<DeserializeError<'static>>> {
+    let mut deserializer = Deserializer::new(&mut *reader, state, config, opts);
+    let res = deserializer
+        .deserialize_root()
+        .and_then(|value| {
+            if opts & FORBID_TRAILING_DATA != 0 && !deserializer.data.at_end()? {
+                unsafe { pyo3::ffi::Py_DECREF(value.as_ptr()) };
+                return Err(Error::TrailingData);
+            }
+            Ok(value)
+        })
+        .map_err(|err| deserializer.error(err));
+    drop(deserializer);
+    if reader.failed() {
+        return Err(None);
+    }
+    res.map_err(Some)
+}
+
+/// An element of an array that may be deserialized as a numpy array.
+enum Numeric {
+    Number(Number),
+    Array(NumericArray),
+    Object(NonNull<pyo3::ffi::PyObject>),
 }
 
 #[derive(Debug)]
 enum Error {
+    Conversion(String),
+    DuplicateKey(String),
     ExtHookFailed,
     ExtHookMissing,
     Internal,
     InvalidStr,
     InvalidType(Marker),
     InvalidValue,
+    LimitExceeded(&'static str),
+    MapTypeFailed,
+    NumpyFailed,
+    NumpyMissing,
+    ObjectHookFailed,
+    ObjectPairsHookFailed,
     RecursionLimitReached,
+    TrailingData,
     UnexpectedEof,
+    UnhashableKey(String),
 }
 
 impl std::fmt::Display for Error {
     #[cold]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
+            Error::Conversion(ref message) => f.write_str(message),
+            Error::DuplicateKey(ref key) => write!(f, "duplicate map key {key}"),
             Error::ExtHookFailed => f.write_str("ext_hook failed"),
             Error::ExtHookMissing => f.write_str("ext_hook missing"),
             Error::Internal => f.write_str("internal error"),
@@ -72,8 +388,29 @@ impl std::fmt::Display for Error {
                 write!(f, "invalid type {marker:?}")
             }
             Error::InvalidValue => f.write_str("invalid value"),
+            Error::LimitExceeded(limit) => write!(f, "{limit} exceeded"),
+            Error::MapTypeFailed => f.write_str("map_type failed"),
+            Error::NumpyFailed => f.write_str("numpy array creation failed"),
+            Error::NumpyMissing => f.write_str("OPT_ARRAY_AS_NUMPY requires numpy"),
+            Error::ObjectHookFailed => f.write_str("object_hook failed"),
+            Error::ObjectPairsHookFailed => f.write_str("object_pairs_hook failed"),
             Error::RecursionLimitReached => f.write_str(RECURSION_LIMIT_REACHED),
+            Error::TrailingData => f.write_str("trailing data"),
             Error::UnexpectedEof => write!(f, "unexpected end of file"),
+            Error::UnhashableKey(ref type_name) => {
+                write!(f, "unhashable map key of type {type_name}")
+            }
+        }
+    }
+}
+
+impl From<Error> for DeserializeError<'static> {
+    #[cold]
+    fn from(value: Error) -> DeserializeError<'static> {
+        let message = Cow::Owned(value.to_string());
+        match value {
+            Error::LimitExceeded(_) => DeserializeError::limit_exceeded(message),
+            _ => DeserializeError::new(message),
         }
     }
 }
@@ -95,33 +432,196 @@ impl From<Utf8Error> for Error {
     }
 }
 
+/// The Python object that holds the input, of which bin objects are
+/// deserialized as slices with `BIN_AS_MEMORYVIEW`.
+struct Source {
+    obj: *mut pyo3::ffi::PyObject,
+    data: *const u8,
+    view: *mut pyo3::ffi::PyObject,
+}
+
+impl Source {
+    fn new(obj: *mut pyo3::ffi::PyObject, contents: &[u8]) -> Self {
+        Source {
+            obj: obj,
+            data: contents.as_ptr(),
+            view: std::ptr::null_mut(),
+        }
+    }
+
+    /// Returns a read-only, one-dimensional memoryview of bytes over the
+    /// object, created on first use.
+    fn view(&mut self, state: *mut State) -> Result<*mut pyo3::ffi::PyObject, Error> {
+        if self.view.is_null() {
+            unsafe {
+                let mut view = pyo3::ffi::PyMemoryView_FromObject(self.obj);
+                if !view.is_null() && ob_type!(self.obj) == &raw mut pyo3::ffi::PyMemoryView_Type {
+                    let cast = pyobject_call_method_one_arg(
+                        view,
+                        (*state).cast_str,
+                        (*state).uint8_format_str,
+                    );
+                    pyo3::ffi::Py_DECREF(view);
+                    view = cast;
+                }
+                if view.is_null() {
+                    return Err(Error::Internal);
+                }
+                self.view = pyobject_call_method_no_args(view, (*state).toreadonly_str);
+                pyo3::ffi::Py_DECREF(view);
+                if self.view.is_null() {
+                    return Err(Error::Internal);
+                }
+            }
+        }
+        Ok(self.view)
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        if !self.view.is_null() {
+            unsafe { pyo3::ffi::Py_DECREF(self.view) };
+        }
+    }
+}
+
 struct Deserializer<R> {
     data: R,
+    source: Option<Source>,
+    origin: usize,
     state: *mut State,
-    ext_hook: Option<NonNull<pyo3::ffi::PyObject>>,
+    config: Config,
     opts: Opt,
     recursion: u16,
+    elements: u64,
+    /// The offset of the innermost object that could not be deserialized.
+    error_offset: Option<usize>,
+    /// The map keys and array indices that lead to the object that could not
+    /// be deserialized, innermost first.
+    error_path: Vec<NonNull<pyo3::ffi::PyObject>>,
+}
+
+impl<R> Drop for Deserializer<R> {
+    fn drop(&mut self) {
+        for item in self.error_path.drain(..) {
+            unsafe { pyo3::ffi::Py_DECREF(item.as_ptr()) };
+        }
+    }
 }
 
 impl<R> Deserializer<R>
 where
     R: Read,
 {
-    fn new(
-        data: R,
-        state: *mut State,
-        ext_hook: Option<NonNull<pyo3::ffi::PyObject>>,
-        opts: Opt,
-    ) -> Self {
+    fn new(data: R, state: *mut State, config: &Config, opts: Opt) -> Self {
         Deserializer {
+            origin: data.position(),
             data: data,
+            source: None,
             state: state,
-            ext_hook: ext_hook,
+            config: *config,
             opts: opts,
             recursion: 0,
+            elements: 0,
+            error_offset: None,
+            error_path: Vec::new(),
+        }
+    }
+
+    /// Returns the offset of the next byte to read, from the start of the
+    /// input if it is known and from the start of the object otherwise.
+    #[inline(always)]
+    fn offset(&self) -> usize {
+        self.data.position() - self.origin
+    }
+
+    /// Records `offset` as the offset of the object that could not be
+    /// deserialized, unless an object nested in it already failed.
+    #[cold]
+    fn set_error_offset(&mut self, offset: usize) {
+        if self.error_offset.is_none() {
+            self.error_offset = Some(offset);
+        }
+    }
+
+    /// Adds an array index to the path of the object that could not be
+    /// deserialized.
+    #[cold]
+    fn push_error_index(&mut self, index: u32) {
+        unsafe {
+            let obj = pyo3::ffi::PyLong_FromUnsignedLong(index.into());
+            self.error_path.push(NonNull::new_unchecked(obj));
+        }
+    }
+
+    /// Converts `err` to a `DeserializeError` holding the offset and the path
+    /// of the object that could not be deserialized.
+    #[cold]
+    #[inline(never)]
+    fn error(&mut self, err: Error) -> DeserializeError<'static> {
+        if let Error::NumpyMissing = err {
+            return DeserializeError::from(err);
+        }
+        let position = self.error_offset.take().unwrap_or_else(|| self.offset());
+        let mut res = DeserializeError::from(err);
+        res.locate(position, self.error_path.drain(..).rev());
+        res
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn duplicate_key(&mut self, key: NonNull<pyo3::ffi::PyObject>, offset: usize) -> Error {
+        self.error_offset = Some(offset);
+        unsafe {
+            let repr = pyo3::ffi::PyObject_Repr(key.as_ptr());
+            if repr.is_null() {
+                pyo3::ffi::PyErr_Clear();
+                return Error::Internal;
+            }
+            let key = unicode_to_str(repr).unwrap_or("?").to_string();
+            pyo3::ffi::Py_DECREF(repr);
+            Error::DuplicateKey(key)
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn unhashable_key(&mut self, key: NonNull<pyo3::ffi::PyObject>, offset: usize) -> Error {
+        self.error_offset = Some(offset);
+        let type_name = unsafe { CStr::from_ptr((*ob_type!(key.as_ptr())).tp_name) };
+        Error::UnhashableKey(type_name.to_string_lossy().into_owned())
+    }
+
+    #[inline(always)]
+    fn check_len(&self, len: u32, max_len: u32, limit: &'static str) -> Result<(), Error> {
+        if unlikely!(len > max_len) {
+            Err(Error::LimitExceeded(limit))
+        } else {
+            Ok(())
         }
     }
 
+    /// Checks the limits that apply to an array or map of `len` elements,
+    /// before any of them is deserialized.
+    fn enter_container(
+        &mut self,
+        len: u32,
+        max_len: u32,
+        limit: &'static str,
+    ) -> Result<(), Error> {
+        let limits = &self.config.limits;
+        self.check_len(len, max_len, limit)?;
+        if unlikely!(u32::from(self.recursion) > limits.max_depth) {
+            return Err(Error::LimitExceeded("max_depth"));
+        }
+        self.elements += u64::from(len);
+        if unlikely!(self.elements > limits.max_elements) {
+            return Err(Error::LimitExceeded("max_elements"));
+        }
+        Ok(())
+    }
+
     #[inline(always)]
     fn read_marker(&mut self) -> Result<Marker, Error> {
         let n = self.data.read_u8()?;
@@ -132,11 +632,21 @@ where
         &mut self,
         len: u32,
     ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
-        let datetime = read_timestamp(&mut self.data, len)?;
-        unsafe {
-            let obj = {
+        let (seconds, nanoseconds) = read_timestamp(&mut self.data, len)?;
+        if self.opts & TIMESTAMP_AS_INT != 0 {
+            return Ok(timestamp_to_int(seconds, nanoseconds));
+        }
+        let datetime = chrono::DateTime::<chrono::Utc>::from_timestamp(seconds, nanoseconds)
+            .filter(|datetime| (1..=9999).contains(&datetime.year()));
+        match datetime {
+            Some(datetime) => unsafe {
                 let datetime_api = *pyo3::ffi::PyDateTimeAPI();
-                (datetime_api.DateTime_FromDateAndTime)(
+                let tzinfo = if self.opts & NAIVE_UTC != 0 {
+                    pyo3::ffi::Py_None()
+                } else {
+                    datetime_api.TimeZone_UTC
+                };
+                let obj = (datetime_api.DateTime_FromDateAndTime)(
                     datetime.year(),
                     datetime.month() as i32,
                     datetime.day() as i32,
@@ -144,43 +654,88 @@ where
                     datetime.minute() as i32,
                     datetime.second() as i32,
                     (datetime.nanosecond() / 1000) as i32,
-                    datetime_api.TimeZone_UTC,
+                    tzinfo,
                     datetime_api.DateTimeType,
-                )
-            };
-            Ok(NonNull::new_unchecked(obj))
+                );
+                Ok(NonNull::new_unchecked(obj))
+            },
+            None if self.opts & TIMESTAMP_FALLBACK != 0 => unsafe {
+                let obj = timestamp_from_parts((*self.state).timestamp_type, seconds, nanoseconds);
+                Ok(NonNull::new_unchecked(obj))
+            },
+            None => Err(Error::InvalidValue),
         }
     }
 
     fn deserialize_ext(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.check_len(len, self.config.limits.max_ext_len, "max_ext_len")?;
         let tag = self.data.read_i8()?;
-        if tag == -1 && self.opts & DATETIME_AS_TIMESTAMP_EXT != 0 {
+        // An entry of ext_hooks takes precedence over the built-in types.
+        let hook = match self.config.ext_hooks {
+            Some(hooks) => unsafe {
+                let tag_obj = pyo3::ffi::PyLong_FromLongLong(tag as i64);
+                let hook = pyo3::ffi::PyDict_GetItem(hooks.as_ptr(), tag_obj);
+                pyo3::ffi::Py_DECREF(tag_obj);
+                hook
+            },
+            None => std::ptr::null_mut(),
+        };
+        if !hook.is_null() {
+            let data = self.data.read_slice(len as usize)?;
+            unsafe {
+                let data_obj = pyo3::ffi::PyBytes_FromStringAndSize(
+                    data.as_ptr().cast::<c_char>(),
+                    data.len() as pyo3::ffi::Py_ssize_t,
+                );
+                // The hook may remove itself from ext_hooks.
+                pyo3::ffi::Py_INCREF(hook);
+                let obj = pyobject_call_one_arg(hook, data_obj);
+                pyo3::ffi::Py_DECREF(hook);
+                pyo3::ffi::Py_DECREF(data_obj);
+                return NonNull::new(obj).ok_or(Error::ExtHookFailed);
+            }
+        }
+        if tag == -1 && self.opts & (DATETIME_AS_TIMESTAMP_EXT | TIMESTAMP_AS_INT) != 0 {
             return self.deserialize_timestamp_ext(len);
         }
+        if tag == BIG_INT_EXT_TYPE && self.opts & BIG_INT_AS_EXT != 0 {
+            let data = self.data.read_slice(len as usize)?;
+            return NonNull::new(pylong_from_be_bytes(data)).ok_or(Error::Internal);
+        }
+        if tag == DECIMAL_EXT_TYPE && self.opts & DECIMAL_AS_EXT != 0 {
+            let data = self.data.read_slice(len as usize)?;
+            let decimal_type = unsafe { (*self.state).get_decimal_type() };
+            return decimal_from_ext_data(decimal_type, data).ok_or(Error::InvalidValue);
+        }
+        if tag == COMPLEX_EXT_TYPE && self.opts & COMPLEX_AS_EXT != 0 {
+            let data = self.data.read_slice(len as usize)?;
+            return complex_from_ext_data(data).ok_or(Error::InvalidValue);
+        }
 
         let data = self.data.read_slice(len as usize)?;
 
-        match self.ext_hook {
-            Some(callable) => unsafe {
-                let tag_obj = pyo3::ffi::PyLong_FromLongLong(tag as i64);
-                let data_ptr = data.as_ptr().cast::<c_char>();
-                let data_len = data.len() as pyo3::ffi::Py_ssize_t;
-                let data_obj = pyo3::ffi::PyBytes_FromStringAndSize(data_ptr, data_len);
-                let obj = pyo3::ffi::PyObject_CallFunctionObjArgs(
-                    callable.as_ptr(),
-                    tag_obj,
-                    data_obj,
-                    std::ptr::null_mut::<pyo3::ffi::PyObject>(),
-                );
-                pyo3::ffi::Py_DECREF(tag_obj);
-                pyo3::ffi::Py_DECREF(data_obj);
-                if unlikely!(obj.is_null()) {
-                    Err(Error::ExtHookFailed)
-                } else {
-                    Ok(NonNull::new_unchecked(obj))
-                }
-            },
-            None => Err(Error::ExtHookMissing),
+        unsafe {
+            let Some(callable) = self.config.ext_hook else {
+                return Err(Error::ExtHookMissing);
+            };
+            let tag_obj = pyo3::ffi::PyLong_FromLongLong(tag as i64);
+            let data_obj = pyo3::ffi::PyBytes_FromStringAndSize(
+                data.as_ptr().cast::<c_char>(),
+                data.len() as pyo3::ffi::Py_ssize_t,
+            );
+            let obj = pyo3::ffi::PyObject_CallFunctionObjArgs(
+                callable.as_ptr(),
+                tag_obj,
+                data_obj,
+                std::ptr::null_mut::<pyo3::ffi::PyObject>(),
+            );
+            pyo3::ffi::Py_DECREF(data_obj);
+            pyo3::ffi::Py_DECREF(tag_obj);
+            if unlikely!(obj.is_null()) {
+                Err(Error::ExtHookFailed)
+            } else {
+                Ok(NonNull::new_unchecked(obj))
+            }
         }
     }
 
@@ -230,13 +785,56 @@ where
     }
 
     fn deserialize_str(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.check_len(len, self.config.limits.max_str_len, "max_str_len")?;
         let data = self.data.read_slice(len as usize)?;
-        let value = from_utf8(data)?;
-        let ptr = unicode_from_str(value);
+        let ptr = if self.config.raw {
+            let len = data.len() as pyo3::ffi::Py_ssize_t;
+            unsafe { pyo3::ffi::PyBytes_FromStringAndSize(data.as_ptr().cast::<c_char>(), len) }
+        } else {
+            match from_utf8(data) {
+                Ok(value) => unicode_from_str(value),
+                Err(err) => match self.config.unicode_errors.handler() {
+                    Some(handler) => unicode_from_invalid_utf8(data, handler),
+                    None => return Err(Error::from(err)),
+                },
+            }
+        };
         unsafe { Ok(NonNull::new_unchecked(ptr)) }
     }
 
     fn deserialize_bin(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.check_len(len, self.config.limits.max_bin_len, "max_bin_len")?;
+        let v = self.data.read_slice(len as usize)?;
+        let ptr = v.as_ptr();
+        let len = v.len() as pyo3::ffi::Py_ssize_t;
+        unsafe {
+            let obj = if self.opts & BIN_AS_MEMORYVIEW != 0 {
+                match self.source {
+                    Some(ref mut source) => {
+                        let start = ptr.offset_from(source.data) as pyo3::ffi::Py_ssize_t;
+                        pyo3::ffi::PySequence_GetSlice(source.view(self.state)?, start, start + len)
+                    }
+                    None => {
+                        let bytes = pyo3::ffi::PyBytes_FromStringAndSize(ptr.cast::<c_char>(), len);
+                        let view = pyo3::ffi::PyMemoryView_FromObject(bytes);
+                        pyo3::ffi::Py_DECREF(bytes);
+                        view
+                    }
+                }
+            } else if self.opts & BIN_AS_BYTEARRAY != 0 {
+                pyo3::ffi::PyByteArray_FromStringAndSize(ptr.cast::<c_char>(), len)
+            } else {
+                pyo3::ffi::PyBytes_FromStringAndSize(ptr.cast::<c_char>(), len)
+            };
+            if unlikely!(obj.is_null()) {
+                return Err(Error::Internal);
+            }
+            Ok(NonNull::new_unchecked(obj))
+        }
+    }
+
+    fn deserialize_map_bin_key(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.check_len(len, self.config.limits.max_bin_len, "max_bin_len")?;
         let v = self.data.read_slice(len as usize)?;
         let ptr = v.as_ptr().cast::<c_char>();
         let len = v.len() as pyo3::ffi::Py_ssize_t;
@@ -246,41 +844,285 @@ where
         }
     }
 
+    fn deserialize_tuple(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let ptr = unsafe { pyo3::ffi::PyTuple_New(len as pyo3::ffi::Py_ssize_t) };
+        for i in 0..len {
+            let elem = self.deserialize().inspect_err(|_| unsafe {
+                self.push_error_index(i);
+                pyo3::ffi::Py_DECREF(ptr);
+            })?;
+            unsafe { pytuple_set_item(ptr, i as pyo3::ffi::Py_ssize_t, elem.as_ptr()) };
+        }
+        unsafe { Ok(NonNull::new_unchecked(ptr)) }
+    }
+
     fn deserialize_array(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.enter_container(len, self.config.limits.max_array_len, "max_array_len")?;
+        if self.opts & ARRAY_AS_NUMPY != 0 {
+            return self.deserialize_numpy_array(len);
+        }
+        if self.opts & ARRAY_AS_TUPLE != 0 {
+            return self.deserialize_tuple(len);
+        }
         let ptr = unsafe { pyo3::ffi::PyList_New(len as pyo3::ffi::Py_ssize_t) };
         for i in 0..len {
-            let elem = self.deserialize()?;
+            let elem = self.deserialize().inspect_err(|_| unsafe {
+                self.push_error_index(i);
+                pyo3::ffi::Py_DECREF(ptr);
+            })?;
             unsafe { pyo3::ffi::PyList_SET_ITEM(ptr, i as pyo3::ffi::Py_ssize_t, elem.as_ptr()) };
         }
         unsafe { Ok(NonNull::new_unchecked(ptr)) }
     }
 
+    /// Returns a list, or a tuple with `ARRAY_AS_TUPLE`, of `items`.
+    fn sequence_object(
+        &self,
+        items: Vec<NonNull<pyo3::ffi::PyObject>>,
+    ) -> NonNull<pyo3::ffi::PyObject> {
+        let len = items.len() as pyo3::ffi::Py_ssize_t;
+        unsafe {
+            if self.opts & ARRAY_AS_TUPLE != 0 {
+                let ptr = pyo3::ffi::PyTuple_New(len);
+                for (i, item) in items.into_iter().enumerate() {
+                    pytuple_set_item(ptr, i as pyo3::ffi::Py_ssize_t, item.as_ptr());
+                }
+                NonNull::new_unchecked(ptr)
+            } else {
+                let ptr = pyo3::ffi::PyList_New(len);
+                for (i, item) in items.into_iter().enumerate() {
+                    pyo3::ffi::PyList_SET_ITEM(ptr, i as pyo3::ffi::Py_ssize_t, item.as_ptr());
+                }
+                NonNull::new_unchecked(ptr)
+            }
+        }
+    }
+
+    /// Deserializes an array as a numpy array if its elements are numbers of
+    /// the same kind, or arrays of the same shape that are deserialized as
+    /// numpy arrays of the same kind. The limits of the array have been
+    /// checked.
+    fn deserialize_numpy_array(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        if unsafe { (*self.state).get_numpy_types() }.is_none() {
+            return Err(Error::NumpyMissing);
+        }
+        match self.read_numeric_array(len)? {
+            Numeric::Array(array) => self.numeric_object(array.view()),
+            Numeric::Number(_) => unreachable!(),
+            Numeric::Object(obj) => Ok(obj),
+        }
+    }
+
+    /// Reads the elements of an array as numbers, as long as the array may
+    /// be deserialized as a numpy array, and deserializes the rest of it as
+    /// usual otherwise.
+    fn read_numeric_array(&mut self, len: u32) -> Result<Numeric, Error> {
+        if len == 0 {
+            return Ok(Numeric::Object(self.sequence_object(Vec::new())));
+        }
+        let mut array = NumericArray::new(len as usize);
+        for i in 0..len {
+            let offset = self.offset();
+            let element = self.read_numeric_element().inspect_err(|_| {
+                self.set_error_offset(offset);
+                self.push_error_index(i);
+            })?;
+            let pushed = match element {
+                Numeric::Number(value) => array.push_number(i, value),
+                Numeric::Array(ref value) => array.push_array(i, value),
+                Numeric::Object(_) => false,
+            };
+            if !pushed {
+                let mut items = Vec::with_capacity(len as usize);
+                return match self.read_numeric_fallback(&mut items, array.view(), i, len, element) {
+                    Ok(()) => Ok(Numeric::Object(self.sequence_object(items))),
+                    Err(err) => {
+                        for item in items {
+                            unsafe { pyo3::ffi::Py_DECREF(item.as_ptr()) };
+                        }
+                        Err(err)
+                    }
+                };
+            }
+        }
+        Ok(Numeric::Array(array))
+    }
+
+    /// Reads an element of an array that may be deserialized as a numpy
+    /// array.
+    fn read_numeric_element(&mut self) -> Result<Numeric, Error> {
+        self.recursion += 1;
+        if unlikely!(self.recursion == RECURSION_LIMIT) {
+            return Err(Error::RecursionLimitReached);
+        }
+        let marker = self.read_marker()?;
+        let len = match marker {
+            Marker::FixArray(len) => len.into(),
+            Marker::Array16 => self.data.read_u16()?.into(),
+            Marker::Array32 => self.data.read_u32()?,
+            _ => {
+                let value = match self.read_number(marker)? {
+                    Some(value) => Numeric::Number(value),
+                    None => Numeric::Object(self.read_value(marker)?),
+                };
+                self.recursion -= 1;
+                return Ok(value);
+            }
+        };
+        self.enter_container(len, self.config.limits.max_array_len, "max_array_len")?;
+        let value = self.read_numeric_array(len)?;
+        self.recursion -= 1;
+        Ok(value)
+    }
+
+    /// Reads the number that starts with `marker`, if it is one.
+    fn read_number(&mut self, marker: Marker) -> Result<Option<Number>, Error> {
+        let value = match marker {
+            Marker::True => Number::Bool(true),
+            Marker::False => Number::Bool(false),
+            Marker::FixPos(value) => Number::UInt(value.into()),
+            Marker::U8 => Number::UInt(self.data.read_u8()?.into()),
+            Marker::U16 => Number::UInt(self.data.read_u16()?.into()),
+            Marker::U32 => Number::UInt(self.data.read_u32()?.into()),
+            Marker::U64 => Number::UInt(self.data.read_u64()?),
+            Marker::FixNeg(value) => Number::Int(value.into()),
+            Marker::I8 => Number::Int(self.data.read_i8()?.into()),
+            Marker::I16 => Number::Int(self.data.read_i16()?.into()),
+            Marker::I32 => Number::Int(self.data.read_i32()?.into()),
+            Marker::I64 => Number::Int(self.data.read_i64()?),
+            Marker::F32 => Number::Float32(self.data.read_f32()?),
+            Marker::F64 => Number::Float64(self.data.read_f64()?),
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+
+    /// Deserializes the elements of an array that is not deserialized as a
+    /// numpy array into `items`. `array` holds the numbers of its first
+    /// `index` elements and `element` is the next one.
+    fn read_numeric_fallback(
+        &mut self,
+        items: &mut Vec<NonNull<pyo3::ffi::PyObject>>,
+        array: NumericView,
+        index: u32,
+        len: u32,
+        element: Numeric,
+    ) -> Result<(), Error> {
+        let current = match element {
+            Numeric::Number(value) => self.number_object(value)?,
+            Numeric::Array(value) => self.numeric_object(value.view())?,
+            Numeric::Object(obj) => obj,
+        };
+        for i in 0..index as usize {
+            let item = if array.shape.len() == 1 {
+                self.number_object(array.data[i])
+            } else {
+                self.numeric_object(array.element(i))
+            };
+            items.push(item.inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(current.as_ptr()) })?);
+        }
+        items.push(current);
+        for i in index + 1..len {
+            items.push(
+                self.deserialize()
+                    .inspect_err(|_| self.push_error_index(i))?,
+            );
+        }
+        Ok(())
+    }
+
+    fn number_object(&self, value: Number) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        match value {
+            Number::Bool(true) => self.deserialize_true(),
+            Number::Bool(false) => self.deserialize_false(),
+            Number::Int(value) => self.deserialize_i64(value),
+            Number::UInt(value) => self.deserialize_u64(value),
+            Number::Float32(value) => self.deserialize_f64(value.into()),
+            Number::Float64(value) => self.deserialize_f64(value),
+        }
+    }
+
+    /// Creates a numpy array holding the numbers of `array`, or a list of
+    /// its elements if no numpy data type holds all of them.
+    fn numeric_object(&self, array: NumericView) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let types = unsafe { (*self.state).get_numpy_types() };
+        match create_ndarray(types.as_ref().ok_or(Error::NumpyMissing)?, array) {
+            Ok(Some(obj)) => return Ok(obj),
+            Ok(None) => {}
+            Err(()) => return Err(Error::NumpyFailed),
+        }
+        let mut items = Vec::with_capacity(array.shape[0]);
+        for i in 0..array.shape[0] {
+            let item = if array.shape.len() == 1 {
+                self.number_object(array.data[i])
+            } else {
+                self.numeric_object(array.element(i))
+            };
+            match item {
+                Ok(obj) => items.push(obj),
+                Err(err) => {
+                    for item in items {
+                        unsafe { pyo3::ffi::Py_DECREF(item.as_ptr()) };
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(self.sequence_object(items))
+    }
+
+    fn deserialize_str_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let offset = self.offset();
+        self.read_str_map_key()
+            .inspect_err(|_| self.set_error_offset(offset))
+    }
+
+    #[inline(always)]
+    fn read_str_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let marker = self.read_marker()?;
+        match marker {
+            Marker::FixStr(len) => self.deserialize_map_str_key(len.into()),
+            Marker::Str8 => {
+                let len = self.data.read_u8()?;
+                self.deserialize_map_str_key(len.into())
+            }
+            Marker::Str16 => {
+                let len = self.data.read_u16()?;
+                self.deserialize_map_str_key(len.into())
+            }
+            Marker::Str32 => {
+                let len = self.data.read_u32()?;
+                self.deserialize_map_str_key(len)
+            }
+            marker => Err(Error::InvalidType(marker)),
+        }
+    }
+
     fn deserialize_map_with_str_keys(
         &mut self,
         len: u32,
     ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
         let dict_ptr = unsafe { pyo3::ffi::PyDict_New() };
-        for _ in 0..len {
-            let marker = self.read_marker()?;
-            let key = match marker {
-                Marker::FixStr(len) => self.deserialize_map_str_key(len.into()),
-                Marker::Str8 => {
-                    let len = self.data.read_u8()?;
-                    self.deserialize_map_str_key(len.into())
-                }
-                Marker::Str16 => {
-                    let len = self.data.read_u16()?;
-                    self.deserialize_map_str_key(len.into())
-                }
-                Marker::Str32 => {
-                    let len = self.data.read_u32()?;
-                    self.deserialize_map_str_key(len)
-                }
-                marker => Err(Error::InvalidType(marker)),
-            }?;
-            let value = self.deserialize()?;
+        for i in 0..len {
+            let offset = self.offset();
+            let key = self
+                .deserialize_str_map_key()
+                .inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(dict_ptr) })?;
+            let value = self.deserialize().inspect_err(|_| unsafe {
+                self.error_path.push(key);
+                pyo3::ffi::Py_DECREF(dict_ptr);
+            })?;
             unsafe {
                 let _ = pyo3::ffi::PyDict_SetItem(dict_ptr, key.as_ptr(), value.as_ptr());
+                if unlikely!(self.opts & FORBID_DUPLICATE_KEYS != 0)
+                    && pydict_size(dict_ptr) as u32 != i + 1
+                {
+                    let err = self.duplicate_key(key, offset);
+                    pyo3::ffi::Py_DECREF(key.as_ptr());
+                    pyo3::ffi::Py_DECREF(value.as_ptr());
+                    pyo3::ffi::Py_DECREF(dict_ptr);
+                    return Err(err);
+                }
                 // counter Py_INCREF in insertdict
                 pyo3::ffi::Py_DECREF(key.as_ptr());
                 pyo3::ffi::Py_DECREF(value.as_ptr());
@@ -294,37 +1136,527 @@ where
         len: u32,
     ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
         let dict_ptr = unsafe { pyo3::ffi::PyDict_New() };
-        for _ in 0..len {
-            let key = self.deserialize_map_key()?;
-            let value = self.deserialize()?;
+        for i in 0..len {
+            let offset = self.offset();
+            let key = self
+                .deserialize_map_key()
+                .inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(dict_ptr) })?;
+            let value = self.deserialize().inspect_err(|_| unsafe {
+                self.error_path.push(key);
+                pyo3::ffi::Py_DECREF(dict_ptr);
+            })?;
             unsafe {
                 let ret = pyo3::ffi::PyDict_SetItem(dict_ptr, key.as_ptr(), value.as_ptr());
+                let err = if unlikely!(ret == -1) {
+                    Some(self.unhashable_key(key, offset))
+                } else if unlikely!(self.opts & FORBID_DUPLICATE_KEYS != 0)
+                    && pydict_size(dict_ptr) as u32 != i + 1
+                {
+                    Some(self.duplicate_key(key, offset))
+                } else {
+                    None
+                };
                 pyo3::ffi::Py_DECREF(key.as_ptr());
                 pyo3::ffi::Py_DECREF(value.as_ptr());
-                if unlikely!(ret == -1) {
-                    return Err(Error::Internal);
+                if let Some(err) = err {
+                    pyo3::ffi::Py_DECREF(dict_ptr);
+                    return Err(err);
                 }
             }
         }
         unsafe { Ok(NonNull::new_unchecked(dict_ptr)) }
     }
 
+    fn deserialize_map_pairs(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let ptr = unsafe { pyo3::ffi::PyList_New(len as pyo3::ffi::Py_ssize_t) };
+        // The keys seen so far, to detect duplicates.
+        let keys = if unlikely!(self.opts & FORBID_DUPLICATE_KEYS != 0) {
+            unsafe { pyo3::ffi::PySet_New(std::ptr::null_mut()) }
+        } else {
+            std::ptr::null_mut()
+        };
+        let release = |ptr: *mut pyo3::ffi::PyObject, keys: *mut pyo3::ffi::PyObject| unsafe {
+            pyo3::ffi::Py_DECREF(ptr);
+            pyo3::ffi::Py_XDECREF(keys);
+        };
+        for i in 0..len {
+            let offset = self.offset();
+            let key = if self.opts & NON_STR_KEYS != 0 {
+                self.deserialize()
+            } else {
+                self.deserialize_str_map_key()
+            }
+            .inspect_err(|_| release(ptr, keys))?;
+            if !keys.is_null() {
+                let err = unsafe {
+                    if pyo3::ffi::PySet_Add(keys, key.as_ptr()) == -1 {
+                        Some(self.unhashable_key(key, offset))
+                    } else if pyo3::ffi::PySet_Size(keys) as u32 != i + 1 {
+                        Some(self.duplicate_key(key, offset))
+                    } else {
+                        None
+                    }
+                };
+                if let Some(err) = err {
+                    unsafe { pyo3::ffi::Py_DECREF(key.as_ptr()) };
+                    release(ptr, keys);
+                    return Err(err);
+                }
+            }
+            let value = self.deserialize().inspect_err(|_| {
+                self.error_path.push(key);
+                release(ptr, keys);
+            })?;
+            unsafe {
+                let pair = pyo3::ffi::PyTuple_New(2);
+                pytuple_set_item(pair, 0, key.as_ptr());
+                pytuple_set_item(pair, 1, value.as_ptr());
+                pyo3::ffi::PyList_SET_ITEM(ptr, i as pyo3::ffi::Py_ssize_t, pair);
+            }
+        }
+        unsafe {
+            pyo3::ffi::Py_XDECREF(keys);
+            Ok(NonNull::new_unchecked(ptr))
+        }
+    }
+
+    fn call_object_hook(
+        &self,
+        callable: NonNull<pyo3::ffi::PyObject>,
+        arg: NonNull<pyo3::ffi::PyObject>,
+        error: Error,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        unsafe {
+            let obj = pyobject_call_one_arg(callable.as_ptr(), arg.as_ptr());
+            pyo3::ffi::Py_DECREF(arg.as_ptr());
+            if unlikely!(obj.is_null()) {
+                Err(error)
+            } else {
+                Ok(NonNull::new_unchecked(obj))
+            }
+        }
+    }
+
     fn deserialize_map(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
-        if self.opts & NON_STR_KEYS != 0 {
+        self.enter_container(len, self.config.limits.max_map_len, "max_map_len")?;
+        if let Some(callable) = self.config.object_pairs_hook {
+            let pairs = self.deserialize_map_pairs(len)?;
+            return self.call_object_hook(callable, pairs, Error::ObjectPairsHookFailed);
+        }
+        let mut dict = if self.opts & NON_STR_KEYS != 0 {
             self.deserialize_map_with_non_str_keys(len)
         } else {
             self.deserialize_map_with_str_keys(len)
+        }?;
+        if let Some(callable) = self.config.map_type {
+            dict = self.call_object_hook(callable, dict, Error::MapTypeFailed)?;
+        }
+        match self.config.object_hook {
+            Some(callable) => self.call_object_hook(callable, dict, Error::ObjectHookFailed),
+            None => Ok(dict),
+        }
+    }
+
+    /// Deserializes an object as the type given by the config, if any.
+    fn deserialize_root(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        match self.config.type_ {
+            Some(tp) => {
+                let plan = typed::plan(self.state, tp).map_err(Error::Conversion)?;
+                self.deserialize_typed(&plan, plan.root())
+            }
+            None => self.deserialize(),
+        }
+    }
+
+    /// Deserializes an object as the node `index` of `plan`, recording its
+    /// offset if it fails.
+    fn deserialize_typed(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let offset = self.offset();
+        self.read_typed_object(plan, index)
+            .inspect_err(|_| self.set_error_offset(offset))
+    }
+
+    fn read_typed_object(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.recursion += 1;
+        if unlikely!(self.recursion == RECURSION_LIMIT) {
+            return Err(Error::RecursionLimitReached);
+        }
+
+        let marker = self.read_marker()?;
+        let value = self.read_typed_value(plan, index, marker);
+        self.recursion -= 1;
+        value
+    }
+
+    /// Deserializes the object that starts with `marker` as the node `index`
+    /// of `plan`. Arrays and maps are deserialized element by element with
+    /// the nodes of their elements, other objects are deserialized as usual
+    /// and converted.
+    fn read_typed_value(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+        marker: Marker,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        match *plan.node(index).1 {
+            typed::Node::Any => return self.read_value(marker),
+            typed::Node::Union(ref alternatives) => {
+                if let Some(index) = plan.optional(alternatives) {
+                    if let Marker::Null = marker {
+                        return self.deserialize_null();
+                    }
+                    return self.read_typed_value(plan, index, marker);
+                }
+            }
+            typed::Node::List(_)
+            | typed::Node::VarTuple(_)
+            | typed::Node::Tuple(_)
+            | typed::Node::NamedTuple(..)
+                if self.opts & ARRAY_AS_NUMPY == 0 =>
+            {
+                let len = match marker {
+                    Marker::FixArray(len) => Some(len.into()),
+                    Marker::Array16 => Some(self.data.read_u16()?.into()),
+                    Marker::Array32 => Some(self.data.read_u32()?),
+                    _ => None,
+                };
+                if let Some(len) = len {
+                    return self.deserialize_typed_array(plan, index, len);
+                }
+            }
+            typed::Node::Dict(..) | typed::Node::Dataclass(_) | typed::Node::TypedDict(_)
+                if self.config.object_hook.is_none()
+                    && self.config.object_pairs_hook.is_none()
+                    && self.config.map_type.is_none() =>
+            {
+                let len = match marker {
+                    Marker::FixMap(len) => Some(len.into()),
+                    Marker::Map16 => Some(self.data.read_u16()?.into()),
+                    Marker::Map32 => Some(self.data.read_u32()?),
+                    _ => None,
+                };
+                if let Some(len) = len {
+                    return self.deserialize_typed_map(plan, index, len);
+                }
+            }
+            _ => {}
+        }
+        let value = self.read_value(marker)?;
+        self.convert(plan, index, value)
+    }
+
+    /// Converts `value` with the node `index` of `plan`, adding the path of
+    /// the object that could not be converted to the error path on error.
+    fn convert(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+        value: NonNull<pyo3::ffi::PyObject>,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let mut converter = typed::Converter::new(plan);
+        match unsafe { converter.convert(index, value.as_ptr()) } {
+            Ok(obj) => Ok(unsafe { NonNull::new_unchecked(obj) }),
+            Err(message) => {
+                self.error_path.extend(converter.take_path());
+                Err(Error::Conversion(message))
+            }
+        }
+    }
+
+    /// Deserializes an array of `len` elements as the list, tuple or named
+    /// tuple node `index` of `plan`.
+    fn deserialize_typed_array(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+        len: u32,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.enter_container(len, self.config.limits.max_array_len, "max_array_len")?;
+        let (tp, node) = plan.node(index);
+        let item_node = |i: usize| match *node {
+            typed::Node::List(item) | typed::Node::VarTuple(item) => item,
+            typed::Node::Tuple(ref items) => items[i],
+            typed::Node::NamedTuple(ref fields, _) => fields[i],
+            _ => unreachable!(),
+        };
+        match *node {
+            typed::Node::Tuple(ref items) if len as usize != items.len() => {
+                return Err(Error::Conversion(typed::array_length_mismatch(
+                    items.len(),
+                    len as usize,
+                )));
+            }
+            typed::Node::NamedTuple(ref fields, required)
+                if (len as usize) < required || len as usize > fields.len() =>
+            {
+                return Err(Error::Conversion(typed::array_length_range_mismatch(
+                    required,
+                    fields.len(),
+                    len as usize,
+                )));
+            }
+            _ => {}
+        }
+        let is_list = matches!(*node, typed::Node::List(_));
+        let ptr = unsafe {
+            if is_list {
+                pyo3::ffi::PyList_New(len as pyo3::ffi::Py_ssize_t)
+            } else {
+                pyo3::ffi::PyTuple_New(len as pyo3::ffi::Py_ssize_t)
+            }
+        };
+        if unlikely!(ptr.is_null()) {
+            return Err(Error::Internal);
+        }
+        for i in 0..len {
+            let elem = self
+                .deserialize_typed(plan, item_node(i as usize))
+                .inspect_err(|_| unsafe {
+                    self.push_error_index(i);
+                    pyo3::ffi::Py_DECREF(ptr);
+                })?;
+            unsafe {
+                if is_list {
+                    pyo3::ffi::PyList_SET_ITEM(ptr, i as pyo3::ffi::Py_ssize_t, elem.as_ptr());
+                } else {
+                    pytuple_set_item(ptr, i as pyo3::ffi::Py_ssize_t, elem.as_ptr());
+                }
+            }
+        }
+        if let typed::Node::NamedTuple(..) = *node {
+            unsafe {
+                let obj = pyo3::ffi::PyObject_Call(tp, ptr, std::ptr::null_mut());
+                pyo3::ffi::Py_DECREF(ptr);
+                if obj.is_null() {
+                    return Err(Error::Conversion(typed::construction_failed(tp)));
+                }
+                return Ok(NonNull::new_unchecked(obj));
+            }
+        }
+        unsafe { Ok(NonNull::new_unchecked(ptr)) }
+    }
+
+    /// Deserializes a map of `len` entries as the dict, dataclass or typed
+    /// dict node `index` of `plan`.
+    fn deserialize_typed_map(
+        &mut self,
+        plan: &typed::Plan,
+        index: usize,
+        len: u32,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.enter_container(len, self.config.limits.max_map_len, "max_map_len")?;
+        let (tp, node) = plan.node(index);
+        match *node {
+            typed::Node::Dict(key_node, value_node) => {
+                self.deserialize_typed_dict(plan, key_node, value_node, len)
+            }
+            typed::Node::Dataclass(ref fields) => {
+                let kwargs = self.deserialize_fields(plan, fields, len)?;
+                unsafe {
+                    let args = pyo3::ffi::PyTuple_New(0);
+                    let obj = pyo3::ffi::PyObject_Call(tp, args, kwargs.as_ptr());
+                    pyo3::ffi::Py_DECREF(args);
+                    pyo3::ffi::Py_DECREF(kwargs.as_ptr());
+                    if obj.is_null() {
+                        return Err(Error::Conversion(typed::construction_failed(tp)));
+                    }
+                    Ok(NonNull::new_unchecked(obj))
+                }
+            }
+            typed::Node::TypedDict(ref fields) => self.deserialize_fields(plan, fields, len),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Deserializes a map key as usual for the options.
+    fn deserialize_any_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        if self.opts & NON_STR_KEYS != 0 {
+            self.deserialize_map_key()
+        } else {
+            self.deserialize_str_map_key()
+        }
+    }
+
+    /// Deserializes a map of `len` entries as a dict, converting its keys
+    /// with the node `key_node` and its values with the node `value_node`.
+    fn deserialize_typed_dict(
+        &mut self,
+        plan: &typed::Plan,
+        key_node: usize,
+        value_node: usize,
+        len: u32,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let dict_ptr = unsafe { pyo3::ffi::PyDict_New() };
+        if unlikely!(dict_ptr.is_null()) {
+            return Err(Error::Internal);
+        }
+        for i in 0..len {
+            let offset = self.offset();
+            let key = self
+                .deserialize_any_map_key()
+                .inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(dict_ptr) })?;
+            unsafe { pyo3::ffi::Py_INCREF(key.as_ptr()) };
+            let converted_key = self.convert(plan, key_node, key).inspect_err(|_| unsafe {
+                self.set_error_offset(offset);
+                self.error_path.push(key);
+                pyo3::ffi::Py_DECREF(dict_ptr);
+            })?;
+            let value = self
+                .deserialize_typed(plan, value_node)
+                .inspect_err(|_| unsafe {
+                    self.error_path.push(key);
+                    pyo3::ffi::Py_DECREF(converted_key.as_ptr());
+                    pyo3::ffi::Py_DECREF(dict_ptr);
+                })?;
+            unsafe {
+                let ret =
+                    pyo3::ffi::PyDict_SetItem(dict_ptr, converted_key.as_ptr(), value.as_ptr());
+                let err = if unlikely!(ret == -1) {
+                    Some(self.unhashable_key(converted_key, offset))
+                } else if unlikely!(self.opts & FORBID_DUPLICATE_KEYS != 0)
+                    && pydict_size(dict_ptr) as u32 != i + 1
+                {
+                    Some(self.duplicate_key(converted_key, offset))
+                } else {
+                    None
+                };
+                pyo3::ffi::Py_DECREF(key.as_ptr());
+                pyo3::ffi::Py_DECREF(converted_key.as_ptr());
+                pyo3::ffi::Py_DECREF(value.as_ptr());
+                if let Some(err) = err {
+                    pyo3::ffi::Py_DECREF(dict_ptr);
+                    return Err(err);
+                }
+            }
         }
+        unsafe { Ok(NonNull::new_unchecked(dict_ptr)) }
     }
 
+    /// Deserializes a map of `len` entries as a dict of the values of
+    /// `fields`. The values of the other keys are deserialized as usual and
+    /// left out.
+    fn deserialize_fields(
+        &mut self,
+        plan: &typed::Plan,
+        fields: &[typed::Field],
+        len: u32,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let dict_ptr = unsafe { pyo3::ffi::PyDict_New() };
+        if unlikely!(dict_ptr.is_null()) {
+            return Err(Error::Internal);
+        }
+        let mut present = 0;
+        for i in 0..len {
+            let offset = self.offset();
+            let key = self
+                .deserialize_any_map_key()
+                .inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(dict_ptr) })?;
+            let field = unsafe {
+                if pyo3::ffi::PyUnicode_Check(key.as_ptr()) != 0 {
+                    fields.iter().find(|field| {
+                        field.name == key.as_ptr()
+                            || pyo3::ffi::PyUnicode_Compare(field.name, key.as_ptr()) == 0
+                    })
+                } else {
+                    None
+                }
+            };
+            let value = match field {
+                Some(field) => self.deserialize_typed(plan, field.node),
+                None => self.deserialize(),
+            }
+            .inspect_err(|_| unsafe {
+                self.error_path.push(key);
+                pyo3::ffi::Py_DECREF(dict_ptr);
+            })?;
+            unsafe {
+                let size = pydict_size(dict_ptr);
+                let ret = pyo3::ffi::PyDict_SetItem(dict_ptr, key.as_ptr(), value.as_ptr());
+                let err = if unlikely!(ret == -1) {
+                    Some(self.unhashable_key(key, offset))
+                } else if unlikely!(self.opts & FORBID_DUPLICATE_KEYS != 0)
+                    && pydict_size(dict_ptr) as u32 != i + 1
+                {
+                    Some(self.duplicate_key(key, offset))
+                } else {
+                    None
+                };
+                pyo3::ffi::Py_DECREF(key.as_ptr());
+                pyo3::ffi::Py_DECREF(value.as_ptr());
+                if let Some(err) = err {
+                    pyo3::ffi::Py_DECREF(dict_ptr);
+                    return Err(err);
+                }
+                if field.is_some() && pydict_size(dict_ptr) != size {
+                    present += 1;
+                }
+            }
+        }
+        unsafe { self.select_fields(fields, dict_ptr, present) }
+    }
+
+    /// Checks that the required `fields` are in `dict_ptr`, which holds
+    /// `present` distinct ones, and returns a dict of the fields only. The
+    /// reference to `dict_ptr` is consumed.
+    unsafe fn select_fields(
+        &mut self,
+        fields: &[typed::Field],
+        dict_ptr: *mut pyo3::ffi::PyObject,
+        present: usize,
+    ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let missing = fields
+            .iter()
+            .find(|field| field.required && pyo3::ffi::PyDict_Contains(dict_ptr, field.name) != 1);
+        if let Some(field) = missing {
+            pyo3::ffi::Py_DECREF(dict_ptr);
+            return Err(Error::Conversion(typed::missing_field(field.name)));
+        }
+        if pydict_size(dict_ptr) as usize == present {
+            return Ok(NonNull::new_unchecked(dict_ptr));
+        }
+        let res = pyo3::ffi::PyDict_New();
+        for field in fields {
+            let value = pyo3::ffi::PyDict_GetItemWithError(dict_ptr, field.name);
+            if !value.is_null() {
+                pyo3::ffi::PyDict_SetItem(res, field.name, value);
+            }
+        }
+        pyo3::ffi::Py_DECREF(dict_ptr);
+        NonNull::new(res).ok_or(Error::Internal)
+    }
+
+    /// Deserializes an object, recording its offset if it fails.
     fn deserialize(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let offset = self.offset();
+        self.read_object()
+            .inspect_err(|_| self.set_error_offset(offset))
+    }
+
+    #[inline(always)]
+    fn read_object(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
         self.recursion += 1;
         if unlikely!(self.recursion == RECURSION_LIMIT) {
             return Err(Error::RecursionLimitReached);
         }
 
         let marker = self.read_marker()?;
-        let value = match marker {
+        let value = self.read_value(marker);
+        self.recursion -= 1;
+        value
+    }
+
+    /// Deserializes the object that starts with `marker`.
+    #[inline(always)]
+    fn read_value(&mut self, marker: Marker) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        match marker {
             Marker::Null => self.deserialize_null(),
             Marker::True => self.deserialize_true(),
             Marker::False => self.deserialize_false(),
@@ -431,20 +1763,20 @@ where
                 self.deserialize_ext(len)
             }
             Marker::Reserved => Err(Error::InvalidType(Marker::Reserved)),
-        };
-
-        self.recursion -= 1;
-        value
+        }
     }
 
     fn deserialize_map_str_key(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
-        if unlikely!(len > 64) {
-            let value = self.deserialize_str(len)?;
-            hash_str(value.as_ptr());
-            Ok(value)
+        if unlikely!(self.config.raw) {
+            self.deserialize_str(len)
         } else {
+            self.check_len(len, self.config.limits.max_str_len, "max_str_len")?;
             let data = self.data.read_slice(len as usize)?;
-            Ok(unsafe { (*self.state).key_map.get(data)? })
+            Ok(unsafe {
+                (*self.state)
+                    .key_map
+                    .get(data, self.config.unicode_errors)?
+            })
         }
     }
 
@@ -452,9 +1784,12 @@ where
         &mut self,
         len: u32,
     ) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        self.enter_container(len, self.config.limits.max_array_len, "max_array_len")?;
         let ptr = unsafe { pyo3::ffi::PyTuple_New(len as pyo3::ffi::Py_ssize_t) };
         for i in 0..len {
-            let elem = self.deserialize_map_key()?;
+            let elem = self
+                .deserialize_map_key()
+                .inspect_err(|_| unsafe { pyo3::ffi::Py_DECREF(ptr) })?;
             unsafe {
                 pytuple_set_item(ptr, i as pyo3::ffi::Py_ssize_t, elem.as_ptr());
             }
@@ -462,16 +1797,15 @@ where
         unsafe { Ok(NonNull::new_unchecked(ptr)) }
     }
 
-    fn deserialize_map_ext_key(&mut self, len: u32) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
-        let tag = self.data.read_i8()?;
-        if tag == -1 && self.opts & DATETIME_AS_TIMESTAMP_EXT != 0 {
-            self.deserialize_timestamp_ext(len)
-        } else {
-            Err(Error::InvalidValue)
-        }
+    /// Deserializes a map key, recording its offset if it fails.
+    fn deserialize_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+        let offset = self.offset();
+        self.read_map_key()
+            .inspect_err(|_| self.set_error_offset(offset))
     }
 
-    fn deserialize_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
+    #[inline(always)]
+    fn read_map_key(&mut self) -> Result<NonNull<pyo3::ffi::PyObject>, Error> {
         self.recursion += 1;
         if unlikely!(self.recursion == RECURSION_LIMIT) {
             return Err(Error::RecursionLimitReached);
@@ -539,15 +1873,15 @@ where
             }
             Marker::Bin8 => {
                 let len = self.data.read_u8()?;
-                self.deserialize_bin(len.into())
+                self.deserialize_map_bin_key(len.into())
             }
             Marker::Bin16 => {
                 let len = self.data.read_u16()?;
-                self.deserialize_bin(len.into())
+                self.deserialize_map_bin_key(len.into())
             }
             Marker::Bin32 => {
                 let len = self.data.read_u32()?;
-                self.deserialize_bin(len)
+                self.deserialize_map_bin_key(len)
             }
             Marker::FixArray(len) => self.deserialize_map_array_key(len.into()),
             Marker::Array16 => {
@@ -558,11 +1892,22 @@ where
                 let len = self.data.read_u32()?;
                 self.deserialize_map_array_key(len)
             }
-            Marker::FixExt4 => self.deserialize_map_ext_key(4),
-            Marker::FixExt8 => self.deserialize_map_ext_key(8),
+            Marker::FixExt1 => self.deserialize_ext(1),
+            Marker::FixExt2 => self.deserialize_ext(2),
+            Marker::FixExt4 => self.deserialize_ext(4),
+            Marker::FixExt8 => self.deserialize_ext(8),
+            Marker::FixExt16 => self.deserialize_ext(16),
             Marker::Ext8 => {
                 let len = self.data.read_u8()?;
-                self.deserialize_map_ext_key(len.into())
+                self.deserialize_ext(len.into())
+            }
+            Marker::Ext16 => {
+                let len = self.data.read_u16()?;
+                self.deserialize_ext(len.into())
+            }
+            Marker::Ext32 => {
+                let len = self.data.read_u32()?;
+                self.deserialize_ext(len)
             }
             marker => Err(Error::InvalidType(marker)),
         };
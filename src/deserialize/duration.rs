@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use pyo3::ffi::*;
+use std::ptr::NonNull;
+
+const MICROSECONDS_PER_DAY: i128 = 86_400_000_000;
+const MAX_DAYS: i128 = 999_999_999;
+
+const DATE_UNITS: [(u8, i128); 1] = [(b'D', MICROSECONDS_PER_DAY)];
+const TIME_UNITS: [(u8, i128); 3] = [(b'H', 3_600_000_000), (b'M', 60_000_000), (b'S', 1_000_000)];
+
+/// Returns the number of microseconds of `number`, a number of `scale`
+/// microseconds with a fraction of up to 6 digits if `fraction` is set.
+fn parse_number(number: &[u8], scale: i128, fraction: bool) -> Option<i128> {
+    let (integer, decimals) = match number.iter().position(|&c| c == b'.') {
+        Some(i) if fraction && i + 1 < number.len() => (&number[..i], &number[i + 1..]),
+        Some(_) => return None,
+        None => (number, &b""[..]),
+    };
+    if integer.is_empty()
+        || integer.len() > 18
+        || decimals.len() > 6
+        || !integer.iter().chain(decimals).all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+    let integer = integer
+        .iter()
+        .fold(0, |acc, &c| acc * 10 + i128::from(c - b'0'));
+    let decimals = decimals
+        .iter()
+        .chain(std::iter::repeat(&b'0'))
+        .take(6)
+        .fold(0, |acc, &c| acc * 10 + i128::from(c - b'0'));
+    Some(integer * scale + decimals)
+}
+
+/// Returns the number of microseconds of an ISO 8601 duration of days,
+/// hours, minutes and seconds, such as `-P1DT2H3M4.5S`. `None` is returned
+/// if `value` is not such a duration.
+pub fn parse_duration(value: &[u8]) -> Option<i128> {
+    let (sign, value) = match value.first() {
+        Some(b'-') => (-1, &value[1..]),
+        Some(b'+') => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let value = value.strip_prefix(b"P")?;
+    let (date, time) = match value.iter().position(|&c| c == b'T') {
+        Some(i) if i + 1 < value.len() => (&value[..i], &value[i + 1..]),
+        Some(_) => return None,
+        None if !value.is_empty() => (value, &b""[..]),
+        None => return None,
+    };
+    let mut total = 0;
+    for (mut part, mut units) in [(date, &DATE_UNITS[..]), (time, &TIME_UNITS[..])] {
+        while !part.is_empty() {
+            let end = part
+                .iter()
+                .position(|&c| !c.is_ascii_digit() && c != b'.')?;
+            let index = units.iter().position(|&(unit, _)| unit == part[end])?;
+            let scale = units[index].1;
+            total += parse_number(&part[..end], scale, part[end] == b'S')?;
+            units = &units[index + 1..];
+            part = &part[end + 1..];
+        }
+    }
+    Some(sign * total)
+}
+
+/// Creates a `datetime.timedelta` instance of `microseconds` microseconds.
+/// `None` is returned if it is out of the range of `datetime.timedelta`.
+pub fn timedelta_from_microseconds(microseconds: i128) -> Option<NonNull<PyObject>> {
+    let days = microseconds.div_euclid(MICROSECONDS_PER_DAY);
+    if days.abs() > MAX_DAYS {
+        return None;
+    }
+    let rest = microseconds.rem_euclid(MICROSECONDS_PER_DAY);
+    unsafe {
+        let datetime_api = *PyDateTimeAPI();
+        let obj = (datetime_api.Delta_FromDelta)(
+            days as i32,
+            (rest / 1_000_000) as i32,
+            (rest % 1_000_000) as i32,
+            1,
+            datetime_api.DeltaType,
+        );
+        NonNull::new(obj)
+    }
+}
@@ -1,15 +1,85 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use crate::deserialize::typed::object_repr;
+use crate::ffi::{pyerr_restore, pyerr_take};
 use std::borrow::Cow;
+use std::ptr::NonNull;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct DeserializeError<'a> {
     pub message: Cow<'a, str>,
+    pub limit_exceeded: bool,
+    /// The offset of the object that could not be deserialized, if known.
+    pub position: Option<usize>,
+    /// The list of the map keys and array indices that lead to the object
+    /// that could not be deserialized, if known.
+    pub path: Option<NonNull<pyo3::ffi::PyObject>>,
 }
 
 impl<'a> DeserializeError<'a> {
     #[cold]
     pub fn new(message: Cow<'a, str>) -> Self {
-        DeserializeError { message }
+        DeserializeError {
+            message,
+            limit_exceeded: false,
+            position: None,
+            path: None,
+        }
+    }
+
+    #[cold]
+    pub fn limit_exceeded(message: Cow<'a, str>) -> Self {
+        DeserializeError {
+            message,
+            limit_exceeded: true,
+            position: None,
+            path: None,
+        }
+    }
+
+    /// Sets the offset of the object that could not be deserialized and the
+    /// map keys and array indices that lead to it, outermost first, and adds
+    /// them to the message. An exception that is already set is kept. The
+    /// references to the items of `path` are consumed.
+    #[cold]
+    pub fn locate(
+        &mut self,
+        position: usize,
+        path: impl ExactSizeIterator<Item = NonNull<pyo3::ffi::PyObject>>,
+    ) {
+        unsafe {
+            let cause = pyerr_take();
+            let list = pyo3::ffi::PyList_New(path.len() as pyo3::ffi::Py_ssize_t);
+            if list.is_null() {
+                pyo3::ffi::PyErr_Clear();
+                for item in path {
+                    pyo3::ffi::Py_DECREF(item.as_ptr());
+                }
+                pyerr_restore(cause);
+                return;
+            }
+            for (i, item) in path.enumerate() {
+                pyo3::ffi::PyList_SET_ITEM(list, i as pyo3::ffi::Py_ssize_t, item.as_ptr());
+            }
+            let mut message = format!("{} at position {position}", self.message);
+            if pyo3::ffi::PyList_GET_SIZE(list) != 0 {
+                message.push_str(", path ");
+                message.push_str(&object_repr(list));
+            }
+            self.message = Cow::Owned(message);
+            self.position = Some(position);
+            if let Some(path) = self.path.replace(NonNull::new_unchecked(list)) {
+                pyo3::ffi::Py_DECREF(path.as_ptr());
+            }
+            pyerr_restore(cause);
+        }
+    }
+}
+
+impl Drop for DeserializeError<'_> {
+    fn drop(&mut self) {
+        if let Some(path) = self.path {
+            unsafe { pyo3::ffi::Py_DECREF(path.as_ptr()) };
+        }
     }
 }
@@ -1,9 +1,24 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 mod cache;
+mod config;
 mod deserializer;
+mod duration;
 mod error;
+mod number;
+mod numpy;
+mod scanner;
+mod stream;
+mod typed;
 
-pub use cache::KeyMap;
-pub use deserializer::deserialize;
+pub use cache::{ConfigureError as KeyMapConfigureError, KeyMap};
+pub use config::{Config, UnicodeErrors};
+pub use deserializer::{
+    deserialize, deserialize_at, deserialize_frame, deserialize_frame_with_offset,
+    deserialize_from_buffer, deserialize_from_stream, deserialize_path, deserialize_with_offset,
+    input_as_bytes,
+};
 pub use error::DeserializeError;
+pub use scanner::Scanner;
+pub use stream::StreamReader;
+pub use typed::get_type_plan;
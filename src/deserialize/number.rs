@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use pyo3::ffi::*;
+use std::ptr::NonNull;
+
+/// Returns the decimal digits of a big-endian unsigned integer, most
+/// significant first and without leading zeros. The conversion is done in Rust
+/// rather than through an int, as CPython limits the number of digits an int
+/// can be converted to.
+fn be_bytes_to_digits(bytes: &[u8]) -> Vec<u8> {
+    // Big-endian limbs in base 2**32, divided by 10**9 until exhausted.
+    let mut limbs: Vec<u32> = Vec::with_capacity(bytes.len() / 4 + 1);
+    let head = bytes.len() % 4;
+    if head != 0 {
+        let mut limb = [0u8; 4];
+        limb[4 - head..].copy_from_slice(&bytes[..head]);
+        limbs.push(u32::from_be_bytes(limb));
+    }
+    for chunk in bytes[head..].chunks_exact(4) {
+        limbs.push(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+    }
+    let zeros = limbs.iter().take_while(|&&limb| limb == 0).count();
+    limbs.drain(..zeros);
+    let mut chunks: Vec<u32> = Vec::with_capacity(limbs.len() * 10 / 9 + 1);
+    while !limbs.is_empty() {
+        let mut remainder = 0u64;
+        for limb in limbs.iter_mut() {
+            let value = (remainder << 32) | u64::from(*limb);
+            *limb = (value / 1_000_000_000) as u32;
+            remainder = value % 1_000_000_000;
+        }
+        chunks.push(remainder as u32);
+        if limbs[0] == 0 {
+            limbs.remove(0);
+        }
+    }
+    let mut digits = Vec::with_capacity(chunks.len() * 9);
+    match chunks.pop() {
+        Some(chunk) => digits.extend(chunk.to_string().bytes().map(|c| c - b'0')),
+        None => digits.push(0),
+    }
+    for chunk in chunks.iter().rev() {
+        digits.extend(format!("{chunk:09}").bytes().map(|c| c - b'0'));
+    }
+    digits
+}
+
+/// Creates a `decimal.Decimal` instance from the data of an extension object
+/// of type `DECIMAL_EXT_TYPE`. `None` is returned if the data is invalid or if
+/// an exception is raised.
+pub fn decimal_from_ext_data(
+    decimal_type: *mut PyTypeObject,
+    data: &[u8],
+) -> Option<NonNull<PyObject>> {
+    if data.len() < 5 || data[4] > 1 {
+        return None;
+    }
+    let exponent = i32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+    let sign = data[4];
+    let digits = be_bytes_to_digits(&data[5..]);
+    unsafe {
+        // The Decimal is created from a (sign, digits, exponent) tuple, which
+        // is exact regardless of the context.
+        let digits_obj = PyTuple_New(digits.len() as Py_ssize_t);
+        if digits_obj.is_null() {
+            return None;
+        }
+        for (i, &digit) in digits.iter().enumerate() {
+            let digit_obj = PyLong_FromLong(digit.into());
+            if digit_obj.is_null() {
+                Py_DECREF(digits_obj);
+                return None;
+            }
+            PyTuple_SET_ITEM(digits_obj, i as Py_ssize_t, digit_obj);
+        }
+        let sign_obj = PyLong_FromLong(sign.into());
+        let exponent_obj = PyLong_FromLong(exponent.into());
+        if sign_obj.is_null() || exponent_obj.is_null() {
+            Py_XDECREF(sign_obj);
+            Py_XDECREF(exponent_obj);
+            Py_DECREF(digits_obj);
+            return None;
+        }
+        let tuple = PyTuple_Pack(3, sign_obj, digits_obj, exponent_obj);
+        Py_DECREF(sign_obj);
+        Py_DECREF(digits_obj);
+        Py_DECREF(exponent_obj);
+        if tuple.is_null() {
+            return None;
+        }
+        let obj = pyobject_call_one_arg(decimal_type.cast::<PyObject>(), tuple);
+        Py_DECREF(tuple);
+        NonNull::new(obj)
+    }
+}
+
+/// Creates a `complex` instance from the data of an extension object of type
+/// `COMPLEX_EXT_TYPE`. `None` is returned if the data is invalid.
+pub fn complex_from_ext_data(data: &[u8]) -> Option<NonNull<PyObject>> {
+    let data: &[u8; 16] = data.try_into().ok()?;
+    let real = f64::from_be_bytes(data[..8].try_into().unwrap());
+    let imag = f64::from_be_bytes(data[8..].try_into().unwrap());
+    NonNull::new(unsafe { PyComplex_FromDoubles(real, imag) })
+}
@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use crate::state::NumpyTypes;
+use pyo3::ffi::*;
+use std::ptr::NonNull;
+
+/// A number of an array that may be deserialized as a numpy array.
+#[derive(Clone, Copy)]
+pub enum Number {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float32(f32),
+    Float64(f64),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bool,
+    Int,
+    Float,
+}
+
+impl Number {
+    pub fn kind(self) -> Kind {
+        match self {
+            Number::Bool(_) => Kind::Bool,
+            Number::Int(_) | Number::UInt(_) => Kind::Int,
+            Number::Float32(_) | Number::Float64(_) => Kind::Float,
+        }
+    }
+
+    fn to_bool(self) -> bool {
+        matches!(self, Number::Bool(true))
+    }
+
+    fn to_i128(self) -> i128 {
+        match self {
+            Number::Int(value) => value.into(),
+            Number::UInt(value) => value.into(),
+            _ => 0,
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        match self {
+            Number::Float32(value) => value.into(),
+            Number::Float64(value) => value,
+            _ => 0.0,
+        }
+    }
+}
+
+/// The numbers of an array of numbers of the same kind, or of arrays of the
+/// same shape holding numbers of the same kind, in row-major order.
+pub struct NumericArray {
+    pub shape: Vec<usize>,
+    pub data: Vec<Number>,
+}
+
+impl NumericArray {
+    pub fn new(len: usize) -> Self {
+        NumericArray {
+            shape: vec![len],
+            data: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.data[0].kind()
+    }
+
+    /// Appends a number, if the elements of the array are numbers of the
+    /// same kind. `index` is the index of the number in the array.
+    pub fn push_number(&mut self, index: u32, value: Number) -> bool {
+        if index != 0 && (self.shape.len() != 1 || value.kind() != self.kind()) {
+            return false;
+        }
+        self.data.push(value);
+        true
+    }
+
+    /// Appends the numbers of a nested array, if the elements of the array
+    /// are arrays of the same shape and kind. `index` is the index of the
+    /// nested array in the array.
+    pub fn push_array(&mut self, index: u32, value: &NumericArray) -> bool {
+        if index == 0 {
+            self.shape.extend_from_slice(&value.shape);
+        } else if self.shape[1..] != value.shape || value.kind() != self.kind() {
+            return false;
+        }
+        self.data.extend_from_slice(&value.data);
+        true
+    }
+
+    pub fn view(&self) -> NumericView<'_> {
+        NumericView {
+            shape: &self.shape,
+            data: &self.data,
+        }
+    }
+}
+
+/// A numeric array, or one of its elements if it is multi-dimensional.
+#[derive(Clone, Copy)]
+pub struct NumericView<'a> {
+    pub shape: &'a [usize],
+    pub data: &'a [Number],
+}
+
+impl<'a> NumericView<'a> {
+    /// Returns the element at `index`, if the array is multi-dimensional.
+    pub fn element(&self, index: usize) -> NumericView<'a> {
+        let size: usize = self.shape[1..].iter().product();
+        NumericView {
+            shape: &self.shape[1..],
+            data: &self.data[index * size..(index + 1) * size],
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum DType {
+    Bool,
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Float32,
+    Float64,
+}
+
+impl DType {
+    /// Returns the smallest data type that holds all of `data`, preferring
+    /// signed integers to unsigned integers of the same size.
+    fn of(data: &[Number]) -> Option<DType> {
+        match data[0].kind() {
+            Kind::Bool => Some(DType::Bool),
+            Kind::Float => {
+                if data.iter().all(|value| matches!(value, Number::Float32(_))) {
+                    Some(DType::Float32)
+                } else {
+                    Some(DType::Float64)
+                }
+            }
+            Kind::Int => {
+                let (min, max) = data
+                    .iter()
+                    .fold((i128::MAX, i128::MIN), |(min, max), value| {
+                        let value = value.to_i128();
+                        (min.min(value), max.max(value))
+                    });
+                [
+                    (DType::Int8, i128::from(i8::MIN), i128::from(i8::MAX)),
+                    (DType::UInt8, 0, i128::from(u8::MAX)),
+                    (DType::Int16, i128::from(i16::MIN), i128::from(i16::MAX)),
+                    (DType::UInt16, 0, i128::from(u16::MAX)),
+                    (DType::Int32, i128::from(i32::MIN), i128::from(i32::MAX)),
+                    (DType::UInt32, 0, i128::from(u32::MAX)),
+                    (DType::Int64, i128::from(i64::MIN), i128::from(i64::MAX)),
+                    (DType::UInt64, 0, i128::from(u64::MAX)),
+                ]
+                .into_iter()
+                .find(|&(_, lower, upper)| lower <= min && max <= upper)
+                .map(|(dtype, _, _)| dtype)
+            }
+        }
+    }
+
+    fn numpy_type(self, types: &NumpyTypes) -> *mut PyTypeObject {
+        match self {
+            DType::Bool => types.bool_,
+            DType::Int8 => types.int8,
+            DType::UInt8 => types.uint8,
+            DType::Int16 => types.int16,
+            DType::UInt16 => types.uint16,
+            DType::Int32 => types.int32,
+            DType::UInt32 => types.uint32,
+            DType::Int64 => types.int64,
+            DType::UInt64 => types.uint64,
+            DType::Float32 => types.float32,
+            DType::Float64 => types.float64,
+        }
+    }
+}
+
+macro_rules! fill {
+    ($buf:expr, $data:expr, $ty:ty, $convert:expr) => {
+        let ptr = $buf.cast::<$ty>();
+        for (i, value) in $data.iter().enumerate() {
+            ptr.add(i).write_unaligned($convert(*value) as $ty);
+        }
+    };
+}
+
+/// Creates a numpy array holding the numbers of `array`. `Ok(None)` is
+/// returned if no numpy data type holds all of them. `Err(())` is returned
+/// if numpy raised an exception, which is left set.
+pub fn create_ndarray(
+    types: &NumpyTypes,
+    array: NumericView,
+) -> Result<Option<NonNull<PyObject>>, ()> {
+    let dtype = match DType::of(array.data) {
+        Some(dtype) => dtype,
+        None => return Ok(None),
+    };
+    unsafe {
+        let shape = PyTuple_New(array.shape.len() as Py_ssize_t);
+        if shape.is_null() {
+            return Err(());
+        }
+        for (i, &dim) in array.shape.iter().enumerate() {
+            let dim = PyLong_FromSize_t(dim);
+            if dim.is_null() {
+                Py_DECREF(shape);
+                return Err(());
+            }
+            pytuple_set_item(shape, i as Py_ssize_t, dim);
+        }
+        let args = PyTuple_New(2);
+        if args.is_null() {
+            Py_DECREF(shape);
+            return Err(());
+        }
+        pytuple_set_item(args, 0, shape);
+        pytuple_set_item(
+            args,
+            1,
+            Py_NewRef(dtype.numpy_type(types).cast::<PyObject>()),
+        );
+        let ndarray = PyObject_Call(types.empty, args, std::ptr::null_mut());
+        Py_DECREF(args);
+        if ndarray.is_null() {
+            return Err(());
+        }
+        let mut view = Py_buffer::new();
+        if PyObject_GetBuffer(ndarray, &mut view, PyBUF_CONTIG) == -1 {
+            Py_DECREF(ndarray);
+            return Err(());
+        }
+        let buf = view.buf;
+        match dtype {
+            DType::Bool => {
+                fill!(buf, array.data, u8, Number::to_bool);
+            }
+            DType::Int8 => {
+                fill!(buf, array.data, i8, Number::to_i128);
+            }
+            DType::UInt8 => {
+                fill!(buf, array.data, u8, Number::to_i128);
+            }
+            DType::Int16 => {
+                fill!(buf, array.data, i16, Number::to_i128);
+            }
+            DType::UInt16 => {
+                fill!(buf, array.data, u16, Number::to_i128);
+            }
+            DType::Int32 => {
+                fill!(buf, array.data, i32, Number::to_i128);
+            }
+            DType::UInt32 => {
+                fill!(buf, array.data, u32, Number::to_i128);
+            }
+            DType::Int64 => {
+                fill!(buf, array.data, i64, Number::to_i128);
+            }
+            DType::UInt64 => {
+                fill!(buf, array.data, u64, Number::to_i128);
+            }
+            DType::Float32 => {
+                fill!(buf, array.data, f32, Number::to_f64);
+            }
+            DType::Float64 => {
+                fill!(buf, array.data, f64, Number::to_f64);
+            }
+        }
+        PyBuffer_Release(&mut view);
+        Ok(Some(NonNull::new_unchecked(ndarray)))
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::deserialize::config::Limits;
+use crate::deserialize::deserializer::RECURSION_LIMIT;
+use crate::msgpack::Marker;
+
+/// The layout of an object, following its marker and length.
+enum Kind {
+    /// A fixed-size payload, whose size is given as the length.
+    Scalar,
+    /// A payload of the length plus the given number of bytes, such as the
+    /// tag of an extension, with the given maximum length.
+    Data(usize, u32),
+    /// An array with the given maximum length.
+    Array(u32),
+    /// A map with the given maximum length.
+    Map(u32),
+}
+
+/// Finds out whether the input of an incremental deserializer holds a
+/// complete object. The progress is kept between calls, so that each byte of
+/// an object fed in many chunks is examined once.
+pub struct Scanner {
+    /// The number of objects left to scan in each open array or map.
+    pending: Vec<u64>,
+    /// The number of bytes scanned, from the start of the object.
+    offset: usize,
+    /// The number of array and map elements scanned.
+    elements: u64,
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Scanner {
+            pending: Vec::new(),
+            offset: 0,
+            elements: 0,
+        }
+    }
+
+    /// Forgets the progress, for scanning the next object.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.offset = 0;
+        self.elements = 0;
+    }
+
+    /// Returns whether `data`, which starts at the object and extends the
+    /// data of the previous calls, is ready to be deserialized. It is if it
+    /// holds the complete object, or if the object is invalid or exceeds
+    /// `limits`, which the deserializer reports without reading the rest.
+    pub fn scan(&mut self, data: &[u8], limits: &Limits) -> bool {
+        loop {
+            let rest = &data[self.offset..];
+            let Some((&byte, header)) = rest.split_first() else {
+                return false;
+            };
+            let (len_size, fix_len, kind) = match Marker::from_u8(byte) {
+                Marker::Null
+                | Marker::True
+                | Marker::False
+                | Marker::FixPos(_)
+                | Marker::FixNeg(_) => (0, 0, Kind::Scalar),
+                Marker::U8 | Marker::I8 => (0, 1, Kind::Scalar),
+                Marker::U16 | Marker::I16 => (0, 2, Kind::Scalar),
+                Marker::U32 | Marker::I32 | Marker::F32 => (0, 4, Kind::Scalar),
+                Marker::U64 | Marker::I64 | Marker::F64 => (0, 8, Kind::Scalar),
+                Marker::FixStr(len) => (0, len.into(), Kind::Data(0, limits.max_str_len)),
+                Marker::Str8 => (1, 0, Kind::Data(0, limits.max_str_len)),
+                Marker::Str16 => (2, 0, Kind::Data(0, limits.max_str_len)),
+                Marker::Str32 => (4, 0, Kind::Data(0, limits.max_str_len)),
+                Marker::Bin8 => (1, 0, Kind::Data(0, limits.max_bin_len)),
+                Marker::Bin16 => (2, 0, Kind::Data(0, limits.max_bin_len)),
+                Marker::Bin32 => (4, 0, Kind::Data(0, limits.max_bin_len)),
+                Marker::FixExt1 => (0, 1, Kind::Data(1, limits.max_ext_len)),
+                Marker::FixExt2 => (0, 2, Kind::Data(1, limits.max_ext_len)),
+                Marker::FixExt4 => (0, 4, Kind::Data(1, limits.max_ext_len)),
+                Marker::FixExt8 => (0, 8, Kind::Data(1, limits.max_ext_len)),
+                Marker::FixExt16 => (0, 16, Kind::Data(1, limits.max_ext_len)),
+                Marker::Ext8 => (1, 0, Kind::Data(1, limits.max_ext_len)),
+                Marker::Ext16 => (2, 0, Kind::Data(1, limits.max_ext_len)),
+                Marker::Ext32 => (4, 0, Kind::Data(1, limits.max_ext_len)),
+                Marker::FixArray(len) => (0, len.into(), Kind::Array(limits.max_array_len)),
+                Marker::Array16 => (2, 0, Kind::Array(limits.max_array_len)),
+                Marker::Array32 => (4, 0, Kind::Array(limits.max_array_len)),
+                Marker::FixMap(len) => (0, len.into(), Kind::Map(limits.max_map_len)),
+                Marker::Map16 => (2, 0, Kind::Map(limits.max_map_len)),
+                Marker::Map32 => (4, 0, Kind::Map(limits.max_map_len)),
+                Marker::Reserved => return true,
+            };
+            let Some(len_bytes) = header.get(..len_size) else {
+                return false;
+            };
+            let len = len_bytes
+                .iter()
+                .fold(fix_len, |acc, &b| (acc << 8) | u32::from(b));
+            let (payload, children) = match kind {
+                Kind::Scalar => (len as usize, 0),
+                Kind::Data(_, max_len) if len > max_len => return true,
+                Kind::Data(extra, _) => (len as usize + extra, 0),
+                Kind::Array(max_len) | Kind::Map(max_len) => {
+                    self.elements += u64::from(len);
+                    let depth = self.pending.len() + 1;
+                    if len > max_len
+                        || depth + 1 >= usize::from(RECURSION_LIMIT)
+                        || depth as u64 > u64::from(limits.max_depth)
+                        || self.elements > limits.max_elements
+                    {
+                        return true;
+                    }
+                    let children = match kind {
+                        Kind::Map(_) => 2 * u64::from(len),
+                        _ => u64::from(len),
+                    };
+                    (0, children)
+                }
+            };
+            let size = 1 + len_size + payload;
+            if rest.len() < size {
+                return false;
+            }
+            self.offset += size;
+            if children != 0 {
+                self.pending.push(children);
+                continue;
+            }
+            loop {
+                match self.pending.last_mut() {
+                    None => return true,
+                    Some(count) => {
+                        *count -= 1;
+                        if *count != 0 {
+                            break;
+                        }
+                        self.pending.pop();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use crate::io::Read;
+use crate::state::State;
+use pyo3::ffi::*;
+
+const CHUNK_LENGTH: usize = 65536;
+
+/// Reads from a binary file object through its `readinto` method in chunks
+/// of bounded size. If `readinto` raises an exception, the exception is left
+/// set and all subsequent reads fail.
+///
+/// Unless the file object is seekable, no more data than requested is read,
+/// as the data read ahead could not be given back.
+pub struct StreamReader {
+    buf: Vec<u8>,
+    pos: usize,
+    consumed: usize,
+    fp: *mut PyObject,
+    state: *mut State,
+    seekable: bool,
+    exhausted: bool,
+    failed: bool,
+}
+
+impl StreamReader {
+    pub fn new(fp: *mut PyObject, state: *mut State, seekable: bool) -> Self {
+        StreamReader {
+            buf: Vec::new(),
+            pos: 0,
+            consumed: 0,
+            fp: fp,
+            state: state,
+            seekable: seekable,
+            exhausted: false,
+            failed: false,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Returns the number of bytes read from the stream but not consumed.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns whether all the data of the stream has been consumed.
+    pub fn at_end(&mut self) -> Result<bool, std::io::Error> {
+        if self.remaining() == 0 && !self.exhausted {
+            self.fill(1)
+                .or_else(|err| if self.failed { Err(err) } else { Ok(()) })?;
+        }
+        Ok(self.remaining() == 0)
+    }
+
+    fn read_chunk(&mut self, len: usize) -> Result<(), std::io::Error> {
+        unsafe {
+            // Reading into a bytearray rather than into a view of the buffer
+            // ensures that no reference to the buffer can outlive it.
+            let chunk = PyByteArray_FromStringAndSize(std::ptr::null(), len as Py_ssize_t);
+            if chunk.is_null() {
+                self.failed = true;
+                return Err(std::io::Error::from(std::io::ErrorKind::Other));
+            }
+            let ret = pyobject_call_method_one_arg(self.fp, (*self.state).readinto_str, chunk);
+            let n = if ret.is_null() {
+                -1
+            } else {
+                let n = PyLong_AsSsize_t(ret);
+                Py_DECREF(ret);
+                n
+            };
+            if n == -1 && !PyErr_Occurred().is_null() {
+                Py_DECREF(chunk);
+                self.failed = true;
+                return Err(std::io::Error::from(std::io::ErrorKind::Other));
+            }
+            if n <= 0 {
+                self.exhausted = true;
+            } else {
+                let n = (n as usize).min(PyByteArray_Size(chunk) as usize);
+                let data = std::slice::from_raw_parts(PyByteArray_AsString(chunk).cast::<u8>(), n);
+                self.buf.extend_from_slice(data);
+            }
+            Py_DECREF(chunk);
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, len: usize) -> Result<(), std::io::Error> {
+        if unlikely!(self.failed) {
+            return Err(std::io::Error::from(std::io::ErrorKind::Other));
+        }
+        if self.pos != 0 {
+            self.buf.drain(..self.pos);
+            self.consumed += self.pos;
+            self.pos = 0;
+        }
+        while self.buf.len() < len {
+            if self.exhausted {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+            }
+            // Grow geometrically rather than trusting the requested length, so
+            // that a corrupt length prefix does not cause a huge allocation.
+            let missing = len - self.buf.len();
+            let chunk_len = if self.seekable {
+                missing.min(self.buf.len()).max(CHUNK_LENGTH)
+            } else {
+                missing.min(self.buf.len().max(CHUNK_LENGTH))
+            };
+            self.read_chunk(chunk_len)?;
+        }
+        Ok(())
+    }
+}
+
+impl Read for StreamReader {
+    fn eof(&self) -> bool {
+        self.exhausted && self.pos == self.buf.len()
+    }
+
+    fn position(&self) -> usize {
+        self.consumed + self.pos
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<&[u8; N], std::io::Error> {
+        if self.remaining() < N {
+            self.fill(N)?;
+        }
+        let value = self.buf[self.pos..].first_chunk().unwrap();
+        self.pos += N;
+        Ok(value)
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&[u8], std::io::Error> {
+        if self.remaining() < len {
+            self.fill(len)?;
+        }
+        let value = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(value)
+    }
+}
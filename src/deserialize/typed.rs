@@ -0,0 +1,1061 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::deserialize::duration::{parse_duration, timedelta_from_microseconds};
+use crate::state::{State, TypingObjects};
+use pyo3::ffi::*;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_void;
+use std::ptr::NonNull;
+
+const PLAN_CAPSULE_NAME: &CStr = c"ormsgpack.TypePlan";
+
+pub(crate) struct Field {
+    pub name: *mut PyObject,
+    pub node: usize,
+    pub required: bool,
+}
+
+pub(crate) enum Node {
+    Any,
+    Bool,
+    Bytes,
+    Complex,
+    Dataclass(Vec<Field>),
+    Decimal,
+    Dict(usize, usize),
+    Float,
+    Fraction,
+    Instance,
+    Int,
+    List(usize),
+    Literal(Vec<*mut PyObject>),
+    NamedTuple(Vec<usize>, usize),
+    None,
+    Str,
+    Timedelta,
+    Tuple(Vec<usize>),
+    TypedDict(Vec<Field>),
+    Union(Vec<usize>),
+    VarTuple(usize),
+}
+
+/// A type compiled to a graph of nodes, each paired with the type it was
+/// compiled from. Classes are compiled once and referenced by index, so
+/// recursive types are supported.
+pub(crate) struct Plan {
+    nodes: Vec<(*mut PyObject, Node)>,
+    root: usize,
+}
+
+impl Plan {
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// Returns the node `index` and the type it was compiled from.
+    pub fn node(&self, index: usize) -> (*mut PyObject, &Node) {
+        let (tp, ref node) = self.nodes[index];
+        (tp, node)
+    }
+
+    /// Returns the alternative of a union other than `None`, if `None` is
+    /// one of `alternatives` and there is a single other one.
+    pub fn optional(&self, alternatives: &[usize]) -> Option<usize> {
+        let is_none = |&index: &usize| matches!(self.nodes[index].1, Node::None);
+        let mut others = alternatives.iter().filter(|index| !is_none(index));
+        match (others.next(), others.next()) {
+            (Some(&index), None) if alternatives.iter().any(is_none) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+/// A reference to a plan, which keeps its capsule alive.
+pub(crate) struct PlanRef {
+    capsule: NonNull<PyObject>,
+}
+
+impl std::ops::Deref for PlanRef {
+    type Target = Plan;
+
+    fn deref(&self) -> &Plan {
+        unsafe {
+            &*PyCapsule_GetPointer(self.capsule.as_ptr(), PLAN_CAPSULE_NAME.as_ptr()).cast::<Plan>()
+        }
+    }
+}
+
+impl Drop for PlanRef {
+    fn drop(&mut self) {
+        unsafe { Py_DECREF(self.capsule.as_ptr()) };
+    }
+}
+
+/// Returns the compiled plan for `tp`.
+pub(crate) fn plan(state: *mut State, tp: NonNull<PyObject>) -> Result<PlanRef, String> {
+    get_type_plan(state, tp.as_ptr()).map(|capsule| PlanRef { capsule })
+}
+
+impl Drop for Plan {
+    fn drop(&mut self) {
+        for (tp, node) in &self.nodes {
+            unsafe {
+                Py_DECREF(*tp);
+                match node {
+                    Node::Dataclass(fields) | Node::TypedDict(fields) => {
+                        for field in fields {
+                            Py_DECREF(field.name);
+                        }
+                    }
+                    Node::Literal(values) => {
+                        for value in values {
+                            Py_DECREF(*value);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn plan_capsule_destructor(capsule: *mut PyObject) {
+    let ptr = PyCapsule_GetPointer(capsule, PLAN_CAPSULE_NAME.as_ptr());
+    drop(Box::from_raw(ptr.cast::<Plan>()));
+}
+
+/// The maximum number of plans cached in the module state. Types that are
+/// created dynamically, such as generic aliases or local classes, would
+/// otherwise accumulate in the cache.
+const MAX_TYPE_PLANS: Py_ssize_t = 256;
+
+/// Returns a capsule holding the compiled plan for `tp`. Plans are cached in
+/// the module state, keyed by type, and the oldest plan is evicted when the
+/// cache is full.
+pub fn get_type_plan(state: *mut State, tp: *mut PyObject) -> Result<NonNull<PyObject>, String> {
+    unsafe {
+        let cache = (*state).type_plans;
+        let mut capsule: *mut PyObject = std::ptr::null_mut();
+        let found = compat::PyDict_GetItemRef(cache, tp, &mut capsule);
+        if found == 1 {
+            return Ok(NonNull::new_unchecked(capsule));
+        }
+        let hashable = found == 0;
+        PyErr_Clear();
+
+        let mut compiler = Compiler {
+            state,
+            typing: (*state).get_typing_objects(),
+            plan: Plan {
+                nodes: Vec::new(),
+                root: 0,
+            },
+            classes: HashMap::new(),
+        };
+        compiler.plan.root = compiler.compile(tp)?;
+        let plan = Box::into_raw(Box::new(compiler.plan));
+        let capsule = PyCapsule_New(
+            plan.cast::<c_void>(),
+            PLAN_CAPSULE_NAME.as_ptr(),
+            Some(plan_capsule_destructor),
+        );
+        if capsule.is_null() {
+            PyErr_Clear();
+            drop(Box::from_raw(plan));
+            return Err(String::from("type plan allocation failed"));
+        }
+        if hashable {
+            if PyDict_Size(cache) >= MAX_TYPE_PLANS {
+                evict_oldest(cache);
+            }
+            if PyDict_SetItem(cache, tp, capsule) == -1 {
+                PyErr_Clear();
+            }
+        }
+        Ok(NonNull::new_unchecked(capsule))
+    }
+}
+
+/// Removes the first inserted item of `dict`.
+unsafe fn evict_oldest(dict: *mut PyObject) {
+    let mut pos: Py_ssize_t = 0;
+    let mut key: *mut PyObject = std::ptr::null_mut();
+    let mut value: *mut PyObject = std::ptr::null_mut();
+    if PyDict_Next(dict, &mut pos, &mut key, &mut value) != 0 {
+        Py_INCREF(key);
+        if PyDict_DelItem(dict, key) == -1 {
+            PyErr_Clear();
+        }
+        Py_DECREF(key);
+    }
+}
+
+#[cold]
+pub(crate) unsafe fn type_name(tp: *mut PyObject) -> String {
+    if PyType_Check(tp) != 0 {
+        let name = CStr::from_ptr((*tp.cast::<PyTypeObject>()).tp_name);
+        return name.to_string_lossy().into_owned();
+    }
+    object_repr(tp)
+}
+
+#[cold]
+pub(crate) unsafe fn object_repr(obj: *mut PyObject) -> String {
+    let repr = PyObject_Repr(obj);
+    if repr.is_null() {
+        PyErr_Clear();
+        return String::from("<unknown>");
+    }
+    let mut size: Py_ssize_t = 0;
+    let ptr = PyUnicode_AsUTF8AndSize(repr, &mut size);
+    let res = if ptr.is_null() {
+        PyErr_Clear();
+        String::from("<unknown>")
+    } else {
+        let bytes = std::slice::from_raw_parts(ptr.cast::<u8>(), size as usize);
+        String::from_utf8_lossy(bytes).into_owned()
+    };
+    Py_DECREF(repr);
+    res
+}
+
+struct Compiler<'a> {
+    state: *mut State,
+    typing: &'a TypingObjects,
+    plan: Plan,
+    classes: HashMap<*mut PyObject, usize>,
+}
+
+impl Compiler<'_> {
+    unsafe fn push(&mut self, tp: *mut PyObject, node: Node) -> usize {
+        Py_INCREF(tp);
+        self.plan.nodes.push((tp, node));
+        self.plan.nodes.len() - 1
+    }
+
+    unsafe fn compile_arg(&mut self, args: *mut PyObject, i: Py_ssize_t) -> Result<usize, String> {
+        if i < PyTuple_Size(args) {
+            self.compile(PyTuple_GetItem(args, i))
+        } else {
+            Ok(self.push(self.typing.any, Node::Any))
+        }
+    }
+
+    unsafe fn compile(&mut self, tp: *mut PyObject) -> Result<usize, String> {
+        let typing = self.typing;
+        if tp == typing.any || tp == (&raw mut PyBaseObject_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Any));
+        }
+        if tp == Py_None() || tp == typing.none_type {
+            return Ok(self.push(tp, Node::None));
+        }
+        if tp == (&raw mut PyBool_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Bool));
+        }
+        if tp == (&raw mut PyBytes_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Bytes));
+        }
+        if tp == (&raw mut PyFloat_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Float));
+        }
+        if tp == (&raw mut PyLong_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Int));
+        }
+        if tp == (&raw mut PyUnicode_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Str));
+        }
+        if tp == (&raw mut PyComplex_Type).cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Complex));
+        }
+        if tp == (*self.state).imported_decimal_type().cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Decimal));
+        }
+        if tp == (*self.state).imported_fraction_type().cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Fraction));
+        }
+        if tp == (*PyDateTimeAPI()).DeltaType.cast::<PyObject>() {
+            return Ok(self.push(tp, Node::Timedelta));
+        }
+
+        let origin = crate::ffi::pyobject_call_one_arg(typing.get_origin, tp);
+        if origin.is_null() {
+            PyErr_Clear();
+            return Err(format!("Invalid type {}", type_name(tp)));
+        }
+        if origin != Py_None() {
+            let args = crate::ffi::pyobject_call_one_arg(typing.get_args, tp);
+            let res = if args.is_null() {
+                PyErr_Clear();
+                Err(format!("Invalid type {}", type_name(tp)))
+            } else {
+                let res = self.compile_generic(tp, origin, args);
+                Py_DECREF(args);
+                res
+            };
+            Py_DECREF(origin);
+            return res;
+        }
+        Py_DECREF(origin);
+
+        if PyType_Check(tp) != 0 {
+            return self.compile_class(tp);
+        }
+        Err(format!("Invalid type {}", type_name(tp)))
+    }
+
+    unsafe fn compile_generic(
+        &mut self,
+        tp: *mut PyObject,
+        origin: *mut PyObject,
+        args: *mut PyObject,
+    ) -> Result<usize, String> {
+        let typing = self.typing;
+        let len = PyTuple_Size(args);
+        let node = if origin == (&raw mut PyList_Type).cast::<PyObject>() {
+            Node::List(self.compile_arg(args, 0)?)
+        } else if origin == (&raw mut PyDict_Type).cast::<PyObject>() {
+            Node::Dict(self.compile_arg(args, 0)?, self.compile_arg(args, 1)?)
+        } else if origin == (&raw mut PyTuple_Type).cast::<PyObject>() {
+            if len == 2 && PyTuple_GetItem(args, 1) == Py_Ellipsis() {
+                Node::VarTuple(self.compile_arg(args, 0)?)
+            } else {
+                let mut items = Vec::with_capacity(len as usize);
+                for i in 0..len {
+                    items.push(self.compile_arg(args, i)?);
+                }
+                Node::Tuple(items)
+            }
+        } else if origin == typing.union || origin == typing.union_type {
+            let mut alternatives = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                alternatives.push(self.compile_arg(args, i)?);
+            }
+            Node::Union(alternatives)
+        } else if origin == typing.literal {
+            let mut values = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                let value = PyTuple_GetItem(args, i);
+                Py_INCREF(value);
+                values.push(value);
+            }
+            Node::Literal(values)
+        } else {
+            return Err(format!("Invalid type {}", type_name(tp)));
+        };
+        Ok(self.push(tp, node))
+    }
+
+    unsafe fn compile_class(&mut self, tp: *mut PyObject) -> Result<usize, String> {
+        if let Some(&index) = self.classes.get(&tp) {
+            return Ok(index);
+        }
+        let any = self.typing.any;
+        if tp == (&raw mut PyList_Type).cast::<PyObject>() {
+            let item = self.push(any, Node::Any);
+            return Ok(self.push(tp, Node::List(item)));
+        }
+        if tp == (&raw mut PyDict_Type).cast::<PyObject>() {
+            let key = self.push(any, Node::Any);
+            let value = self.push(any, Node::Any);
+            return Ok(self.push(tp, Node::Dict(key, value)));
+        }
+        if tp == (&raw mut PyTuple_Type).cast::<PyObject>() {
+            let item = self.push(any, Node::Any);
+            return Ok(self.push(tp, Node::VarTuple(item)));
+        }
+
+        let is_dataclass = PyObject_HasAttr(tp, (*self.state).dataclass_fields_str) == 1;
+        let is_namedtuple = !is_dataclass
+            && PyType_IsSubtype(tp.cast::<PyTypeObject>(), &raw mut PyTuple_Type) != 0
+            && PyObject_HasAttrString(tp, c"_fields".as_ptr()) == 1;
+        let is_typeddict = !is_dataclass && !is_namedtuple && {
+            let res = crate::ffi::pyobject_call_one_arg(self.typing.is_typeddict, tp);
+            let is_true = res == Py_True();
+            Py_XDECREF(res);
+            is_true
+        };
+        PyErr_Clear();
+        if !(is_dataclass || is_namedtuple || is_typeddict) {
+            return Ok(self.push(tp, Node::Instance));
+        }
+
+        // The node is added before the fields are compiled so that the fields
+        // can refer to the class.
+        let index = self.push(tp, Node::Any);
+        self.classes.insert(tp, index);
+        let hints = crate::ffi::pyobject_call_one_arg(self.typing.get_type_hints, tp);
+        if hints.is_null() {
+            return Err(format!(
+                "Invalid type {}: type hints cannot be resolved",
+                type_name(tp)
+            ));
+        }
+        let res = if is_dataclass {
+            self.compile_dataclass(tp, hints).map(Node::Dataclass)
+        } else if is_namedtuple {
+            self.compile_namedtuple(tp, hints)
+        } else {
+            self.compile_typeddict(tp, hints).map(Node::TypedDict)
+        };
+        Py_DECREF(hints);
+        self.plan.nodes[index].1 = res?;
+        Ok(index)
+    }
+
+    unsafe fn compile_hint(
+        &mut self,
+        hints: *mut PyObject,
+        name: *mut PyObject,
+    ) -> Result<usize, String> {
+        let hint = PyDict_GetItemWithError(hints, name);
+        if hint.is_null() {
+            PyErr_Clear();
+            Ok(self.push(self.typing.any, Node::Any))
+        } else {
+            self.compile(hint)
+        }
+    }
+
+    unsafe fn compile_dataclass(
+        &mut self,
+        tp: *mut PyObject,
+        hints: *mut PyObject,
+    ) -> Result<Vec<Field>, String> {
+        let state = self.state;
+        let missing = self.typing.missing;
+        let dataclass_fields = PyObject_GetAttr(tp, (*state).dataclass_fields_str);
+        if dataclass_fields.is_null() || PyDict_Check(dataclass_fields) == 0 {
+            PyErr_Clear();
+            Py_XDECREF(dataclass_fields);
+            return Err(format!("Invalid type {}", type_name(tp)));
+        }
+        let mut fields: Vec<Field> = Vec::new();
+        let mut pos: Py_ssize_t = 0;
+        let mut name: *mut PyObject = std::ptr::null_mut();
+        let mut field: *mut PyObject = std::ptr::null_mut();
+        while PyDict_Next(dataclass_fields, &mut pos, &mut name, &mut field) != 0 {
+            let field_type = PyObject_GetAttr(field, (*state).field_type_str);
+            let init = PyObject_GetAttrString(field, c"init".as_ptr());
+            let default = PyObject_GetAttr(field, (*state).default_str);
+            let default_factory = PyObject_GetAttrString(field, c"default_factory".as_ptr());
+            PyErr_Clear();
+            let include = field_type == (*state).dataclass_field_type.cast::<PyObject>()
+                && !init.is_null()
+                && PyObject_IsTrue(init) == 1;
+            let required = default == missing && default_factory == missing;
+            Py_XDECREF(field_type);
+            Py_XDECREF(init);
+            Py_XDECREF(default);
+            Py_XDECREF(default_factory);
+            PyErr_Clear();
+            if !include {
+                continue;
+            }
+            let node = match self.compile_hint(hints, name) {
+                Ok(node) => node,
+                Err(err) => {
+                    Py_DECREF(dataclass_fields);
+                    for field in fields {
+                        Py_DECREF(field.name);
+                    }
+                    return Err(err);
+                }
+            };
+            Py_INCREF(name);
+            fields.push(Field {
+                name,
+                node,
+                required,
+            });
+        }
+        Py_DECREF(dataclass_fields);
+        Ok(fields)
+    }
+
+    unsafe fn compile_namedtuple(
+        &mut self,
+        tp: *mut PyObject,
+        hints: *mut PyObject,
+    ) -> Result<Node, String> {
+        let names = PyObject_GetAttrString(tp, c"_fields".as_ptr());
+        let defaults = PyObject_GetAttrString(tp, c"_field_defaults".as_ptr());
+        if names.is_null() || PyTuple_Check(names) == 0 || defaults.is_null() {
+            PyErr_Clear();
+            Py_XDECREF(names);
+            Py_XDECREF(defaults);
+            return Err(format!("Invalid type {}", type_name(tp)));
+        }
+        let len = PyTuple_Size(names);
+        let mut res = Ok(Vec::with_capacity(len as usize));
+        for i in 0..len {
+            match self.compile_hint(hints, PyTuple_GetItem(names, i)) {
+                Ok(node) => res.as_mut().unwrap().push(node),
+                Err(err) => {
+                    res = Err(err);
+                    break;
+                }
+            }
+        }
+        let required = len as usize - PyObject_Size(defaults).max(0) as usize;
+        PyErr_Clear();
+        Py_DECREF(names);
+        Py_DECREF(defaults);
+        res.map(|fields| Node::NamedTuple(fields, required))
+    }
+
+    unsafe fn compile_typeddict(
+        &mut self,
+        tp: *mut PyObject,
+        hints: *mut PyObject,
+    ) -> Result<Vec<Field>, String> {
+        let required_keys = PyObject_GetAttrString(tp, c"__required_keys__".as_ptr());
+        if required_keys.is_null() {
+            PyErr_Clear();
+            return Err(format!("Invalid type {}", type_name(tp)));
+        }
+        let mut fields = Vec::new();
+        let mut res = Ok(());
+        let mut pos: Py_ssize_t = 0;
+        let mut name: *mut PyObject = std::ptr::null_mut();
+        let mut hint: *mut PyObject = std::ptr::null_mut();
+        while PyDict_Next(hints, &mut pos, &mut name, &mut hint) != 0 {
+            match self.compile(hint) {
+                Ok(node) => {
+                    Py_INCREF(name);
+                    fields.push(Field {
+                        name,
+                        node,
+                        required: PySequence_Contains(required_keys, name) == 1,
+                    });
+                }
+                Err(err) => {
+                    res = Err(err);
+                    break;
+                }
+            }
+        }
+        PyErr_Clear();
+        Py_DECREF(required_keys);
+        if let Err(err) = res {
+            for field in fields {
+                Py_DECREF(field.name);
+            }
+            return Err(err);
+        }
+        Ok(fields)
+    }
+}
+
+enum Segment {
+    Field(*mut PyObject),
+    Index(usize),
+    /// A map key, of which a reference is held.
+    Key(*mut PyObject),
+}
+
+/// Converts deserialized objects with the nodes of a plan. On error, the path
+/// of the object that could not be converted is kept, relative to the object
+/// passed to `convert`.
+pub(crate) struct Converter<'a> {
+    plan: &'a Plan,
+    path: Vec<Segment>,
+}
+
+impl Drop for Segment {
+    fn drop(&mut self) {
+        if let Segment::Key(key) = *self {
+            unsafe { Py_DECREF(key) };
+        }
+    }
+}
+
+impl<'a> Converter<'a> {
+    pub fn new(plan: &'a Plan) -> Self {
+        Converter {
+            plan,
+            path: Vec::new(),
+        }
+    }
+
+    /// Returns the map keys, field names and array indices that lead to the
+    /// object that could not be converted, innermost first.
+    #[cold]
+    pub fn take_path(&mut self) -> Vec<NonNull<PyObject>> {
+        let mut res = Vec::with_capacity(self.path.len());
+        for segment in self.path.drain(..).rev() {
+            let obj = unsafe {
+                match segment {
+                    Segment::Field(name) => {
+                        Py_INCREF(name);
+                        name
+                    }
+                    Segment::Index(index) => PyLong_FromSize_t(index),
+                    Segment::Key(key) => {
+                        Py_INCREF(key);
+                        key
+                    }
+                }
+            };
+            if let Some(obj) = NonNull::new(obj) {
+                res.push(obj);
+            }
+        }
+        res
+    }
+
+    /// Releases `obj` and returns the error for a value whose type does not
+    /// match the type `tp`.
+    #[cold]
+    unsafe fn mismatch(&self, tp: *mut PyObject, obj: *mut PyObject) -> String {
+        PyErr_Clear();
+        let got = type_name(ob_type!(obj).cast::<PyObject>());
+        Py_DECREF(obj);
+        format!("expected {}, got {}", type_name(tp), got)
+    }
+
+    /// Releases `obj` and returns the error for a value that does not match
+    /// the literal type `tp`.
+    #[cold]
+    unsafe fn value_mismatch(&self, tp: *mut PyObject, obj: *mut PyObject) -> String {
+        PyErr_Clear();
+        let got = object_repr(obj);
+        Py_DECREF(obj);
+        format!("expected {}, got {}", type_name(tp), got)
+    }
+
+    /// Converts `obj` with the node `index`. The reference to `obj` is
+    /// consumed, also on error.
+    pub unsafe fn convert(
+        &mut self,
+        index: usize,
+        obj: *mut PyObject,
+    ) -> Result<*mut PyObject, String> {
+        let plan = self.plan;
+        let (tp, ref node) = plan.nodes[index];
+        match *node {
+            Node::Any => Ok(obj),
+            Node::Bool => {
+                if PyBool_Check(obj) != 0 {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Bytes => {
+                if PyBytes_Check(obj) != 0
+                    || PyByteArray_Check(obj) != 0
+                    || PyMemoryView_Check(obj) != 0
+                {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Complex => {
+                if PyComplex_Check(obj) != 0 {
+                    return Ok(obj);
+                }
+                let parts = match pair(obj) {
+                    Some((real, imag)) if is_real(real) && is_real(imag) => (real, imag),
+                    _ => return Err(self.mismatch(tp, obj)),
+                };
+                let real = PyFloat_AsDouble(parts.0);
+                let imag = PyFloat_AsDouble(parts.1);
+                if !PyErr_Occurred().is_null() {
+                    return Err(self.mismatch(tp, obj));
+                }
+                Py_DECREF(obj);
+                Ok(PyComplex_FromDoubles(real, imag))
+            }
+            Node::Decimal => {
+                if PyObject_IsInstance(obj, tp) == 1 {
+                    return Ok(obj);
+                }
+                if PyUnicode_Check(obj) == 0 && !is_int(obj) {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let res = crate::ffi::pyobject_call_one_arg(tp, obj);
+                Py_DECREF(obj);
+                self.constructed(tp, res)
+            }
+            Node::Fraction => {
+                if PyObject_IsInstance(obj, tp) == 1 {
+                    return Ok(obj);
+                }
+                let res = if is_int(obj) {
+                    crate::ffi::pyobject_call_one_arg(tp, obj)
+                } else {
+                    match pair(obj) {
+                        Some((numerator, denominator))
+                            if is_int(numerator) && is_int(denominator) =>
+                        {
+                            PyObject_CallFunctionObjArgs(
+                                tp,
+                                numerator,
+                                denominator,
+                                std::ptr::null_mut::<PyObject>(),
+                            )
+                        }
+                        _ => return Err(self.mismatch(tp, obj)),
+                    }
+                };
+                Py_DECREF(obj);
+                self.constructed(tp, res)
+            }
+            Node::Timedelta => {
+                if PyObject_IsInstance(obj, tp) == 1 {
+                    return Ok(obj);
+                }
+                let microseconds = if PyUnicode_Check(obj) != 0 {
+                    match crate::ffi::unicode_to_str(obj) {
+                        Ok(value) => parse_duration(value.as_bytes()),
+                        Err(_) => None,
+                    }
+                } else if is_int(obj) {
+                    // Values out of range fail the construction.
+                    let mut overflow = 0;
+                    let value = PyLong_AsLongLongAndOverflow(obj, &mut overflow);
+                    Some(if overflow == 0 {
+                        value.into()
+                    } else {
+                        i128::MAX
+                    })
+                } else if PyFloat_Check(obj) != 0 {
+                    let value = (PyFloat_AS_DOUBLE(obj) * 1_000_000.0).round_ties_even();
+                    Some(if value.is_finite() {
+                        value as i128
+                    } else {
+                        i128::MAX
+                    })
+                } else {
+                    None
+                };
+                match microseconds {
+                    Some(microseconds) => {
+                        Py_DECREF(obj);
+                        let res = timedelta_from_microseconds(microseconds)
+                            .map_or(std::ptr::null_mut(), NonNull::as_ptr);
+                        self.constructed(tp, res)
+                    }
+                    None => Err(self.mismatch(tp, obj)),
+                }
+            }
+            Node::Float => {
+                if PyFloat_Check(obj) != 0 {
+                    Ok(obj)
+                } else if PyLong_Check(obj) != 0 && PyBool_Check(obj) == 0 {
+                    let value = PyNumber_Float(obj);
+                    if value.is_null() {
+                        return Err(self.mismatch(tp, obj));
+                    }
+                    Py_DECREF(obj);
+                    Ok(value)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Instance => {
+                if PyObject_IsInstance(obj, tp) == 1 {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Int => {
+                if PyLong_Check(obj) != 0 && PyBool_Check(obj) == 0 {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Literal(ref values) => {
+                for &value in values {
+                    if ob_type!(value) == ob_type!(obj)
+                        && PyObject_RichCompareBool(obj, value, Py_EQ) == 1
+                    {
+                        return Ok(obj);
+                    }
+                }
+                Err(self.value_mismatch(tp, obj))
+            }
+            Node::None => {
+                if obj == Py_None() {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Str => {
+                if PyUnicode_Check(obj) != 0 {
+                    Ok(obj)
+                } else {
+                    Err(self.mismatch(tp, obj))
+                }
+            }
+            Node::Union(ref alternatives) => self.convert_union(tp, alternatives, obj),
+            Node::List(item) => {
+                if PyList_Check(obj) == 0 && PyTuple_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let len = PySequence_Size(obj);
+                let list = PyList_New(len);
+                for i in 0..len {
+                    match self.convert_item(item, obj, i) {
+                        Ok(value) => {
+                            PyList_SetItem(list, i, value);
+                        }
+                        Err(err) => {
+                            Py_DECREF(list);
+                            Py_DECREF(obj);
+                            return Err(err);
+                        }
+                    }
+                }
+                Py_DECREF(obj);
+                Ok(list)
+            }
+            Node::VarTuple(item) => {
+                if PyList_Check(obj) == 0 && PyTuple_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                self.convert_tuple(obj, |_| item)
+            }
+            Node::Tuple(ref items) => {
+                if PyList_Check(obj) == 0 && PyTuple_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let len = PySequence_Size(obj) as usize;
+                if len != items.len() {
+                    Py_DECREF(obj);
+                    return Err(array_length_mismatch(items.len(), len));
+                }
+                self.convert_tuple(obj, |i| items[i])
+            }
+            Node::NamedTuple(ref fields, required) => {
+                if PyList_Check(obj) == 0 && PyTuple_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let len = PySequence_Size(obj) as usize;
+                if len < required || len > fields.len() {
+                    Py_DECREF(obj);
+                    return Err(array_length_range_mismatch(required, fields.len(), len));
+                }
+                let args = self.convert_tuple(obj, |i| fields[i])?;
+                let res = PyObject_Call(tp, args, std::ptr::null_mut());
+                Py_DECREF(args);
+                self.constructed(tp, res)
+            }
+            Node::Dict(key_node, value_node) => {
+                if PyDict_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let dict = PyDict_New();
+                let mut pos: Py_ssize_t = 0;
+                let mut key: *mut PyObject = std::ptr::null_mut();
+                let mut value: *mut PyObject = std::ptr::null_mut();
+                while PyDict_Next(obj, &mut pos, &mut key, &mut value) != 0 {
+                    Py_INCREF(key);
+                    self.path.push(Segment::Key(key));
+                    Py_INCREF(key);
+                    Py_INCREF(value);
+                    let res = self.convert(key_node, key).and_then(|key| {
+                        match self.convert(value_node, value) {
+                            Ok(value) => Ok((key, value)),
+                            Err(err) => {
+                                Py_DECREF(key);
+                                Err(err)
+                            }
+                        }
+                    });
+                    match res {
+                        Ok((key, value)) => {
+                            PyDict_SetItem(dict, key, value);
+                            Py_DECREF(key);
+                            Py_DECREF(value);
+                        }
+                        Err(err) => {
+                            Py_DECREF(dict);
+                            Py_DECREF(obj);
+                            return Err(err);
+                        }
+                    }
+                    self.path.pop();
+                }
+                Py_DECREF(obj);
+                Ok(dict)
+            }
+            Node::Dataclass(ref fields) => {
+                if PyDict_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                let kwargs = self.convert_fields(fields, obj)?;
+                let args = PyTuple_New(0);
+                let res = PyObject_Call(tp, args, kwargs);
+                Py_DECREF(args);
+                Py_DECREF(kwargs);
+                self.constructed(tp, res)
+            }
+            Node::TypedDict(ref fields) => {
+                if PyDict_Check(obj) == 0 {
+                    return Err(self.mismatch(tp, obj));
+                }
+                self.convert_fields(fields, obj)
+            }
+        }
+    }
+
+    unsafe fn convert_item(
+        &mut self,
+        index: usize,
+        seq: *mut PyObject,
+        i: Py_ssize_t,
+    ) -> Result<*mut PyObject, String> {
+        let item = PySequence_GetItem(seq, i);
+        self.path.push(Segment::Index(i as usize));
+        let res = self.convert(index, item)?;
+        self.path.pop();
+        Ok(res)
+    }
+
+    unsafe fn convert_tuple(
+        &mut self,
+        obj: *mut PyObject,
+        node: impl Fn(usize) -> usize,
+    ) -> Result<*mut PyObject, String> {
+        let len = PySequence_Size(obj);
+        let tuple = PyTuple_New(len);
+        for i in 0..len {
+            match self.convert_item(node(i as usize), obj, i) {
+                Ok(value) => crate::ffi::pytuple_set_item(tuple, i, value),
+                Err(err) => {
+                    Py_DECREF(tuple);
+                    Py_DECREF(obj);
+                    return Err(err);
+                }
+            }
+        }
+        Py_DECREF(obj);
+        Ok(tuple)
+    }
+
+    /// Converts the fields of a map to a new dict, with the keys that are not
+    /// fields left out.
+    unsafe fn convert_fields(
+        &mut self,
+        fields: &[Field],
+        obj: *mut PyObject,
+    ) -> Result<*mut PyObject, String> {
+        let dict = PyDict_New();
+        for field in fields {
+            let value = PyDict_GetItemWithError(obj, field.name);
+            if value.is_null() {
+                PyErr_Clear();
+                if field.required {
+                    Py_DECREF(dict);
+                    Py_DECREF(obj);
+                    return Err(missing_field(field.name));
+                }
+                continue;
+            }
+            Py_INCREF(value);
+            self.path.push(Segment::Field(field.name));
+            match self.convert(field.node, value) {
+                Ok(value) => {
+                    PyDict_SetItem(dict, field.name, value);
+                    Py_DECREF(value);
+                }
+                Err(err) => {
+                    Py_DECREF(dict);
+                    Py_DECREF(obj);
+                    return Err(err);
+                }
+            }
+            self.path.pop();
+        }
+        Py_DECREF(obj);
+        Ok(dict)
+    }
+
+    unsafe fn convert_union(
+        &mut self,
+        tp: *mut PyObject,
+        alternatives: &[usize],
+        obj: *mut PyObject,
+    ) -> Result<*mut PyObject, String> {
+        let plan = self.plan;
+        let is_none = |&index: &usize| matches!(plan.nodes[index].1, Node::None);
+        if obj == Py_None() && alternatives.iter().any(is_none) {
+            return Ok(obj);
+        }
+        // With a single alternative besides None, such as Optional[T], the
+        // error of the alternative is more useful than a generic one.
+        if let Some(index) = plan.optional(alternatives) {
+            return self.convert(index, obj);
+        }
+        let depth = self.path.len();
+        for &index in alternatives {
+            Py_INCREF(obj);
+            if let Ok(value) = self.convert(index, obj) {
+                Py_DECREF(obj);
+                return Ok(value);
+            }
+            PyErr_Clear();
+            self.path.truncate(depth);
+        }
+        Err(self.mismatch(tp, obj))
+    }
+
+    /// Returns the result of calling the constructor of `tp`. The exception
+    /// raised by the constructor, if any, is left set.
+    unsafe fn constructed(
+        &self,
+        tp: *mut PyObject,
+        res: *mut PyObject,
+    ) -> Result<*mut PyObject, String> {
+        if res.is_null() {
+            Err(construction_failed(tp))
+        } else {
+            Ok(res)
+        }
+    }
+}
+
+unsafe fn is_int(obj: *mut PyObject) -> bool {
+    PyLong_Check(obj) != 0 && PyBool_Check(obj) == 0
+}
+
+unsafe fn is_real(obj: *mut PyObject) -> bool {
+    PyFloat_Check(obj) != 0 || is_int(obj)
+}
+
+/// Returns the items of `obj`, if it is a list or a tuple of two items.
+unsafe fn pair(obj: *mut PyObject) -> Option<(*mut PyObject, *mut PyObject)> {
+    if PyList_Check(obj) != 0 && PyList_Size(obj) == 2 {
+        Some((PyList_GetItem(obj, 0), PyList_GetItem(obj, 1)))
+    } else if PyTuple_Check(obj) != 0 && PyTuple_Size(obj) == 2 {
+        Some((PyTuple_GetItem(obj, 0), PyTuple_GetItem(obj, 1)))
+    } else {
+        None
+    }
+}
+
+#[cold]
+pub(crate) fn array_length_mismatch(expected: usize, len: usize) -> String {
+    format!("expected array of length {expected}, got {len}")
+}
+
+#[cold]
+pub(crate) fn array_length_range_mismatch(required: usize, max: usize, len: usize) -> String {
+    format!("expected array of length {required} to {max}, got {len}")
+}
+
+#[cold]
+pub(crate) unsafe fn missing_field(name: *mut PyObject) -> String {
+    format!("missing required field {}", object_repr(name))
+}
+
+#[cold]
+pub(crate) unsafe fn construction_failed(tp: *mut PyObject) -> String {
+    format!("{} construction failed", type_name(tp))
+}
@@ -40,6 +40,58 @@ pub fn pylong_to_u64(op: *mut PyObject) -> Option<u64> {
     }
 }
 
+/// Returns the two's complement representation of an int in big-endian
+/// byte order, in the smallest number of bytes.
+pub fn pylong_to_be_bytes(op: *mut PyObject) -> Vec<u8> {
+    unsafe {
+        let len = _PyLong_NumBits(op) / 8 + 1;
+        let mut bytes = vec![0u8; len];
+        #[cfg(all(Py_3_13, not(any(PyPy, GraalPy))))]
+        PyLong_AsNativeBytes(
+            op,
+            bytes.as_mut_ptr().cast(),
+            len as Py_ssize_t,
+            Py_ASNATIVEBYTES_BIG_ENDIAN,
+        );
+        #[cfg(not(all(Py_3_13, not(any(PyPy, GraalPy)))))]
+        _PyLong_AsByteArray(op.cast::<PyLongObject>(), bytes.as_mut_ptr(), len, 0, 1);
+        let start = redundant_sign_bytes(&bytes);
+        bytes.drain(..start);
+        bytes
+    }
+}
+
+/// Returns the number of leading bytes of a two's complement representation
+/// in big-endian byte order that only extend the sign.
+fn redundant_sign_bytes(bytes: &[u8]) -> usize {
+    bytes
+        .windows(2)
+        .take_while(|pair| {
+            (pair[0] == 0x00 && pair[1] < 0x80) || (pair[0] == 0xff && pair[1] >= 0x80)
+        })
+        .count()
+}
+
+/// Creates an int from its two's complement representation in big-endian
+/// byte order.
+#[cfg(all(Py_3_13, not(any(PyPy, GraalPy))))]
+pub fn pylong_from_be_bytes(bytes: &[u8]) -> *mut PyObject {
+    unsafe {
+        PyLong_FromNativeBytes(
+            bytes.as_ptr().cast(),
+            bytes.len(),
+            Py_ASNATIVEBYTES_BIG_ENDIAN,
+        )
+    }
+}
+
+/// Creates an int from its two's complement representation in big-endian
+/// byte order.
+#[cfg(not(all(Py_3_13, not(any(PyPy, GraalPy)))))]
+pub fn pylong_from_be_bytes(bytes: &[u8]) -> *mut PyObject {
+    unsafe { _PyLong_FromByteArray(bytes.as_ptr(), bytes.len(), 0, 1) }
+}
+
 // https://tools.ietf.org/html/rfc7159#section-6
 // "[-(2**53)+1, (2**53)-1]"
 
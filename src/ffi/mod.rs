@@ -71,6 +71,52 @@ pub unsafe fn pymemoryview_as_bytes(op: *mut PyObject) -> Option<&'static [u8]>
     }
 }
 
+/// Takes the exception that is set, if any, as a normalized exception
+/// instance.
+#[cfg(Py_3_12)]
+pub unsafe fn pyerr_take() -> *mut PyObject {
+    PyErr_GetRaisedException()
+}
+
+/// Takes the exception that is set, if any, as a normalized exception
+/// instance.
+#[cfg(not(Py_3_12))]
+pub unsafe fn pyerr_take() -> *mut PyObject {
+    let mut ptype = std::ptr::null_mut();
+    let mut pvalue = std::ptr::null_mut();
+    let mut ptraceback = std::ptr::null_mut();
+    PyErr_Fetch(&mut ptype, &mut pvalue, &mut ptraceback);
+    if ptype.is_null() {
+        return std::ptr::null_mut();
+    }
+    PyErr_NormalizeException(&mut ptype, &mut pvalue, &mut ptraceback);
+    if !ptraceback.is_null() {
+        PyException_SetTraceback(pvalue, ptraceback);
+        Py_DECREF(ptraceback);
+    }
+    Py_DECREF(ptype);
+    pvalue
+}
+
+/// Sets `exc`, a normalized exception instance taken by `pyerr_take`, as
+/// the current exception. The reference to `exc` is consumed.
+#[cfg(Py_3_12)]
+pub unsafe fn pyerr_restore(exc: *mut PyObject) {
+    PyErr_SetRaisedException(exc)
+}
+
+/// Sets `exc`, a normalized exception instance taken by `pyerr_take`, as
+/// the current exception. The reference to `exc` is consumed.
+#[cfg(not(Py_3_12))]
+pub unsafe fn pyerr_restore(exc: *mut PyObject) {
+    if exc.is_null() {
+        return;
+    }
+    let ptype = Py_TYPE(exc).cast::<PyObject>();
+    Py_INCREF(ptype);
+    PyErr_Restore(ptype, exc, PyException_GetTraceback(exc));
+}
+
 pub struct PyDictIter {
     op: *mut PyObject,
     pos: isize,
@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use pyo3::ffi::*;
+use std::ffi::CStr;
+use std::os::raw::c_char;
 
 #[derive(Debug)]
 pub enum UnicodeError {
@@ -15,6 +17,20 @@ impl std::fmt::Display for UnicodeError {
     }
 }
 
+/// Decodes UTF-8 data that is not valid, with the Python error handler
+/// `errors`.
+#[cold]
+#[inline(never)]
+pub fn unicode_from_invalid_utf8(buf: &[u8], errors: &CStr) -> *mut PyObject {
+    unsafe {
+        PyUnicode_DecodeUTF8(
+            buf.as_ptr().cast::<c_char>(),
+            buf.len() as Py_ssize_t,
+            errors.as_ptr(),
+        )
+    }
+}
+
 #[inline(never)]
 pub fn unicode_to_str_via_ffi(op: *mut PyObject) -> Result<&'static str, UnicodeError> {
     unsafe {
@@ -2,6 +2,9 @@
 
 pub trait Read {
     fn eof(&self) -> bool;
+    /// Returns the position of the next byte to read. The origin is
+    /// unspecified, only the difference of two positions is meaningful.
+    fn position(&self) -> usize;
     fn read_array<const N: usize>(&mut self) -> Result<&[u8; N], std::io::Error>;
     fn read_slice(&mut self, len: usize) -> Result<&[u8], std::io::Error>;
 
@@ -71,6 +74,11 @@ impl Read for &[u8] {
         self.is_empty()
     }
 
+    #[inline(always)]
+    fn position(&self) -> usize {
+        self.as_ptr() as usize
+    }
+
     fn read_array<const N: usize>(&mut self) -> Result<&[u8; N], std::io::Error> {
         let (a, b) = match self.split_first_chunk() {
             Some(value) => value,
@@ -90,6 +98,90 @@ impl Read for &[u8] {
     }
 }
 
+impl<T> Read for &mut T
+where
+    T: Read,
+{
+    fn eof(&self) -> bool {
+        (**self).eof()
+    }
+
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<&[u8; N], std::io::Error> {
+        (**self).read_array()
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&[u8], std::io::Error> {
+        (**self).read_slice(len)
+    }
+}
+
+/// A growable buffer of incoming data that is consumed incrementally.
+pub struct ReadBuffer {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl ReadBuffer {
+    pub fn new() -> Self {
+        ReadBuffer {
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    pub fn extend(&mut self, data: &[u8]) {
+        if self.pos != 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns the data that has not been read yet.
+    pub fn remaining(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    #[inline]
+    pub fn seek(&mut self, pos: usize) {
+        debug_assert!(pos <= self.buf.len());
+        self.pos = pos;
+    }
+}
+
+impl Read for ReadBuffer {
+    fn eof(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<&[u8; N], std::io::Error> {
+        let value = match self.buf[self.pos..].first_chunk() {
+            Some(value) => value,
+            None => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)),
+        };
+        self.pos += N;
+        Ok(value)
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&[u8], std::io::Error> {
+        let value = match self.buf[self.pos..].get(..len) {
+            Some(value) => value,
+            None => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)),
+        };
+        self.pos += len;
+        Ok(value)
+    }
+}
+
 pub trait WriteSlices: std::io::Write {
     fn write_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) -> Result<(), std::io::Error>;
 }
@@ -102,3 +194,14 @@ where
         (**self).write_slices(bufs)
     }
 }
+
+impl WriteSlices for Vec<u8> {
+    fn write_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) -> Result<(), std::io::Error> {
+        let len: usize = bufs.iter().map(|b| b.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend_from_slice(buf);
+        }
+        Ok(())
+    }
+}
@@ -13,19 +13,25 @@
 #[macro_use]
 mod util;
 
+mod codec;
 mod deserialize;
 mod exc;
 mod ext;
 mod ffi;
 mod fragment;
+mod frame;
 mod io;
 mod msgpack;
 mod opt;
 mod serialize;
 mod state;
+mod timestamp;
+mod unpacker;
+mod view;
 
 use crate::ffi::*;
 use pyo3::ffi::*;
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::os::raw::c_int;
@@ -33,10 +39,32 @@ use std::os::raw::c_long;
 use std::os::raw::c_void;
 use std::ptr::NonNull;
 
+const CACHE_CLEAR_DOC: &CStr =
+    c"cache_clear()\n--\n\nRemove all the map keys from the key cache and reset its statistics.";
+const CACHE_CONFIGURE_DOC: &CStr =
+    c"cache_configure(*, size=None, max_key_len=None, associativity=None)\n--\n\nSet the parameters of the key cache and clear it.";
+const CACHE_INFO_DOC: &CStr =
+    c"cache_info()\n--\n\nReturn the parameters and the statistics of the key cache.";
+const PACK_DOC: &CStr =
+    c"pack(obj, fp, /, *, default=None, ext_types=None, option=None)\n--\n\nSerialize Python objects to msgpack and write them to a binary file.";
+const PACK_FRAME_DOC: &CStr =
+    c"pack_frame(obj, /, *, checksum=True, default=None, ext_types=None, option=None)\n--\n\nSerialize Python objects to a length-prefixed msgpack frame.";
 const PACKB_DOC: &CStr =
-    c"packb(obj, /, default=None, option=None)\n--\n\nSerialize Python objects to msgpack.";
+    c"packb(obj, /, default=None, option=None, *, ext_types=None)\n--\n\nSerialize Python objects to msgpack.";
+const PACKB_INTO_DOC: &CStr =
+    c"packb_into(obj, buffer, /, offset=0, *, default=None, ext_types=None, option=None)\n--\n\nSerialize Python objects to msgpack into a writable buffer.";
+const UNPACK_DOC: &CStr =
+    c"unpack(fp, /, *, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nRead msgpack from a binary file and deserialize it to Python objects.";
+const UNPACK_FRAME_DOC: &CStr =
+    c"unpack_frame(obj, /, *, checksum=True, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nDeserialize a length-prefixed msgpack frame to Python objects.";
+const UNPACK_FRAME_WITH_OFFSET_DOC: &CStr =
+    c"unpack_frame_with_offset(obj, /, start=0, *, checksum=True, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nDeserialize the length-prefixed msgpack frame starting at an offset and return the object and the end offset, or None if the frame is incomplete.";
 const UNPACKB_DOC: &CStr =
-    c"unpackb(obj, /, *, ext_hook=None, option=None)\n--\n\nDeserialize msgpack to Python objects.";
+    c"unpackb(obj, /, *, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nDeserialize msgpack to Python objects.";
+const UNPACKB_PATH_DOC: &CStr =
+    c"unpackb_path(obj, path, /, *, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nDeserialize the msgpack object at a path of map keys and array indices.";
+const UNPACKB_WITH_OFFSET_DOC: &CStr =
+    c"unpackb_with_offset(obj, /, start=0, *, ext_hook=None, ext_hooks=None, object_hook=None, object_pairs_hook=None, map_type=None, max_array_len=None, max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, max_map_len=None, max_str_len=None, raw=False, type=None, unicode_errors='strict', option=None)\n--\n\nDeserialize msgpack starting at an offset and return the object and the end offset.";
 
 macro_rules! module_add_object {
     ($mptr: expr, $name: expr, $object:expr) => {
@@ -54,7 +82,47 @@ macro_rules! module_add_int {
 #[no_mangle]
 #[cold]
 pub unsafe extern "C" fn PyInit_ormsgpack() -> *mut PyModuleDef {
-    let methods: Box<[PyMethodDef; 3]> = Box::new([
+    let methods: Box<[PyMethodDef; 14]> = Box::new([
+        PyMethodDef {
+            ml_name: c"cache_clear".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: cache_clear,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: CACHE_CLEAR_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"cache_configure".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: cache_configure,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: CACHE_CONFIGURE_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"cache_info".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: cache_info,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: CACHE_INFO_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"pack".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: pack,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: PACK_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"pack_frame".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: pack_frame,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: PACK_FRAME_DOC.as_ptr(),
+        },
         PyMethodDef {
             ml_name: c"packb".as_ptr(),
             ml_meth: PyMethodDefPointer {
@@ -63,6 +131,38 @@ pub unsafe extern "C" fn PyInit_ormsgpack() -> *mut PyModuleDef {
             ml_flags: METH_FASTCALL | METH_KEYWORDS,
             ml_doc: PACKB_DOC.as_ptr(),
         },
+        PyMethodDef {
+            ml_name: c"packb_into".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: packb_into,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: PACKB_INTO_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"unpack".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: unpack,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: UNPACK_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"unpack_frame".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: unpack_frame,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: UNPACK_FRAME_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"unpack_frame_with_offset".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: unpack_frame_with_offset,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: UNPACK_FRAME_WITH_OFFSET_DOC.as_ptr(),
+        },
         PyMethodDef {
             ml_name: c"unpackb".as_ptr(),
             ml_meth: PyMethodDefPointer {
@@ -71,6 +171,22 @@ pub unsafe extern "C" fn PyInit_ormsgpack() -> *mut PyModuleDef {
             ml_flags: METH_FASTCALL | METH_KEYWORDS,
             ml_doc: UNPACKB_DOC.as_ptr(),
         },
+        PyMethodDef {
+            ml_name: c"unpackb_path".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: unpackb_path,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: UNPACKB_PATH_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"unpackb_with_offset".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFastWithKeywords: unpackb_with_offset,
+            },
+            ml_flags: METH_FASTCALL | METH_KEYWORDS,
+            ml_doc: UNPACKB_WITH_OFFSET_DOC.as_ptr(),
+        },
         PyMethodDef::zeroed(),
     ]);
 
@@ -118,7 +234,7 @@ pub unsafe extern "C" fn ormsgpack_exec(mptr: *mut PyObject) -> c_int {
     PyDateTime_IMPORT();
 
     let state: *mut state::State = PyModule_GetState(mptr).cast();
-    *state = state::State::new();
+    *state = state::State::new(mptr);
 
     let version = env!("CARGO_PKG_VERSION");
     module_add_object!(
@@ -126,16 +242,45 @@ pub unsafe extern "C" fn ormsgpack_exec(mptr: *mut PyObject) -> c_int {
         c"__version__",
         PyUnicode_FromStringAndSize(version.as_ptr().cast::<c_char>(), version.len() as isize)
     );
+    module_add_object!(mptr, c"Codec", (*state).codec_type.cast::<PyObject>());
     module_add_object!(mptr, c"Ext", (*state).ext_type.cast::<PyObject>());
     module_add_object!(mptr, c"Fragment", (*state).fragment_type.cast::<PyObject>());
+    module_add_object!(
+        mptr,
+        c"Timestamp",
+        (*state).timestamp_type.cast::<PyObject>()
+    );
+    module_add_object!(mptr, c"Unpacker", (*state).unpacker_type.cast::<PyObject>());
+    module_add_object!(mptr, c"View", (*state).view_type.cast::<PyObject>());
+    if view::register_view_subtypes(state) != 0 {
+        return -1;
+    }
     module_add_object!(mptr, c"MsgpackDecodeError", (*state).MsgpackDecodeError);
+    module_add_object!(
+        mptr,
+        c"MsgpackDecodeLimitError",
+        (*state).MsgpackDecodeLimitError
+    );
     module_add_object!(mptr, c"MsgpackEncodeError", (*state).MsgpackEncodeError);
 
+    module_add_int!(mptr, c"OPT_ARRAY_AS_NUMPY", opt::ARRAY_AS_NUMPY);
+    module_add_int!(mptr, c"OPT_ARRAY_AS_TUPLE", opt::ARRAY_AS_TUPLE);
+    module_add_int!(mptr, c"OPT_BIG_INT_AS_EXT", opt::BIG_INT_AS_EXT);
+    module_add_int!(mptr, c"OPT_BIN_AS_BYTEARRAY", opt::BIN_AS_BYTEARRAY);
+    module_add_int!(mptr, c"OPT_BIN_AS_MEMORYVIEW", opt::BIN_AS_MEMORYVIEW);
+    module_add_int!(mptr, c"OPT_COMPLEX_AS_EXT", opt::COMPLEX_AS_EXT);
     module_add_int!(
         mptr,
         c"OPT_DATETIME_AS_TIMESTAMP_EXT",
         opt::DATETIME_AS_TIMESTAMP_EXT
     );
+    module_add_int!(mptr, c"OPT_DECIMAL_AS_EXT", opt::DECIMAL_AS_EXT);
+    module_add_int!(
+        mptr,
+        c"OPT_FORBID_DUPLICATE_KEYS",
+        opt::FORBID_DUPLICATE_KEYS
+    );
+    module_add_int!(mptr, c"OPT_FORBID_TRAILING_DATA", opt::FORBID_TRAILING_DATA);
     module_add_int!(mptr, c"OPT_NAIVE_UTC", opt::NAIVE_UTC);
     module_add_int!(mptr, c"OPT_NON_STR_KEYS", opt::NON_STR_KEYS);
     module_add_int!(mptr, c"OPT_OMIT_MICROSECONDS", opt::OMIT_MICROSECONDS);
@@ -147,6 +292,7 @@ pub unsafe extern "C" fn ormsgpack_exec(mptr: *mut PyObject) -> c_int {
     );
     module_add_int!(mptr, c"OPT_PASSTHROUGH_DATETIME", opt::PASSTHROUGH_DATETIME);
     module_add_int!(mptr, c"OPT_PASSTHROUGH_ENUM", opt::PASSTHROUGH_ENUM);
+    module_add_int!(mptr, c"OPT_PASSTHROUGH_NUMBER", opt::PASSTHROUGH_NUMBER);
     module_add_int!(mptr, c"OPT_PASSTHROUGH_SUBCLASS", opt::PASSTHROUGH_SUBCLASS);
     module_add_int!(mptr, c"OPT_PASSTHROUGH_TUPLE", opt::PASSTHROUGH_TUPLE);
     module_add_int!(mptr, c"OPT_PASSTHROUGH_UUID", opt::PASSTHROUGH_UUID);
@@ -154,6 +300,14 @@ pub unsafe extern "C" fn ormsgpack_exec(mptr: *mut PyObject) -> c_int {
     module_add_int!(mptr, c"OPT_SERIALIZE_NUMPY", opt::SERIALIZE_NUMPY);
     module_add_int!(mptr, c"OPT_SERIALIZE_PYDANTIC", opt::SERIALIZE_PYDANTIC);
     module_add_int!(mptr, c"OPT_SORT_KEYS", opt::SORT_KEYS);
+    module_add_int!(
+        mptr,
+        c"OPT_TIMEDELTA_AS_MICROSECONDS",
+        opt::TIMEDELTA_AS_MICROSECONDS
+    );
+    module_add_int!(mptr, c"OPT_TIMEDELTA_AS_SECONDS", opt::TIMEDELTA_AS_SECONDS);
+    module_add_int!(mptr, c"OPT_TIMESTAMP_AS_INT", opt::TIMESTAMP_AS_INT);
+    module_add_int!(mptr, c"OPT_TIMESTAMP_FALLBACK", opt::TIMESTAMP_FALLBACK);
     module_add_int!(mptr, c"OPT_UTC_Z", opt::UTC_Z);
 
     0
@@ -162,14 +316,80 @@ pub unsafe extern "C" fn ormsgpack_exec(mptr: *mut PyObject) -> c_int {
 #[cold]
 #[inline(never)]
 fn raise_unpackb_exception(state: *mut state::State, msg: &str) -> *mut PyObject {
+    unsafe { raise_decode_exception(state, (*state).MsgpackDecodeError, msg, None, None) }
+}
+
+#[cold]
+#[inline(never)]
+fn raise_deserialize_exception(
+    state: *mut state::State,
+    err: &deserialize::DeserializeError,
+) -> *mut PyObject {
     unsafe {
-        let err_msg =
-            PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), msg.len() as isize);
-        let args = PyTuple_New(1);
-        pytuple_set_item(args, 0, err_msg);
-        PyErr_SetObject((*state).MsgpackDecodeError, args);
+        let exc = if err.limit_exceeded {
+            (*state).MsgpackDecodeLimitError
+        } else {
+            (*state).MsgpackDecodeError
+        };
+        raise_decode_exception(state, exc, &err.message, err.position, err.path)
+    }
+}
+
+/// Raises `exc` with the message `msg` and, if they are known, the `pos` and
+/// `path` attributes. An exception that is already set, such as one raised by
+/// a hook, becomes its cause.
+unsafe fn raise_decode_exception(
+    state: *mut state::State,
+    exc: *mut PyObject,
+    msg: &str,
+    position: Option<usize>,
+    path: Option<NonNull<PyObject>>,
+) -> *mut PyObject {
+    let cause = pyerr_take();
+    let err_msg = PyUnicode_FromStringAndSize(msg.as_ptr().cast::<c_char>(), msg.len() as isize);
+    if err_msg.is_null() {
+        Py_XDECREF(cause);
+        return std::ptr::null_mut();
+    }
+    let args = PyTuple_New(1);
+    pytuple_set_item(args, 0, err_msg);
+    if cause.is_null() && position.is_none() && path.is_none() {
+        PyErr_SetObject(exc, args);
         Py_DECREF(args);
-    };
+        return std::ptr::null_mut();
+    }
+    let value = PyObject_Call(exc, args, std::ptr::null_mut());
+    Py_DECREF(args);
+    if value.is_null() {
+        Py_XDECREF(cause);
+        return std::ptr::null_mut();
+    }
+    let mut ret = 0;
+    if let Some(position) = position {
+        let pos = PyLong_FromSize_t(position);
+        ret = if pos.is_null() {
+            -1
+        } else {
+            let ret = PyObject_SetAttr(value, (*state).pos_str, pos);
+            Py_DECREF(pos);
+            ret
+        };
+    }
+    if let Some(path) = path {
+        if ret == 0 {
+            ret = PyObject_SetAttr(value, (*state).path_str, path.as_ptr());
+        }
+    }
+    if ret != 0 {
+        Py_XDECREF(cause);
+        Py_DECREF(value);
+        return std::ptr::null_mut();
+    }
+    if !cause.is_null() {
+        PyException_SetCause(value, cause);
+    }
+    PyErr_SetObject(exc, value);
+    Py_DECREF(value);
     std::ptr::null_mut()
 }
 
@@ -185,6 +405,159 @@ fn raise_packb_exception(state: *mut state::State, msg: &str) -> *mut PyObject {
     std::ptr::null_mut()
 }
 
+unsafe fn parse_bool_arg(value: *mut PyObject) -> Result<bool, ()> {
+    if value == Py_True() {
+        Ok(true)
+    } else if value == Py_False() {
+        Ok(false)
+    } else {
+        Err(())
+    }
+}
+
+unsafe fn parse_map_type_arg(value: *mut PyObject) -> Result<Option<NonNull<PyObject>>, ()> {
+    if value == Py_None() || value == (&raw mut PyDict_Type).cast::<PyObject>() {
+        Ok(None)
+    } else if PyCallable_Check(value) != 0 {
+        Ok(Some(NonNull::new_unchecked(value)))
+    } else {
+        Err(())
+    }
+}
+
+/// Parses a mapping of extension types, from -128 to 127, to callables.
+unsafe fn parse_ext_hooks_arg(value: *mut PyObject) -> Result<Option<NonNull<PyObject>>, ()> {
+    if value == Py_None() {
+        return Ok(None);
+    }
+    if PyDict_Check(value) == 0 {
+        return Err(());
+    }
+    for (tag, hook) in PyDictIter::from_pyobject(value) {
+        if Py_TYPE(tag.as_ptr()) != &raw mut PyLong_Type || PyCallable_Check(hook.as_ptr()) == 0 {
+            return Err(());
+        }
+        let mut overflow: c_int = 0;
+        let val = PyLong_AsLongAndOverflow(tag.as_ptr(), &mut overflow);
+        if overflow != 0 || !(-128..=127).contains(&val) {
+            return Err(());
+        }
+    }
+    Ok(Some(NonNull::new_unchecked(value)))
+}
+
+/// Parses a mapping of types to `(tag, encoder)` tuples.
+unsafe fn parse_ext_types_arg(value: *mut PyObject) -> Result<Option<NonNull<PyObject>>, ()> {
+    if value == Py_None() {
+        return Ok(None);
+    }
+    if PyDict_Check(value) == 0 {
+        return Err(());
+    }
+    for (tp, entry) in PyDictIter::from_pyobject(value) {
+        if PyType_Check(tp.as_ptr()) == 0 || serialize::ext_type_entry(entry.as_ptr()).is_none() {
+            return Err(());
+        }
+    }
+    Ok(Some(NonNull::new_unchecked(value)))
+}
+
+unsafe fn parse_limit_arg(value: *mut PyObject) -> Result<u64, ()> {
+    if value == Py_None() {
+        return Ok(u64::MAX);
+    }
+    if Py_TYPE(value) != &raw mut PyLong_Type {
+        return Err(());
+    }
+    let mut overflow: c_int = 0;
+    let val = PyLong_AsLongLongAndOverflow(value, &mut overflow);
+    if overflow > 0 {
+        Ok(u64::MAX)
+    } else if overflow < 0 || val < 0 {
+        Err(())
+    } else {
+        Ok(val as u64)
+    }
+}
+
+/// Parses a keyword argument that sets a field of the deserializer config.
+/// Returns `Ok(false)` if `arg` is not the name of one.
+unsafe fn parse_config_kwarg(
+    state: *mut state::State,
+    config: &mut deserialize::Config,
+    arg: *mut PyObject,
+    value: *mut PyObject,
+) -> Result<bool, Cow<'static, str>> {
+    let limits = &mut config.limits;
+    if PyUnicode_Compare(arg, (*state).ext_hook_str) == 0 {
+        if value != Py_None() {
+            config.ext_hook = Some(NonNull::new_unchecked(value));
+        }
+    } else if PyUnicode_Compare(arg, (*state).ext_hooks_str) == 0 {
+        config.ext_hooks = parse_ext_hooks_arg(value).map_err(|()| "Invalid ext_hooks")?;
+    } else if PyUnicode_Compare(arg, (*state).object_hook_str) == 0 {
+        if value != Py_None() {
+            config.object_hook = Some(NonNull::new_unchecked(value));
+        }
+    } else if PyUnicode_Compare(arg, (*state).object_pairs_hook_str) == 0 {
+        if value != Py_None() {
+            config.object_pairs_hook = Some(NonNull::new_unchecked(value));
+        }
+    } else if PyUnicode_Compare(arg, (*state).map_type_str) == 0 {
+        config.map_type = parse_map_type_arg(value).map_err(|()| "Invalid map_type")?;
+    } else if PyUnicode_Compare(arg, (*state).max_array_len_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_array_len")?;
+        limits.max_array_len = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).max_bin_len_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_bin_len")?;
+        limits.max_bin_len = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).max_depth_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_depth")?;
+        limits.max_depth = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).max_elements_str) == 0 {
+        limits.max_elements = parse_limit_arg(value).map_err(|()| "Invalid max_elements")?;
+    } else if PyUnicode_Compare(arg, (*state).max_ext_len_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_ext_len")?;
+        limits.max_ext_len = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).max_map_len_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_map_len")?;
+        limits.max_map_len = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).max_str_len_str) == 0 {
+        let val = parse_limit_arg(value).map_err(|()| "Invalid max_str_len")?;
+        limits.max_str_len = val.min(u32::MAX.into()) as u32;
+    } else if PyUnicode_Compare(arg, (*state).raw_str) == 0 {
+        config.raw = parse_bool_arg(value).map_err(|()| "Invalid raw")?;
+    } else if PyUnicode_Compare(arg, (*state).type_str) == 0 {
+        if value != Py_None() {
+            let plan = deserialize::get_type_plan(state, value).map_err(Cow::Owned)?;
+            Py_DECREF(plan.as_ptr());
+            config.type_ = Some(NonNull::new_unchecked(value));
+        }
+    } else if PyUnicode_Compare(arg, (*state).unicode_errors_str) == 0 {
+        config.unicode_errors =
+            parse_unicode_errors_arg(value).map_err(|()| "Invalid unicode_errors")?;
+    } else {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+unsafe fn parse_unicode_errors_arg(value: *mut PyObject) -> Result<deserialize::UnicodeErrors, ()> {
+    if value == Py_None() {
+        Ok(deserialize::UnicodeErrors::Strict)
+    } else if PyUnicode_Check(value) == 0 {
+        Err(())
+    } else if PyUnicode_CompareWithASCIIString(value, c"strict".as_ptr()) == 0 {
+        Ok(deserialize::UnicodeErrors::Strict)
+    } else if PyUnicode_CompareWithASCIIString(value, c"replace".as_ptr()) == 0 {
+        Ok(deserialize::UnicodeErrors::Replace)
+    } else if PyUnicode_CompareWithASCIIString(value, c"surrogateescape".as_ptr()) == 0 {
+        Ok(deserialize::UnicodeErrors::SurrogateEscape)
+    } else {
+        Err(())
+    }
+}
+
 unsafe fn parse_option_arg(opts: *mut PyObject, mask: i32) -> Result<i32, ()> {
     if Py_TYPE(opts) == &raw mut PyLong_Type {
         let val = PyLong_AsLong(opts) as i32;
@@ -208,7 +581,7 @@ pub unsafe extern "C" fn unpackb(
     kwnames: *mut PyObject,
 ) -> *mut PyObject {
     let state: *mut state::State = PyModule_GetState(module).cast();
-    let mut ext_hook: Option<NonNull<PyObject>> = None;
+    let mut config = crate::deserialize::Config::default();
     let mut optsptr: Option<NonNull<PyObject>> = None;
 
     let num_args = PyVectorcall_NARGS(nargs as usize);
@@ -224,15 +597,19 @@ pub unsafe extern "C" fn unpackb(
         let tuple_size = Py_SIZE(kwnames);
         for i in 0..tuple_size {
             let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
-            if PyUnicode_Compare(arg, (*state).ext_hook_str) == 0 {
-                ext_hook = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
-            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+            if PyUnicode_Compare(arg, (*state).option_str) == 0 {
                 optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
             } else {
-                return raise_unpackb_exception(
-                    state,
-                    "unpackb() got an unexpected keyword argument",
-                );
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpackb() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
             }
         }
     }
@@ -245,9 +622,168 @@ pub unsafe extern "C" fn unpackb(
         }
     }
 
-    match crate::deserialize::deserialize(*args, state, ext_hook, optsbits as opt::Opt) {
+    match crate::deserialize::deserialize(*args, state, &config, optsbits as opt::Opt) {
+        Ok(val) => val.as_ptr(),
+        Err(err) => raise_deserialize_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn unpackb_path(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut config = crate::deserialize::Config::default();
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 2) {
+        let msg = if num_args > 2 {
+            "unpackb_path() accepts only 2 positional arguments"
+        } else if num_args == 1 {
+            "unpackb_path() missing 1 required positional argument: 'path'"
+        } else {
+            "unpackb_path() missing 2 required positional arguments: 'obj' and 'path'"
+        };
+        return raise_unpackb_exception(state, msg);
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpackb_path() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::UNPACKB_PARTIAL_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::deserialize::deserialize_path(
+        *args,
+        *args.offset(1),
+        state,
+        &config,
+        optsbits as opt::Opt,
+    ) {
         Ok(val) => val.as_ptr(),
-        Err(err) => raise_unpackb_exception(state, &err.message),
+        Err(err) => raise_deserialize_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn unpackb_with_offset(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut startptr: Option<NonNull<PyObject>> = None;
+    let mut config = crate::deserialize::Config::default();
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args == 0) {
+        return raise_unpackb_exception(
+            state,
+            "unpackb_with_offset() missing 1 required positional argument: 'obj'",
+        );
+    }
+    if unlikely!(num_args > 2) {
+        return raise_unpackb_exception(
+            state,
+            "unpackb_with_offset() accepts at most 2 positional arguments",
+        );
+    }
+    if num_args == 2 {
+        startptr = Some(NonNull::new_unchecked(*args.offset(1)));
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).start_str) == 0 {
+                if unlikely!(startptr.is_some()) {
+                    return raise_unpackb_exception(
+                        state,
+                        "unpackb_with_offset() got multiple values for argument: 'start'",
+                    );
+                }
+                startptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpackb_with_offset() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let mut start: usize = 0;
+    if let Some(startobj) = startptr {
+        let val = if Py_TYPE(startobj.as_ptr()) == &raw mut PyLong_Type {
+            PyLong_AsSsize_t(startobj.as_ptr())
+        } else {
+            -1
+        };
+        if val < 0 {
+            PyErr_Clear();
+            return raise_unpackb_exception(state, "Invalid start");
+        }
+        start = val as usize;
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::UNPACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::deserialize::deserialize_with_offset(
+        *args,
+        start,
+        state,
+        &config,
+        optsbits as opt::Opt,
+    ) {
+        Ok((val, end)) => {
+            let tuple = PyTuple_New(2);
+            pytuple_set_item(tuple, 0, val.as_ptr());
+            pytuple_set_item(tuple, 1, PyLong_FromSize_t(end));
+            tuple
+        }
+        Err(err) => raise_deserialize_exception(state, &err),
     }
 }
 
@@ -260,6 +796,7 @@ pub unsafe extern "C" fn packb(
 ) -> *mut PyObject {
     let state: *mut state::State = PyModule_GetState(module).cast();
     let mut default: Option<NonNull<PyObject>> = None;
+    let mut ext_types: Option<NonNull<PyObject>> = None;
     let mut optsptr: Option<NonNull<PyObject>> = None;
 
     let num_args = PyVectorcall_NARGS(nargs as usize);
@@ -287,6 +824,11 @@ pub unsafe extern "C" fn packb(
                     );
                 }
                 default = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).ext_types_str) == 0 {
+                match parse_ext_types_arg(*args.offset(num_args + i)) {
+                    Ok(val) => ext_types = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid ext_types"),
+                }
             } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
                 if unlikely!(optsptr.is_some()) {
                     return raise_packb_exception(
@@ -309,8 +851,611 @@ pub unsafe extern "C" fn packb(
         }
     }
 
-    match crate::serialize::serialize(*args, state, default, optsbits as opt::Opt) {
+    match crate::serialize::serialize(*args, state, default, ext_types, optsbits as opt::Opt) {
         Ok(val) => val.as_ptr(),
         Err(err) => raise_packb_exception(state, &err),
     }
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn packb_into(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut offsetptr: Option<NonNull<PyObject>> = None;
+    let mut default: Option<NonNull<PyObject>> = None;
+    let mut ext_types: Option<NonNull<PyObject>> = None;
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args < 2) {
+        return raise_packb_exception(
+            state,
+            "packb_into() missing required positional arguments: 'obj' and 'buffer'",
+        );
+    }
+    if unlikely!(num_args > 3) {
+        return raise_packb_exception(state, "packb_into() accepts at most 3 positional arguments");
+    }
+    if num_args == 3 {
+        offsetptr = Some(NonNull::new_unchecked(*args.offset(2)));
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).offset_str) == 0 {
+                if unlikely!(offsetptr.is_some()) {
+                    return raise_packb_exception(
+                        state,
+                        "packb_into() got multiple values for argument: 'offset'",
+                    );
+                }
+                offsetptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).default_str) == 0 {
+                default = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).ext_types_str) == 0 {
+                match parse_ext_types_arg(*args.offset(num_args + i)) {
+                    Ok(val) => ext_types = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid ext_types"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                return raise_packb_exception(
+                    state,
+                    "packb_into() got an unexpected keyword argument",
+                );
+            }
+        }
+    }
+
+    let mut offset: usize = 0;
+    if let Some(offsetobj) = offsetptr {
+        let val = if Py_TYPE(offsetobj.as_ptr()) == &raw mut PyLong_Type {
+            PyLong_AsSsize_t(offsetobj.as_ptr())
+        } else {
+            -1
+        };
+        if val < 0 {
+            PyErr_Clear();
+            return raise_packb_exception(state, "Invalid offset");
+        }
+        offset = val as usize;
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::PACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_packb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::serialize::serialize_into_buffer(
+        *args,
+        state,
+        default,
+        ext_types,
+        optsbits as opt::Opt,
+        *args.offset(1),
+        offset,
+    ) {
+        Ok(len) => PyLong_FromSize_t(len),
+        Err(err) => raise_packb_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pack(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut default: Option<NonNull<PyObject>> = None;
+    let mut ext_types: Option<NonNull<PyObject>> = None;
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 2) {
+        let msg = if num_args > 2 {
+            "pack() accepts only 2 positional arguments"
+        } else {
+            "pack() missing required positional arguments: 'obj' and 'fp'"
+        };
+        return raise_packb_exception(state, msg);
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).default_str) == 0 {
+                default = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).ext_types_str) == 0 {
+                match parse_ext_types_arg(*args.offset(num_args + i)) {
+                    Ok(val) => ext_types = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid ext_types"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                return raise_packb_exception(state, "pack() got an unexpected keyword argument");
+            }
+        }
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::PACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_packb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::serialize::serialize_into_stream(
+        *args,
+        state,
+        default,
+        ext_types,
+        optsbits as opt::Opt,
+        *args.offset(1),
+    ) {
+        Ok(()) => Py_NewRef(Py_None()),
+        Err(Some(err)) => raise_packb_exception(state, &err),
+        Err(None) => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn unpack(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut config = crate::deserialize::Config::default();
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 1) {
+        let msg = if num_args > 1 {
+            "unpack() accepts only 1 positional argument"
+        } else {
+            "unpack() missing 1 required positional argument: 'fp'"
+        };
+        return raise_unpackb_exception(state, msg);
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpack() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::UNPACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+        }
+    }
+
+    let fp = *args;
+    let seekable = pyobject_call_method_no_args(fp, (*state).seekable_str);
+    let is_seekable = if seekable.is_null() {
+        PyErr_Clear();
+        false
+    } else {
+        let is_seekable = seekable == Py_True();
+        Py_DECREF(seekable);
+        is_seekable
+    };
+    let mut reader = crate::deserialize::StreamReader::new(fp, state, is_seekable);
+    let obj = match crate::deserialize::deserialize_from_stream(
+        &mut reader,
+        state,
+        &config,
+        optsbits as opt::Opt,
+    ) {
+        Ok(val) => val.as_ptr(),
+        Err(Some(err)) => return raise_deserialize_exception(state, &err),
+        Err(None) => return std::ptr::null_mut(),
+    };
+
+    // Give back the data that was read past the end of the object, so that
+    // the stream is positioned right after it.
+    let remaining = reader.remaining();
+    if remaining != 0 {
+        let offset = PyLong_FromSsize_t(-(remaining as Py_ssize_t));
+        let whence = PyLong_FromLong(1);
+        let ret = PyObject_CallMethodObjArgs(
+            fp,
+            (*state).seek_str,
+            offset,
+            whence,
+            std::ptr::null_mut::<PyObject>(),
+        );
+        Py_DECREF(offset);
+        Py_DECREF(whence);
+        if ret.is_null() {
+            Py_DECREF(obj);
+            return std::ptr::null_mut();
+        }
+        Py_DECREF(ret);
+    }
+    obj
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pack_frame(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut checksum = true;
+    let mut default: Option<NonNull<PyObject>> = None;
+    let mut ext_types: Option<NonNull<PyObject>> = None;
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 1) {
+        let msg = if num_args > 1 {
+            "pack_frame() accepts only 1 positional argument"
+        } else {
+            "pack_frame() missing 1 required positional argument: 'obj'"
+        };
+        return raise_packb_exception(state, msg);
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).checksum_str) == 0 {
+                match parse_bool_arg(*args.offset(num_args + i)) {
+                    Ok(val) => checksum = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid checksum"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).default_str) == 0 {
+                default = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).ext_types_str) == 0 {
+                match parse_ext_types_arg(*args.offset(num_args + i)) {
+                    Ok(val) => ext_types = val,
+                    Err(()) => return raise_packb_exception(state, "Invalid ext_types"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                return raise_packb_exception(
+                    state,
+                    "pack_frame() got an unexpected keyword argument",
+                );
+            }
+        }
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::PACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_packb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::serialize::serialize_frame(
+        *args,
+        state,
+        default,
+        ext_types,
+        optsbits as opt::Opt,
+        checksum,
+    ) {
+        Ok(val) => val.as_ptr(),
+        Err(err) => raise_packb_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn unpack_frame(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut checksum = true;
+    let mut config = crate::deserialize::Config::default();
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 1) {
+        let msg = if num_args > 1 {
+            "unpack_frame() accepts only 1 positional argument"
+        } else {
+            "unpack_frame() missing 1 required positional argument: 'obj'"
+        };
+        return raise_unpackb_exception(state, msg);
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).checksum_str) == 0 {
+                match parse_bool_arg(*args.offset(num_args + i)) {
+                    Ok(val) => checksum = val,
+                    Err(()) => return raise_unpackb_exception(state, "Invalid checksum"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpack_frame() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::UNPACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::deserialize::deserialize_frame(
+        *args,
+        checksum,
+        state,
+        &config,
+        optsbits as opt::Opt,
+    ) {
+        Ok(val) => val.as_ptr(),
+        Err(err) => raise_deserialize_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn unpack_frame_with_offset(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut startptr: Option<NonNull<PyObject>> = None;
+    let mut checksum = true;
+    let mut config = crate::deserialize::Config::default();
+    let mut optsptr: Option<NonNull<PyObject>> = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args == 0) {
+        return raise_unpackb_exception(
+            state,
+            "unpack_frame_with_offset() missing 1 required positional argument: 'obj'",
+        );
+    }
+    if unlikely!(num_args > 2) {
+        return raise_unpackb_exception(
+            state,
+            "unpack_frame_with_offset() accepts at most 2 positional arguments",
+        );
+    }
+    if num_args == 2 {
+        startptr = Some(NonNull::new_unchecked(*args.offset(1)));
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            if PyUnicode_Compare(arg, (*state).start_str) == 0 {
+                if unlikely!(startptr.is_some()) {
+                    return raise_unpackb_exception(
+                        state,
+                        "unpack_frame_with_offset() got multiple values for argument: 'start'",
+                    );
+                }
+                startptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else if PyUnicode_Compare(arg, (*state).checksum_str) == 0 {
+                match parse_bool_arg(*args.offset(num_args + i)) {
+                    Ok(val) => checksum = val,
+                    Err(()) => return raise_unpackb_exception(state, "Invalid checksum"),
+                }
+            } else if PyUnicode_Compare(arg, (*state).option_str) == 0 {
+                optsptr = Some(NonNull::new_unchecked(*args.offset(num_args + i)));
+            } else {
+                match parse_config_kwarg(state, &mut config, arg, *args.offset(num_args + i)) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "unpack_frame_with_offset() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let mut start: usize = 0;
+    if let Some(startobj) = startptr {
+        let val = if Py_TYPE(startobj.as_ptr()) == &raw mut PyLong_Type {
+            PyLong_AsSsize_t(startobj.as_ptr())
+        } else {
+            -1
+        };
+        if val < 0 {
+            PyErr_Clear();
+            return raise_unpackb_exception(state, "Invalid start");
+        }
+        start = val as usize;
+    }
+
+    let mut optsbits: i32 = 0;
+    if let Some(opts) = optsptr {
+        match parse_option_arg(opts.as_ptr(), opt::UNPACKB_OPT_MASK) {
+            Ok(val) => optsbits = val,
+            Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+        }
+    }
+
+    match crate::deserialize::deserialize_frame_with_offset(
+        *args,
+        start,
+        checksum,
+        state,
+        &config,
+        optsbits as opt::Opt,
+    ) {
+        Ok(Some((val, end))) => {
+            let tuple = PyTuple_New(2);
+            pytuple_set_item(tuple, 0, val.as_ptr());
+            pytuple_set_item(tuple, 1, PyLong_FromSize_t(end));
+            tuple
+        }
+        Ok(None) => Py_NewRef(Py_None()),
+        Err(err) => raise_deserialize_exception(state, &err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cache_clear(module: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    (*state).key_map.clear();
+    Py_NewRef(Py_None())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cache_configure(
+    module: *mut PyObject,
+    args: *const *mut PyObject,
+    nargs: Py_ssize_t,
+    kwnames: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let mut size = None;
+    let mut max_key_len = None;
+    let mut associativity = None;
+
+    let num_args = PyVectorcall_NARGS(nargs as usize);
+    if unlikely!(num_args != 0) {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"cache_configure() takes no positional arguments".as_ptr(),
+        );
+        return std::ptr::null_mut();
+    }
+    if !kwnames.is_null() {
+        let tuple_size = Py_SIZE(kwnames);
+        for i in 0..tuple_size {
+            let arg = pytuple_get_item(kwnames, i as Py_ssize_t);
+            let (target, msg) = if PyUnicode_Compare(arg, (*state).size_str) == 0 {
+                (&mut size, c"Invalid size")
+            } else if PyUnicode_Compare(arg, (*state).max_key_len_str) == 0 {
+                (&mut max_key_len, c"Invalid max_key_len")
+            } else if PyUnicode_Compare(arg, (*state).associativity_str) == 0 {
+                (&mut associativity, c"Invalid associativity")
+            } else {
+                PyErr_SetString(
+                    PyExc_TypeError,
+                    c"cache_configure() got an unexpected keyword argument".as_ptr(),
+                );
+                return std::ptr::null_mut();
+            };
+            let value = *args.offset(i);
+            if value != Py_None() {
+                match parse_cache_arg(value) {
+                    Ok(val) => *target = Some(val),
+                    Err(()) => {
+                        PyErr_SetString(PyExc_ValueError, msg.as_ptr());
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+        }
+    }
+
+    let msg = match (*state).key_map.configure(size, max_key_len, associativity) {
+        Ok(()) => return Py_NewRef(Py_None()),
+        Err(deserialize::KeyMapConfigureError::InvalidSize) => c"Invalid size",
+        Err(deserialize::KeyMapConfigureError::InvalidAssociativity) => c"Invalid associativity",
+        Err(deserialize::KeyMapConfigureError::SizeNotMultiple) => {
+            c"size must be a multiple of associativity"
+        }
+    };
+    PyErr_SetString(PyExc_ValueError, msg.as_ptr());
+    std::ptr::null_mut()
+}
+
+unsafe fn parse_cache_arg(value: *mut PyObject) -> Result<usize, ()> {
+    if Py_TYPE(value) != &raw mut PyLong_Type {
+        return Err(());
+    }
+    let val = PyLong_AsSsize_t(value);
+    if val < 0 {
+        PyErr_Clear();
+        return Err(());
+    }
+    Ok(val as usize)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cache_info(module: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let state: *mut state::State = PyModule_GetState(module).cast();
+    let info = (*state).key_map.info();
+    let dict = PyDict_New();
+    if dict.is_null() {
+        return std::ptr::null_mut();
+    }
+    for (key, value) in [
+        (c"hits", info.hits),
+        (c"misses", info.misses),
+        (c"evictions", info.evictions),
+        (c"entries", info.entries as u64),
+        (c"size", info.size as u64),
+        (c"max_key_len", info.max_key_len as u64),
+        (c"associativity", info.associativity as u64),
+    ] {
+        let value = PyLong_FromUnsignedLongLong(value);
+        if value.is_null() {
+            Py_DECREF(dict);
+            return std::ptr::null_mut();
+        }
+        let ret = PyDict_SetItemString(dict, key.as_ptr(), value);
+        Py_DECREF(value);
+        if ret == -1 {
+            Py_DECREF(dict);
+            return std::ptr::null_mut();
+        }
+    }
+    dict
+}
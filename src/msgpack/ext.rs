@@ -3,6 +3,19 @@
 use crate::io::{Read, WriteSlices};
 use crate::msgpack::marker::Marker;
 
+/// The extension type of integers that exceed the 64-bit range, holding
+/// their two's complement representation in big-endian byte order.
+pub const BIG_INT_EXT_TYPE: i8 = 127;
+
+/// The extension type of `decimal.Decimal` instances, holding their exponent
+/// as a 4-byte big-endian signed integer and their coefficient in the format
+/// of `BIG_INT_EXT_TYPE`.
+pub const DECIMAL_EXT_TYPE: i8 = 126;
+
+/// The extension type of `complex` instances, holding their real and
+/// imaginary parts as big-endian IEEE 754 double precision numbers.
+pub const COMPLEX_EXT_TYPE: i8 = 125;
+
 pub fn write_ext<W>(writer: &mut W, value: &[u8], tag: i8) -> Result<(), std::io::Error>
 where
     W: WriteSlices,
@@ -39,10 +52,9 @@ where
     }
 }
 
-pub fn read_timestamp<R>(
-    reader: &mut R,
-    len: u32,
-) -> Result<chrono::DateTime<chrono::Utc>, std::io::Error>
+/// Reads the data of a timestamp extension object as seconds and nanoseconds
+/// since the epoch.
+pub fn read_timestamp<R>(reader: &mut R, len: u32) -> Result<(i64, u32), std::io::Error>
 where
     R: Read,
 {
@@ -62,10 +74,10 @@ where
         }
         _ => return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)),
     };
-    match chrono::DateTime::<chrono::Utc>::from_timestamp(seconds, nanoseconds) {
-        Some(value) => Ok(value),
-        None => Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)),
+    if nanoseconds >= 1_000_000_000 {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
     }
+    Ok((seconds, nanoseconds))
 }
 
 pub fn write_timestamp<W>(
@@ -75,8 +87,23 @@ pub fn write_timestamp<W>(
 where
     W: std::io::Write,
 {
-    let seconds = datetime.timestamp();
-    let nanoseconds = datetime.timestamp_subsec_nanos();
+    write_timestamp_parts(
+        writer,
+        datetime.timestamp(),
+        datetime.timestamp_subsec_nanos(),
+    )
+}
+
+/// Writes the data of a timestamp extension object in the smallest of the
+/// 32-bit, 64-bit and 96-bit formats.
+pub fn write_timestamp_parts<W>(
+    writer: &mut W,
+    seconds: i64,
+    nanoseconds: u32,
+) -> Result<(), std::io::Error>
+where
+    W: std::io::Write,
+{
     if seconds >> 34 == 0 {
         let value = (i64::from(nanoseconds) << 34) | seconds;
         if value <= 4294967295 {
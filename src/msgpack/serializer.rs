@@ -3,8 +3,13 @@
 use crate::exc::*;
 use crate::io::WriteSlices;
 use crate::msgpack;
+use crate::msgpack::{Header, Validator};
 use serde::ser;
 
+/// The name of the newtype struct whose value is serialized as an array with
+/// its elements sorted by their serialized bytes.
+pub const SORTED_ARRAY: &str = "SortedArray";
+
 #[derive(Debug)]
 pub enum Error {
     Custom(String),
@@ -434,6 +439,41 @@ where
             recursion: 0,
         }
     }
+
+    #[cold]
+    #[inline(never)]
+    fn serialize_sorted_array<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let mut se = Serializer {
+            writer: Vec::new(),
+            recursion: self.recursion,
+        };
+        value.serialize(&mut se)?;
+        let data = se.writer.as_slice();
+        let origin = data.as_ptr() as usize;
+        let mut validator = Validator::new(data);
+        let len = match validator.read_header() {
+            Ok(Header::Array(len)) => len,
+            _ => unreachable!(),
+        };
+        let header_len = validator.position() - origin;
+        let mut elements = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let start = validator.position() - origin;
+            validator
+                .skip()
+                .map_err(|err| Error::Custom(err.to_string()))?;
+            elements.push(&data[start..validator.position() - origin]);
+        }
+        elements.sort_unstable();
+        self.writer.write_slices([&data[..header_len]])?;
+        for element in elements {
+            self.writer.write_slices([element])?;
+        }
+        Ok(())
+    }
 }
 
 pub struct Compound<'a, W> {
@@ -602,10 +642,13 @@ where
         unreachable!();
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Self::Error>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Self::Error>
     where
         T: ?Sized + ser::Serialize,
     {
+        if unlikely!(name == SORTED_ARRAY) {
+            return self.serialize_sorted_array(value);
+        }
         let mut fragment_se = FragmentSerializer::new(&mut self.writer);
         value.serialize(&mut fragment_se)
     }
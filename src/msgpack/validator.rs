@@ -46,6 +46,42 @@ impl From<Utf8Error> for Error {
     }
 }
 
+/// An element of a path addressing a value nested in maps and arrays.
+pub enum PathItem<'a> {
+    /// A map key of type string, as UTF-8.
+    Key(&'a [u8]),
+    /// A map key of type integer or an array index. Negative indices count
+    /// from the end of the array.
+    Int(i64),
+}
+
+impl PathItem<'_> {
+    fn matches(&self, key: &Key) -> bool {
+        match (self, key) {
+            (PathItem::Key(item), Key::Str(key)) => item == key,
+            (PathItem::Int(item), Key::Int(key)) => item == key,
+            _ => false,
+        }
+    }
+}
+
+/// The header of an object, as read by [`Validator::read_header`].
+pub enum Header {
+    Array(u32),
+    Map(u32),
+    /// Any other object, whose marker has been read.
+    Other,
+}
+
+/// A map key, as read by [`Validator::read_key`].
+pub enum Key<'a> {
+    Str(&'a [u8]),
+    /// An integer key that fits in an `i64`.
+    Int(i64),
+    /// Any other key, which has been validated.
+    Other,
+}
+
 pub struct Validator<R> {
     data: R,
     recursion: u8,
@@ -85,26 +121,145 @@ where
 
     fn validate_array(&mut self, len: u32) -> Result<(), Error> {
         for _ in 0..len {
-            self.validate()?;
+            self.skip()?;
         }
         Ok(())
     }
 
     fn validate_map(&mut self, len: u32) -> Result<(), Error> {
         for _ in 0..len {
-            self.validate()?;
-            self.validate()?;
+            self.skip()?;
+            self.skip()?;
         }
         Ok(())
     }
 
+    /// Returns the reader, positioned after the last value read.
+    pub fn into_inner(self) -> R {
+        self.data
+    }
+
+    /// Validates the whole input, which must hold exactly one object.
     pub fn validate(&mut self) -> Result<(), Error> {
+        self.skip()?;
+        if self.data.eof() {
+            Ok(())
+        } else {
+            Err(Error::InvalidValue)
+        }
+    }
+
+    /// Validates the next object and advances past it.
+    pub fn skip(&mut self) -> Result<(), Error> {
+        let marker = Marker::from_u8(self.data.read_u8()?);
+        self.skip_marker(marker)
+    }
+
+    /// Returns the position of the next byte to read.
+    pub fn position(&self) -> usize {
+        self.data.position()
+    }
+
+    /// Reads the marker of the next object and, if it is an array or a map,
+    /// its length.
+    pub fn read_header(&mut self) -> Result<Header, Error> {
+        let marker = Marker::from_u8(self.data.read_u8()?);
+        let header = match marker {
+            Marker::FixArray(len) => Header::Array(len.into()),
+            Marker::Array16 => Header::Array(self.data.read_u16()?.into()),
+            Marker::Array32 => Header::Array(self.data.read_u32()?),
+            Marker::FixMap(len) => Header::Map(len.into()),
+            Marker::Map16 => Header::Map(self.data.read_u16()?.into()),
+            Marker::Map32 => Header::Map(self.data.read_u32()?),
+            _ => Header::Other,
+        };
+        Ok(header)
+    }
+
+    /// Validates the next object, a map key, and advances past it.
+    pub fn read_key(&mut self) -> Result<Key<'_>, Error> {
+        let marker = Marker::from_u8(self.data.read_u8()?);
+        let key = match marker {
+            Marker::FixStr(len) => return self.read_str_key(len.into()),
+            Marker::Str8 => {
+                let len = self.data.read_u8()?;
+                return self.read_str_key(len.into());
+            }
+            Marker::Str16 => {
+                let len = self.data.read_u16()?;
+                return self.read_str_key(len.into());
+            }
+            Marker::Str32 => {
+                let len = self.data.read_u32()?;
+                return self.read_str_key(len);
+            }
+            Marker::FixPos(value) => Key::Int(value.into()),
+            Marker::FixNeg(value) => Key::Int(value.into()),
+            Marker::U8 => Key::Int(self.data.read_u8()?.into()),
+            Marker::U16 => Key::Int(self.data.read_u16()?.into()),
+            Marker::U32 => Key::Int(self.data.read_u32()?.into()),
+            Marker::U64 => match i64::try_from(self.data.read_u64()?) {
+                Ok(value) => Key::Int(value),
+                Err(_) => Key::Other,
+            },
+            Marker::I8 => Key::Int(self.data.read_i8()?.into()),
+            Marker::I16 => Key::Int(self.data.read_i16()?.into()),
+            Marker::I32 => Key::Int(self.data.read_i32()?.into()),
+            Marker::I64 => Key::Int(self.data.read_i64()?),
+            _ => {
+                self.skip_marker(marker)?;
+                Key::Other
+            }
+        };
+        Ok(key)
+    }
+
+    fn read_str_key(&mut self, len: u32) -> Result<Key<'_>, Error> {
+        let data = self.data.read_slice(len as usize)?;
+        from_utf8(data)?;
+        Ok(Key::Str(data))
+    }
+
+    /// Reads the header of the next object and, if it is a map holding
+    /// `item` as a key or an array holding `item` as an index, advances to
+    /// the corresponding value and returns `true`. Otherwise returns `false`
+    /// with the position unspecified. If a map holds the key more than once,
+    /// the first occurrence is used.
+    pub fn seek(&mut self, item: &PathItem) -> Result<bool, Error> {
+        match self.read_header()? {
+            Header::Array(len) => {
+                let index = match *item {
+                    PathItem::Int(index) if index < 0 => index + i64::from(len),
+                    PathItem::Int(index) => index,
+                    PathItem::Key(_) => return Ok(false),
+                };
+                if !(0..i64::from(len)).contains(&index) {
+                    return Ok(false);
+                }
+                for _ in 0..index {
+                    self.skip()?;
+                }
+                Ok(true)
+            }
+            Header::Map(len) => {
+                for _ in 0..len {
+                    if item.matches(&self.read_key()?) {
+                        return Ok(true);
+                    }
+                    self.skip()?;
+                }
+                Ok(false)
+            }
+            Header::Other => Ok(false),
+        }
+    }
+
+    fn skip_marker(&mut self, marker: Marker) -> Result<(), Error> {
         self.recursion += 1;
         if unlikely!(self.recursion == RECURSION_LIMIT) {
             return Err(Error::RecursionLimitReached);
         }
 
-        let marker = Marker::from_u8(self.data.read_u8()?);
         match marker {
             Marker::Null => Ok(()),
             Marker::True => Ok(()),
@@ -215,10 +370,6 @@ where
         }?;
 
         self.recursion -= 1;
-        if self.recursion == 0 && !self.data.eof() {
-            Err(Error::InvalidValue)
-        } else {
-            Ok(())
-        }
+        Ok(())
     }
 }
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-pub type Opt = u16;
+pub type Opt = u32;
 
 pub const NAIVE_UTC: Opt = 1;
 pub const NON_STR_KEYS: Opt = 1 << 1;
@@ -18,16 +18,34 @@ pub const PASSTHROUGH_UUID: Opt = 1 << 12;
 pub const PASSTHROUGH_ENUM: Opt = 1 << 13;
 pub const DATETIME_AS_TIMESTAMP_EXT: Opt = 1 << 14;
 pub const REPLACE_SURROGATES: Opt = 1 << 15;
+pub const FORBID_TRAILING_DATA: Opt = 1 << 16;
+pub const ARRAY_AS_TUPLE: Opt = 1 << 17;
+pub const BIN_AS_BYTEARRAY: Opt = 1 << 18;
+pub const BIN_AS_MEMORYVIEW: Opt = 1 << 19;
+pub const FORBID_DUPLICATE_KEYS: Opt = 1 << 20;
+pub const TIMESTAMP_AS_INT: Opt = 1 << 21;
+pub const TIMESTAMP_FALLBACK: Opt = 1 << 22;
+pub const ARRAY_AS_NUMPY: Opt = 1 << 23;
+pub const BIG_INT_AS_EXT: Opt = 1 << 24;
+pub const DECIMAL_AS_EXT: Opt = 1 << 25;
+pub const COMPLEX_AS_EXT: Opt = 1 << 26;
+pub const TIMEDELTA_AS_MICROSECONDS: Opt = 1 << 27;
+pub const TIMEDELTA_AS_SECONDS: Opt = 1 << 28;
+pub const PASSTHROUGH_NUMBER: Opt = 1 << 29;
 
 pub const NOT_PASSTHROUGH: Opt = !(PASSTHROUGH_BIG_INT
     | PASSTHROUGH_DATACLASS
     | PASSTHROUGH_DATETIME
     | PASSTHROUGH_ENUM
+    | PASSTHROUGH_NUMBER
     | PASSTHROUGH_SUBCLASS
     | PASSTHROUGH_TUPLE
     | PASSTHROUGH_UUID);
 
-pub const PACKB_OPT_MASK: i32 = (DATETIME_AS_TIMESTAMP_EXT
+pub const PACKB_OPT_MASK: i32 = (BIG_INT_AS_EXT
+    | COMPLEX_AS_EXT
+    | DATETIME_AS_TIMESTAMP_EXT
+    | DECIMAL_AS_EXT
     | NAIVE_UTC
     | NON_STR_KEYS
     | OMIT_MICROSECONDS
@@ -35,6 +53,7 @@ pub const PACKB_OPT_MASK: i32 = (DATETIME_AS_TIMESTAMP_EXT
     | PASSTHROUGH_DATACLASS
     | PASSTHROUGH_DATETIME
     | PASSTHROUGH_ENUM
+    | PASSTHROUGH_NUMBER
     | PASSTHROUGH_SUBCLASS
     | PASSTHROUGH_TUPLE
     | PASSTHROUGH_UUID
@@ -42,6 +61,24 @@ pub const PACKB_OPT_MASK: i32 = (DATETIME_AS_TIMESTAMP_EXT
     | SERIALIZE_NUMPY
     | SERIALIZE_PYDANTIC
     | SORT_KEYS
+    | TIMEDELTA_AS_MICROSECONDS
+    | TIMEDELTA_AS_SECONDS
     | UTC_Z) as i32;
 
-pub const UNPACKB_OPT_MASK: i32 = (DATETIME_AS_TIMESTAMP_EXT | NON_STR_KEYS) as i32;
+pub const UNPACKB_OPT_MASK: i32 = (ARRAY_AS_NUMPY
+    | ARRAY_AS_TUPLE
+    | BIG_INT_AS_EXT
+    | BIN_AS_BYTEARRAY
+    | BIN_AS_MEMORYVIEW
+    | COMPLEX_AS_EXT
+    | DATETIME_AS_TIMESTAMP_EXT
+    | DECIMAL_AS_EXT
+    | FORBID_DUPLICATE_KEYS
+    | FORBID_TRAILING_DATA
+    | NAIVE_UTC
+    | NON_STR_KEYS
+    | TIMESTAMP_AS_INT
+    | TIMESTAMP_FALLBACK) as i32;
+
+/// The options of the functions that deserialize only part of their input.
+pub const UNPACKB_PARTIAL_OPT_MASK: i32 = UNPACKB_OPT_MASK & !(FORBID_TRAILING_DATA as i32);
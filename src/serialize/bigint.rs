@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::pylong_to_be_bytes;
+use crate::msgpack::BIG_INT_EXT_TYPE;
+use serde::ser::{Serialize, Serializer};
+use serde_bytes::Bytes;
+
+/// An int that exceeds the 64-bit range, serialized as an extension object.
+#[repr(transparent)]
+pub struct BigInt {
+    ptr: *mut pyo3::ffi::PyObject,
+}
+
+impl BigInt {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject) -> Self {
+        BigInt { ptr: ptr }
+    }
+}
+
+impl Serialize for BigInt {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let data = pylong_to_be_bytes(self.ptr);
+        serializer.serialize_newtype_variant("", BIG_INT_EXT_TYPE as u32, "", Bytes::new(&data))
+    }
+}
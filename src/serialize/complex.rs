@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::msgpack::COMPLEX_EXT_TYPE;
+use crate::opt::*;
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use serde_bytes::Bytes;
+
+/// A `complex` instance, serialized as an array of its real and imaginary
+/// parts, or as an extension object with `OPT_COMPLEX_AS_EXT`.
+pub struct Complex {
+    ptr: *mut pyo3::ffi::PyObject,
+    opts: Opt,
+}
+
+impl Complex {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject, opts: Opt) -> Self {
+        Complex {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+}
+
+impl Serialize for Complex {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let real = unsafe { pyo3::ffi::PyComplex_RealAsDouble(self.ptr) };
+        let imag = unsafe { pyo3::ffi::PyComplex_ImagAsDouble(self.ptr) };
+        if self.opts & COMPLEX_AS_EXT != 0 {
+            let mut data = [0u8; 16];
+            data[..8].copy_from_slice(&real.to_be_bytes());
+            data[8..].copy_from_slice(&imag.to_be_bytes());
+            serializer.serialize_newtype_variant("", COMPLEX_EXT_TYPE as u32, "", Bytes::new(&data))
+        } else {
+            let mut seq = serializer.serialize_seq(Some(2))?;
+            seq.serialize_element(&real)?;
+            seq.serialize_element(&imag)?;
+            seq.end()
+        }
+    }
+}
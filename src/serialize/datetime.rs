@@ -2,7 +2,7 @@
 
 use crate::ffi::*;
 use crate::opt::*;
-use crate::serialize::datetimelike::{DateLike, DateTimeLike, TimeLike};
+use crate::serialize::datetimelike::{DateLike, DateTimeLike, DurationLike, TimeLike};
 use crate::state::State;
 use serde::ser::{Serialize, Serializer};
 use serde_bytes::Bytes;
@@ -108,6 +108,123 @@ impl Serialize for Time {
     }
 }
 
+pub enum TimedeltaError {
+    IncompatibleOptions,
+    MicrosecondsOverflow,
+    TotalSecondsFailed,
+}
+
+impl std::fmt::Display for TimedeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompatibleOptions => write!(
+                f,
+                "OPT_TIMEDELTA_AS_MICROSECONDS is not compatible with OPT_TIMEDELTA_AS_SECONDS"
+            ),
+            Self::MicrosecondsOverflow => {
+                write!(f, "timedelta microseconds exceed 64-bit range")
+            }
+            Self::TotalSecondsFailed => write!(f, "timedelta total_seconds failed"),
+        }
+    }
+}
+
+/// A `datetime.timedelta` instance, serialized as an ISO 8601 duration, or as
+/// a number of microseconds or seconds with `OPT_TIMEDELTA_AS_MICROSECONDS`
+/// or `OPT_TIMEDELTA_AS_SECONDS`.
+pub struct Timedelta {
+    ptr: *mut pyo3::ffi::PyObject,
+    opts: Opt,
+}
+
+impl Timedelta {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject, opts: Opt) -> Self {
+        Timedelta {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+
+    /// Returns the duration in seconds, rounded as by
+    /// `timedelta.total_seconds()`, or `None` with an exception set.
+    fn total_seconds(&self) -> Option<f64> {
+        let microseconds = self.total_microseconds();
+        if microseconds.unsigned_abs() < 1 << 53 {
+            return Some(microseconds as f64 / 1_000_000.0);
+        }
+        unsafe {
+            let numerator = pylong_from_be_bytes(&microseconds.to_be_bytes());
+            if numerator.is_null() {
+                return None;
+            }
+            let denominator = pyo3::ffi::PyLong_FromLong(1_000_000);
+            if denominator.is_null() {
+                pyo3::ffi::Py_DECREF(numerator);
+                return None;
+            }
+            let quotient = pyo3::ffi::PyNumber_TrueDivide(numerator, denominator);
+            pyo3::ffi::Py_DECREF(denominator);
+            pyo3::ffi::Py_DECREF(numerator);
+            if quotient.is_null() {
+                return None;
+            }
+            let value = pyo3::ffi::PyFloat_AsDouble(quotient);
+            pyo3::ffi::Py_DECREF(quotient);
+            Some(value)
+        }
+    }
+}
+
+impl DurationLike for Timedelta {
+    fn days(&self) -> i32 {
+        unsafe { pyo3::ffi::PyDateTime_DELTA_GET_DAYS(self.ptr) as i32 }
+    }
+
+    fn seconds(&self) -> i32 {
+        unsafe { pyo3::ffi::PyDateTime_DELTA_GET_SECONDS(self.ptr) as i32 }
+    }
+
+    fn microseconds(&self) -> i32 {
+        unsafe { pyo3::ffi::PyDateTime_DELTA_GET_MICROSECONDS(self.ptr) as i32 }
+    }
+}
+
+impl Serialize for Timedelta {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.opts & (TIMEDELTA_AS_MICROSECONDS | TIMEDELTA_AS_SECONDS) {
+            0 => {
+                let mut cursor = std::io::Cursor::new([0u8; 32]);
+                DurationLike::write_iso8601(self, &mut cursor).unwrap();
+                let len = cursor.position() as usize;
+                let value = unsafe { std::str::from_utf8_unchecked(&cursor.get_ref()[0..len]) };
+                serializer.serialize_str(value)
+            }
+            TIMEDELTA_AS_MICROSECONDS => match i64::try_from(self.total_microseconds()) {
+                Ok(value) => serializer.serialize_i64(value),
+                Err(_) => Err(serde::ser::Error::custom(
+                    TimedeltaError::MicrosecondsOverflow,
+                )),
+            },
+            TIMEDELTA_AS_SECONDS => match self.total_seconds() {
+                Some(value) => serializer.serialize_f64(value),
+                None => {
+                    unsafe { pyo3::ffi::PyErr_Clear() };
+                    Err(serde::ser::Error::custom(
+                        TimedeltaError::TotalSecondsFailed,
+                    ))
+                }
+            },
+            _ => Err(serde::ser::Error::custom(
+                TimedeltaError::IncompatibleOptions,
+            )),
+        }
+    }
+}
+
 pub enum DateTimeError {
     LibraryUnsupported,
 }
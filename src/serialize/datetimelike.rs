@@ -11,7 +11,7 @@ where
 {
     let mut itoa_buf = itoa::Buffer::new();
     let formatted = itoa_buf.format(value);
-    for _ in 0..width - formatted.len() {
+    for _ in 0..width.saturating_sub(formatted.len()) {
         writer.write_all(b"0")?;
     }
     let len = writer.write(formatted.as_bytes())?;
@@ -63,6 +63,63 @@ pub trait TimeLike {
     }
 }
 
+pub trait DurationLike {
+    fn days(&self) -> i32;
+    fn seconds(&self) -> i32;
+    fn microseconds(&self) -> i32;
+
+    fn total_microseconds(&self) -> i128 {
+        (i128::from(self.days()) * 86_400 + i128::from(self.seconds())) * 1_000_000
+            + i128::from(self.microseconds())
+    }
+
+    fn write_iso8601<W>(&self, writer: &mut W) -> Result<(), std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        let total = self.total_microseconds();
+        if total < 0 {
+            writer.write_all(b"-")?;
+        }
+        let total = total.unsigned_abs();
+        let days = (total / 86_400_000_000) as i32;
+        let seconds = (total / 1_000_000 % 86_400) as i32;
+        let mut microsecond = (total % 1_000_000) as i32;
+        writer.write_all(b"P")?;
+        if days != 0 {
+            write_integer(writer, days, 1)?;
+            writer.write_all(b"D")?;
+            if seconds == 0 && microsecond == 0 {
+                return Ok(());
+            }
+        }
+        writer.write_all(b"T")?;
+        let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hour != 0 {
+            write_integer(writer, hour, 1)?;
+            writer.write_all(b"H")?;
+        }
+        if minute != 0 {
+            write_integer(writer, minute, 1)?;
+            writer.write_all(b"M")?;
+        }
+        if second != 0 || microsecond != 0 || seconds == 0 {
+            write_integer(writer, second, 1)?;
+            if microsecond != 0 {
+                let mut width = 6;
+                while microsecond % 10 == 0 {
+                    microsecond /= 10;
+                    width -= 1;
+                }
+                writer.write_all(b".")?;
+                write_integer(writer, microsecond, width)?;
+            }
+            writer.write_all(b"S")?;
+        }
+        Ok(())
+    }
+}
+
 pub trait DateTimeLike: DateLike + TimeLike {
     fn offset(&self) -> Option<i32>;
     fn to_utc_datetime(&self) -> chrono::DateTime<chrono::Utc>;
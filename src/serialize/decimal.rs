@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use crate::msgpack::DECIMAL_EXT_TYPE;
+use crate::opt::*;
+use serde::ser::{Serialize, Serializer};
+use serde_bytes::Bytes;
+
+pub enum DecimalError {
+    NotFinite,
+    ExponentOverflow,
+}
+
+impl std::fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFinite => write!(f, "Decimal is not finite"),
+            Self::ExponentOverflow => write!(f, "Decimal exponent exceeds 32-bit range"),
+        }
+    }
+}
+
+/// Returns the big-endian representation of a string of decimal digits, in
+/// the smallest number of bytes. The conversion is done in Rust rather than
+/// through an int, as CPython limits the number of digits an int can be
+/// created from.
+fn digits_to_be_bytes(digits: &str) -> Vec<u8> {
+    if let Ok(value) = digits.parse::<u64>() {
+        let bytes = value.to_be_bytes();
+        return bytes[(value.leading_zeros() / 8) as usize..].to_vec();
+    }
+    // Little-endian limbs in base 2**32, fed with chunks of up to 9 digits.
+    let mut limbs: Vec<u32> = Vec::with_capacity(digits.len() / 9 + 1);
+    let mut start = 0;
+    let mut end = match digits.len() % 9 {
+        0 => 9,
+        head => head,
+    };
+    while start < digits.len() {
+        let chunk = &digits[start..end];
+        let mut carry = chunk.parse::<u64>().unwrap_or_else(|_| unreachable!());
+        let factor = 10u64.pow(chunk.len() as u32);
+        for limb in limbs.iter_mut() {
+            let value = u64::from(*limb) * factor + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+        start = end;
+        end += 9;
+    }
+    let bytes: Vec<u8> = limbs
+        .iter()
+        .rev()
+        .flat_map(|limb| limb.to_be_bytes())
+        .collect();
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    bytes[zeros..].to_vec()
+}
+
+/// Returns the data of the extension object of a decimal, given its string
+/// representation.
+fn ext_data(value: &str) -> Result<Vec<u8>, DecimalError> {
+    let (mantissa, exponent) = match value.find('E') {
+        Some(i) => match value[i + 1..].parse::<i64>() {
+            Ok(exponent) => (&value[..i], exponent),
+            Err(_) => return Err(DecimalError::ExponentOverflow),
+        },
+        None => (value, 0),
+    };
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => (1u8, mantissa),
+        None => (0u8, mantissa),
+    };
+    if !mantissa.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(DecimalError::NotFinite);
+    }
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let exponent = i32::try_from(exponent - fraction.len() as i64)
+        .map_err(|_| DecimalError::ExponentOverflow)?;
+    let coefficient = digits_to_be_bytes(&format!("{integer}{fraction}"));
+    let mut data = Vec::with_capacity(5 + coefficient.len());
+    data.extend_from_slice(&exponent.to_be_bytes());
+    data.push(sign);
+    data.extend_from_slice(&coefficient);
+    Ok(data)
+}
+
+/// A `decimal.Decimal` instance, serialized as its string representation, or
+/// as an extension object with `OPT_DECIMAL_AS_EXT`.
+pub struct Decimal {
+    ptr: *mut pyo3::ffi::PyObject,
+    opts: Opt,
+}
+
+impl Decimal {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject, opts: Opt) -> Self {
+        Decimal {
+            ptr: ptr,
+            opts: opts,
+        }
+    }
+}
+
+impl Serialize for Decimal {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str_obj = unsafe { pyo3::ffi::PyObject_Str(self.ptr) };
+        if unlikely!(str_obj.is_null()) {
+            unsafe { pyo3::ffi::PyErr_Clear() };
+            return Err(serde::ser::Error::custom("Decimal str failed"));
+        }
+        let value = unicode_to_str(str_obj).unwrap_or_else(|_| unreachable!());
+        let res = if self.opts & DECIMAL_AS_EXT != 0 {
+            match ext_data(value) {
+                Ok(data) => serializer.serialize_newtype_variant(
+                    "",
+                    DECIMAL_EXT_TYPE as u32,
+                    "",
+                    Bytes::new(&data),
+                ),
+                Err(err) => Err(serde::ser::Error::custom(err)),
+            }
+        } else {
+            serializer.serialize_str(value)
+        };
+        unsafe { pyo3::ffi::Py_DECREF(str_obj) };
+        res
+    }
+}
@@ -27,17 +27,35 @@ impl std::fmt::Display for Error {
 
 pub struct DefaultHook {
     pub inner: Option<NonNull<pyo3::ffi::PyObject>>,
+    /// The mapping of types to `(tag, encoder)` tuples, consulted before
+    /// `default`.
+    pub ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
     recursion: Cell<u8>,
 }
 
 impl DefaultHook {
-    pub fn new(default: Option<NonNull<pyo3::ffi::PyObject>>) -> Self {
+    pub fn new(
+        default: Option<NonNull<pyo3::ffi::PyObject>>,
+        ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    ) -> Self {
         DefaultHook {
             inner: default,
+            ext_types: ext_types,
             recursion: Cell::new(0),
         }
     }
 
+    /// Returns the entry of `ext_types` for the type `ob_type`, or null.
+    #[inline(always)]
+    pub fn ext_type(&self, ob_type: *mut pyo3::ffi::PyTypeObject) -> *mut pyo3::ffi::PyObject {
+        match self.ext_types {
+            Some(ext_types) => unsafe {
+                pyo3::ffi::PyDict_GetItem(ext_types.as_ptr(), ob_type.cast::<pyo3::ffi::PyObject>())
+            },
+            None => std::ptr::null_mut(),
+        }
+    }
+
     pub fn enter_call(
         &self,
         ptr: *mut pyo3::ffi::PyObject,
@@ -121,7 +121,7 @@ impl Dict<'_> {
                 )?;
             } else {
                 map.serialize_entry(
-                    &DictKey::new(key.as_ptr(), self.state, opts),
+                    &DictKey::new(key.as_ptr(), self.state, opts, self.default),
                     &PyObject::new(value.as_ptr(), self.state, self.opts, self.default),
                 )?;
             }
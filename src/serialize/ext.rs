@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::ext::PyExt;
-use crate::ffi::pybytes_as_bytes;
+use crate::ffi::{pybytes_as_bytes, pyobject_call_one_arg};
 use serde::ser::{Serialize, Serializer};
 use serde_bytes::Bytes;
 
@@ -31,3 +31,78 @@ impl Serialize for Ext {
         serializer.serialize_newtype_variant("", tag as u32, "", Bytes::new(data))
     }
 }
+
+/// Returns the extension type and the encoder of an entry of `ext_types`, a
+/// `(tag, encoder)` tuple, if it is valid.
+pub fn ext_type_entry(entry: *mut pyo3::ffi::PyObject) -> Option<(u8, *mut pyo3::ffi::PyObject)> {
+    unsafe {
+        if ob_type!(entry) != &raw mut pyo3::ffi::PyTuple_Type || pyo3::ffi::Py_SIZE(entry) != 2 {
+            return None;
+        }
+        let tag = crate::ffi::pytuple_get_item(entry, 0);
+        let encoder = crate::ffi::pytuple_get_item(entry, 1);
+        if ob_type!(tag) != &raw mut pyo3::ffi::PyLong_Type
+            || pyo3::ffi::PyCallable_Check(encoder) == 0
+        {
+            return None;
+        }
+        let mut overflow: std::os::raw::c_int = 0;
+        let tag = pyo3::ffi::PyLong_AsLongAndOverflow(tag, &mut overflow);
+        if overflow != 0 || !(0..=127).contains(&tag) {
+            return None;
+        }
+        Some((tag as u8, encoder))
+    }
+}
+
+/// An object of a type registered in `ext_types`, serialized as an extension
+/// object with the data returned by the encoder.
+pub struct ExtType {
+    ptr: *mut pyo3::ffi::PyObject,
+    entry: *mut pyo3::ffi::PyObject,
+}
+
+impl ExtType {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject, entry: *mut pyo3::ffi::PyObject) -> Self {
+        ExtType {
+            ptr: ptr,
+            entry: entry,
+        }
+    }
+}
+
+impl Serialize for ExtType {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (tag, encoder) = match ext_type_entry(self.entry) {
+            Some(value) => value,
+            None => return Err(serde::ser::Error::custom("Invalid ext_types")),
+        };
+        let data = unsafe {
+            pyo3::ffi::Py_INCREF(encoder);
+            let data = pyobject_call_one_arg(encoder, self.ptr);
+            pyo3::ffi::Py_DECREF(encoder);
+            data
+        };
+        if unlikely!(data.is_null()) {
+            return Err(serde::ser::Error::custom("ext_types encoder failed"));
+        }
+        if unlikely!(ob_type!(data) != &raw mut pyo3::ffi::PyBytes_Type) {
+            unsafe { pyo3::ffi::Py_DECREF(data) };
+            return Err(serde::ser::Error::custom(
+                "ext_types encoder must return bytes",
+            ));
+        }
+        let res = serializer.serialize_newtype_variant(
+            "",
+            tag.into(),
+            "",
+            Bytes::new(unsafe { pybytes_as_bytes(data) }),
+        );
+        unsafe { pyo3::ffi::Py_DECREF(data) };
+        res
+    }
+}
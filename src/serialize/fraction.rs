@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::opt::*;
+use crate::serialize::default::DefaultHook;
+use crate::serialize::serializer::*;
+use crate::state::State;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// A `fractions.Fraction` instance, serialized as an array of its numerator
+/// and denominator.
+pub struct Fraction<'a> {
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    opts: Opt,
+    default: &'a DefaultHook,
+}
+
+impl<'a> Fraction<'a> {
+    pub fn new(
+        ptr: *mut pyo3::ffi::PyObject,
+        state: *mut State,
+        opts: Opt,
+        default: &'a DefaultHook,
+    ) -> Self {
+        Fraction {
+            ptr: ptr,
+            state: state,
+            opts: opts,
+            default: default,
+        }
+    }
+}
+
+impl Serialize for Fraction<'_> {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(2))?;
+        for name in unsafe { [(*self.state).numerator_str, (*self.state).denominator_str] } {
+            let item = unsafe { pyo3::ffi::PyObject_GetAttr(self.ptr, name) };
+            if unlikely!(item.is_null()) {
+                unsafe { pyo3::ffi::PyErr_Clear() };
+                return Err(serde::ser::Error::custom("Invalid Fraction"));
+            }
+            let res =
+                seq.serialize_element(&PyObject::new(item, self.state, self.opts, self.default));
+            unsafe { pyo3::ffi::Py_DECREF(item) };
+            res?;
+        }
+        seq.end()
+    }
+}
@@ -1,22 +1,33 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+mod bigint;
 mod bytearray;
 mod bytes;
+mod complex;
 mod dataclass;
 mod datetime;
 mod datetimelike;
+mod decimal;
 mod default;
 mod dict;
 mod ext;
+mod fraction;
 mod fragment;
 mod list;
 mod memoryview;
 mod numpy;
 mod pydantic;
 mod serializer;
+mod set;
+mod stdlib;
 mod str;
+mod timestamp;
 mod tuple;
 mod uuid;
 mod writer;
 
-pub use serializer::serialize;
+pub use ext::ext_type_entry;
+pub use serializer::{
+    serialize, serialize_frame, serialize_into_buffer, serialize_into_stream,
+    serialize_with_capacity,
+};
@@ -1,21 +1,30 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::ffi::*;
+use crate::frame;
+use crate::io::WriteSlices;
 use crate::msgpack;
 use crate::opt::*;
+use crate::serialize::bigint::*;
 use crate::serialize::bytearray::*;
 use crate::serialize::bytes::*;
+use crate::serialize::complex::*;
 use crate::serialize::dataclass::*;
 use crate::serialize::datetime::*;
+use crate::serialize::decimal::*;
 use crate::serialize::default::*;
 use crate::serialize::dict::*;
 use crate::serialize::ext::*;
+use crate::serialize::fraction::*;
 use crate::serialize::fragment::*;
 use crate::serialize::list::*;
 use crate::serialize::memoryview::*;
 use crate::serialize::numpy::*;
 use crate::serialize::pydantic::*;
+use crate::serialize::set::*;
+use crate::serialize::stdlib::*;
 use crate::serialize::str::*;
+use crate::serialize::timestamp::*;
 use crate::serialize::tuple::*;
 use crate::serialize::uuid::*;
 use crate::serialize::writer::*;
@@ -28,20 +37,154 @@ pub fn serialize(
     ptr: *mut pyo3::ffi::PyObject,
     state: *mut State,
     default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
     opts: Opt,
 ) -> Result<NonNull<pyo3::ffi::PyObject>, String> {
-    let mut buf = BytesWriter::default();
-    let default_hook = DefaultHook::new(default);
-    let obj = PyObject::new(ptr, state, opts, &default_hook);
-    let mut ser = msgpack::Serializer::new(&mut buf);
-    let res = obj.serialize(&mut ser);
+    serialize_with_capacity(ptr, state, default, ext_types, opts, BUFFER_LENGTH)
+}
+
+/// Serializes to a bytes object that is initially allocated with `cap` bytes,
+/// and shrunk to the length of the output.
+pub fn serialize_with_capacity(
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    opts: Opt,
+    cap: usize,
+) -> Result<NonNull<pyo3::ffi::PyObject>, String> {
+    let mut buf = BytesWriter::with_capacity(cap);
+    match serialize_into(ptr, state, default, ext_types, opts, &mut buf) {
+        Ok(()) => Ok(buf.finish()),
+        Err(err) => {
+            unsafe { pyo3::ffi::Py_DECREF(buf.finish().as_ptr()) };
+            Err(err)
+        }
+    }
+}
+
+pub fn serialize_frame(
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    opts: Opt,
+    checksum: bool,
+) -> Result<NonNull<pyo3::ffi::PyObject>, String> {
+    let mut buf = BytesWriter::with_prefix(frame::HEADER_LENGTH);
+    let res = serialize_into(ptr, state, default, ext_types, opts, &mut buf).and_then(|()| {
+        let data = buf.as_mut_slice();
+        let (header, payload) = data.split_at_mut(frame::HEADER_LENGTH);
+        let len = match u32::try_from(payload.len()) {
+            Ok(len) => len,
+            Err(_) => return Err("Frame payload too large".to_string()),
+        };
+        header.copy_from_slice(&len.to_be_bytes());
+        if checksum {
+            let crc = frame::crc32(payload);
+            buf.write_slices([&crc.to_be_bytes()]).unwrap();
+        }
+        Ok(())
+    });
     match res {
-        Ok(_) => Ok(buf.finish()),
+        Ok(()) => Ok(buf.finish()),
         Err(err) => {
             unsafe { pyo3::ffi::Py_DECREF(buf.finish().as_ptr()) };
-            Err(err.to_string())
+            Err(err)
+        }
+    }
+}
+
+pub fn serialize_into<W>(
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    opts: Opt,
+    writer: W,
+) -> Result<(), String>
+where
+    W: WriteSlices,
+{
+    let default_hook = DefaultHook::new(default, ext_types);
+    let obj = PyObject::new(ptr, state, opts, &default_hook);
+    let mut ser = msgpack::Serializer::new(writer);
+    obj.serialize(&mut ser).map_err(|err| err.to_string())
+}
+
+/// Serializes into a bytearray, growing it as needed, or into a writable
+/// buffer, starting at `offset`. The data is written directly to the buffer,
+/// through an export that is held for the whole serialization so that
+/// `default` cannot resize a bytearray. Only the data that does not fit in a
+/// bytearray is written to a temporary buffer, to be copied once the
+/// bytearray has been grown.
+pub fn serialize_into_buffer(
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    opts: Opt,
+    buffer: *mut pyo3::ffi::PyObject,
+    offset: usize,
+) -> Result<usize, String> {
+    let growable = ob_type!(buffer) == &raw mut pyo3::ffi::PyByteArray_Type;
+    let mut view = pyo3::ffi::Py_buffer::new();
+    if unsafe { pyo3::ffi::PyObject_GetBuffer(buffer, &mut view, pyo3::ffi::PyBUF_CONTIG) } == -1 {
+        unsafe { pyo3::ffi::PyErr_Clear() };
+        return Err("Buffer must be a bytearray or a writable C contiguous buffer".to_string());
+    }
+    let cap = view.len as usize;
+    if offset > cap {
+        unsafe { pyo3::ffi::PyBuffer_Release(&mut view) };
+        return Err("Offset out of range".to_string());
+    }
+    let mut writer =
+        unsafe { BufferWriter::new(view.buf.cast::<u8>().add(offset), cap - offset, growable) };
+    let res = serialize_into(ptr, state, default, ext_types, opts, &mut writer);
+    unsafe { pyo3::ffi::PyBuffer_Release(&mut view) };
+    res?;
+    let len = writer.len();
+    if len <= cap - offset {
+        return Ok(len);
+    }
+    if !growable {
+        return Err(format!(
+            "Buffer too small: {} bytes required, {} bytes available",
+            len,
+            cap - offset
+        ));
+    }
+    unsafe {
+        if pyo3::ffi::PyByteArray_Resize(buffer, (offset + len) as isize) == -1 {
+            pyo3::ffi::PyErr_Clear();
+            return Err("Failed to resize bytearray".to_string());
         }
+        let overflow = writer.overflow();
+        let dst = pyo3::ffi::PyByteArray_AsString(buffer)
+            .cast::<u8>()
+            .add(cap);
+        std::ptr::copy_nonoverlapping(overflow.as_ptr(), dst, overflow.len());
     }
+    Ok(len)
+}
+
+/// Serializes to a binary file object. `Err(None)` is returned if `fp.write`
+/// raised an exception, which is left set.
+pub fn serialize_into_stream(
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    default: Option<NonNull<pyo3::ffi::PyObject>>,
+    ext_types: Option<NonNull<pyo3::ffi::PyObject>>,
+    opts: Opt,
+    fp: *mut pyo3::ffi::PyObject,
+) -> Result<(), Option<String>> {
+    let mut writer = StreamWriter::new(fp, unsafe { (*state).write_str });
+    let res = serialize_into(ptr, state, default, ext_types, opts, &mut writer);
+    if writer.failed() {
+        return Err(None);
+    }
+    res.map_err(Some)?;
+    writer.finish().map_err(|_| None)
 }
 
 #[inline(always)]
@@ -92,6 +235,11 @@ impl<'a> PyObject<'a> {
     {
         let ob_type = ob_type!(self.ptr);
 
+        let entry = self.default.ext_type(ob_type);
+        if !entry.is_null() {
+            return ExtType::new(self.ptr, entry).serialize(serializer);
+        }
+
         if self.opts & PASSTHROUGH_DATETIME == 0 {
             let datetime_api = unsafe { *pyo3::ffi::PyDateTimeAPI() };
             if ob_type == datetime_api.DateTimeType {
@@ -109,12 +257,21 @@ impl<'a> PyObject<'a> {
                     Err(err) => return Err(serde::ser::Error::custom(err)),
                 };
             }
+            if ob_type == datetime_api.DeltaType {
+                return Timedelta::new(self.ptr, self.opts).serialize(serializer);
+            }
         }
 
         if self.opts & PASSTHROUGH_TUPLE == 0 && ob_type == &raw mut pyo3::ffi::PyTuple_Type {
             return Tuple::new(self.ptr, self.state, self.opts, self.default).serialize(serializer);
         }
 
+        if ob_type == &raw mut pyo3::ffi::PySet_Type
+            || ob_type == &raw mut pyo3::ffi::PyFrozenSet_Type
+        {
+            return Set::new(self.ptr, self.state, self.opts, self.default).serialize(serializer);
+        }
+
         if self.opts & PASSTHROUGH_UUID == 0 && ob_type == unsafe { (*self.state).uuid_type } {
             return UUID::new(self.ptr, self.state).serialize(serializer);
         }
@@ -141,6 +298,8 @@ impl<'a> PyObject<'a> {
                     Err(err) => {
                         if self.opts & PASSTHROUGH_BIG_INT != 0 {
                             return self.serialize_with_default_hook(serializer);
+                        } else if self.opts & BIG_INT_AS_EXT != 0 {
+                            return BigInt::new(self.ptr).serialize(serializer);
                         } else {
                             return Err(serde::ser::Error::custom(err));
                         }
@@ -161,6 +320,10 @@ impl<'a> PyObject<'a> {
             return Ext::new(self.ptr).serialize(serializer);
         }
 
+        if ob_type == unsafe { (*self.state).timestamp_type } {
+            return Timestamp::new(self.ptr).serialize(serializer);
+        }
+
         if self.opts & PASSTHROUGH_DATACLASS == 0 && is_dataclass(ob_type, self.state) {
             return Dataclass::new(self.ptr, self.state, self.opts, self.default)
                 .serialize(serializer);
@@ -242,6 +405,25 @@ impl<'a> PyObject<'a> {
             return Fragment::new(self.ptr).serialize(serializer);
         }
 
+        if self.opts & PASSTHROUGH_NUMBER == 0 {
+            if ob_type == &raw mut pyo3::ffi::PyComplex_Type {
+                return Complex::new(self.ptr, self.opts).serialize(serializer);
+            }
+            if ob_type == unsafe { (*self.state).imported_decimal_type() } {
+                return Decimal::new(self.ptr, self.opts).serialize(serializer);
+            }
+            if ob_type == unsafe { (*self.state).imported_fraction_type() } {
+                return Fraction::new(self.ptr, self.state, self.opts, self.default)
+                    .serialize(serializer);
+            }
+        }
+
+        // The types of the standard library modules are checked last, as
+        // they are looked up in sys.modules until the modules are imported.
+        if StdlibValue::is_supported(ob_type, self.state) {
+            return StdlibValue::new(self.ptr, self.state, self.opts).serialize(serializer);
+        }
+
         self.serialize_with_default_hook(serializer)
     }
 }
@@ -262,6 +444,8 @@ impl Serialize for PyObject<'_> {
                 Err(err) => {
                     if self.opts & PASSTHROUGH_BIG_INT != 0 {
                         self.serialize_with_default_hook(serializer)
+                    } else if self.opts & BIG_INT_AS_EXT != 0 {
+                        BigInt::new(self.ptr).serialize(serializer)
                     } else {
                         Err(serde::ser::Error::custom(err))
                     }
@@ -283,23 +467,30 @@ impl Serialize for PyObject<'_> {
     }
 }
 
-pub struct DictTupleKey {
+pub struct DictTupleKey<'a> {
     ptr: *mut pyo3::ffi::PyObject,
     state: *mut State,
     opts: Opt,
+    default: &'a DefaultHook,
 }
 
-impl DictTupleKey {
-    pub fn new(ptr: *mut pyo3::ffi::PyObject, state: *mut State, opts: Opt) -> Self {
+impl<'a> DictTupleKey<'a> {
+    pub fn new(
+        ptr: *mut pyo3::ffi::PyObject,
+        state: *mut State,
+        opts: Opt,
+        default: &'a DefaultHook,
+    ) -> Self {
         DictTupleKey {
             ptr: ptr,
             state: state,
             opts: opts,
+            default: default,
         }
     }
 }
 
-impl Serialize for DictTupleKey {
+impl Serialize for DictTupleKey<'_> {
     #[inline(never)]
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -309,25 +500,34 @@ impl Serialize for DictTupleKey {
         let mut seq = serializer.serialize_seq(Some(len))?;
         for i in 0..len {
             let item = unsafe { pytuple_get_item(self.ptr, i as isize) };
-            let value = DictKey::new(item, self.state, self.opts);
+            let value = DictKey::new(item, self.state, self.opts, self.default);
             seq.serialize_element(&value)?;
         }
         seq.end()
     }
 }
 
-pub struct DictKey {
+/// A map key, serialized with `OPT_NON_STR_KEYS`. `default` is not called
+/// for keys, only `ext_types` applies.
+pub struct DictKey<'a> {
     ptr: *mut pyo3::ffi::PyObject,
     state: *mut State,
     opts: Opt,
+    default: &'a DefaultHook,
 }
 
-impl DictKey {
-    pub fn new(ptr: *mut pyo3::ffi::PyObject, state: *mut State, opts: Opt) -> Self {
+impl<'a> DictKey<'a> {
+    pub fn new(
+        ptr: *mut pyo3::ffi::PyObject,
+        state: *mut State,
+        opts: Opt,
+        default: &'a DefaultHook,
+    ) -> Self {
         DictKey {
             ptr: ptr,
             state: state,
             opts: opts,
+            default: default,
         }
     }
 
@@ -338,6 +538,11 @@ impl DictKey {
     {
         let ob_type = ob_type!(self.ptr);
 
+        let entry = self.default.ext_type(ob_type);
+        if !entry.is_null() {
+            return ExtType::new(self.ptr, entry).serialize(serializer);
+        }
+
         let datetime_api = unsafe { *pyo3::ffi::PyDateTimeAPI() };
         if ob_type == datetime_api.DateTimeType {
             match DateTime::new(self.ptr, self.state, self.opts) {
@@ -354,19 +559,27 @@ impl DictKey {
                 Err(err) => return Err(serde::ser::Error::custom(err)),
             };
         }
+        if ob_type == datetime_api.DeltaType {
+            return Timedelta::new(self.ptr, self.opts).serialize(serializer);
+        }
 
         if ob_type == &raw mut pyo3::ffi::PyTuple_Type {
-            return DictTupleKey::new(self.ptr, self.state, self.opts).serialize(serializer);
+            return DictTupleKey::new(self.ptr, self.state, self.opts, self.default)
+                .serialize(serializer);
         }
 
         if ob_type == unsafe { (*self.state).uuid_type } {
             return UUID::new(self.ptr, self.state).serialize(serializer);
         }
 
+        if ob_type == unsafe { (*self.state).timestamp_type } {
+            return Timestamp::new(self.ptr).serialize(serializer);
+        }
+
         if ob_type!(ob_type) == unsafe { (*self.state).enum_type } {
             let value = unsafe { pyo3::ffi::PyObject_GetAttr(self.ptr, (*self.state).value_str) };
             unsafe { pyo3::ffi::Py_DECREF(value) };
-            return DictKey::new(value, self.state, self.opts).serialize(serializer);
+            return DictKey::new(value, self.state, self.opts, self.default).serialize(serializer);
         }
 
         if is_subclass(ob_type, pyo3::ffi::Py_TPFLAGS_UNICODE_SUBCLASS) {
@@ -375,6 +588,9 @@ impl DictKey {
         if is_subclass(ob_type, pyo3::ffi::Py_TPFLAGS_LONG_SUBCLASS) {
             match Int::new(self.ptr) {
                 Ok(val) => return val.serialize(serializer),
+                Err(_) if self.opts & BIG_INT_AS_EXT != 0 => {
+                    return BigInt::new(self.ptr).serialize(serializer)
+                }
                 Err(err) => return Err(serde::ser::Error::custom(err)),
             }
         }
@@ -383,13 +599,17 @@ impl DictKey {
             return MemoryView::new(self.ptr).serialize(serializer);
         }
 
+        if StdlibValue::is_supported(ob_type, self.state) {
+            return StdlibValue::new(self.ptr, self.state, self.opts).serialize(serializer);
+        }
+
         Err(serde::ser::Error::custom(
             "Dict key must a type serializable with OPT_NON_STR_KEYS",
         ))
     }
 }
 
-impl Serialize for DictKey {
+impl Serialize for DictKey<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -402,6 +622,9 @@ impl Serialize for DictKey {
         } else if ob_type == &raw mut pyo3::ffi::PyLong_Type {
             match Int::new(self.ptr) {
                 Ok(val) => val.serialize(serializer),
+                Err(_) if self.opts & BIG_INT_AS_EXT != 0 => {
+                    BigInt::new(self.ptr).serialize(serializer)
+                }
                 Err(err) => Err(serde::ser::Error::custom(err)),
             }
         } else if ob_type == &raw mut pyo3::ffi::PyBool_Type {
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::CriticalSection;
+use crate::msgpack::SORTED_ARRAY;
+use crate::opt::*;
+use crate::serialize::default::DefaultHook;
+use crate::serialize::serializer::*;
+use crate::state::State;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+/// A `set` or `frozenset` instance, serialized as an array. With
+/// `OPT_SORT_KEYS`, the elements are sorted by their serialized bytes.
+pub struct Set<'a> {
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    opts: Opt,
+    default: &'a DefaultHook,
+}
+
+impl<'a> Set<'a> {
+    pub fn new(
+        ptr: *mut pyo3::ffi::PyObject,
+        state: *mut State,
+        opts: Opt,
+        default: &'a DefaultHook,
+    ) -> Self {
+        Set {
+            ptr: ptr,
+            state: state,
+            opts: opts,
+            default: default,
+        }
+    }
+}
+
+impl Serialize for Set<'_> {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.opts & SORT_KEYS != 0 {
+            serializer.serialize_newtype_struct(SORTED_ARRAY, &Elements(self))
+        } else {
+            Elements(self).serialize(serializer)
+        }
+    }
+}
+
+/// The elements of a set, in iteration order.
+struct Elements<'a>(&'a Set<'a>);
+
+impl Serialize for Elements<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let set = self.0;
+        let mut critical_section = CriticalSection::new();
+        critical_section.begin(set.ptr);
+        let len = unsafe { pyo3::ffi::PySet_Size(set.ptr) } as usize;
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let iter = unsafe { pyo3::ffi::PyObject_GetIter(set.ptr) };
+        let mut count = 0;
+        let mut res = Ok(());
+        loop {
+            let item = unsafe { pyo3::ffi::PyIter_Next(iter) };
+            if item.is_null() {
+                break;
+            }
+            count += 1;
+            let value = PyObject::new(item, set.state, set.opts, set.default);
+            res = seq.serialize_element(&value);
+            unsafe { pyo3::ffi::Py_DECREF(item) };
+            if res.is_err() {
+                break;
+            }
+        }
+        unsafe { pyo3::ffi::Py_DECREF(iter) };
+        res?;
+        if unlikely!(count != len || !unsafe { pyo3::ffi::PyErr_Occurred() }.is_null()) {
+            unsafe { pyo3::ffi::PyErr_Clear() };
+            return Err(serde::ser::Error::custom(
+                "set changed size during iteration",
+            ));
+        }
+        seq.end()
+    }
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use crate::opt::*;
+use crate::serialize::str::Str;
+use crate::state::State;
+use serde::ser::{Serialize, Serializer};
+
+/// An instance of a `pathlib` path type, of an `ipaddress` address, network
+/// or interface type or of a `urllib.parse` result type, serialized as its
+/// string representation, or its URL for `urllib.parse` results.
+pub struct StdlibValue {
+    ptr: *mut pyo3::ffi::PyObject,
+    state: *mut State,
+    opts: Opt,
+}
+
+impl StdlibValue {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject, state: *mut State, opts: Opt) -> Self {
+        StdlibValue {
+            ptr: ptr,
+            state: state,
+            opts: opts,
+        }
+    }
+
+    /// Returns whether instances of `ob_type` are serialized as a
+    /// `StdlibValue`.
+    pub fn is_supported(ob_type: *mut pyo3::ffi::PyTypeObject, state: *mut State) -> bool {
+        unsafe {
+            (*state).imported_path_types().contains(&ob_type)
+                || (*state).imported_ipaddress_types().contains(&ob_type)
+                || (*state).imported_url_types().contains(&ob_type)
+        }
+    }
+}
+
+impl Serialize for StdlibValue {
+    #[inline(never)]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str_obj = unsafe {
+            if (*self.state)
+                .imported_url_types()
+                .contains(&ob_type!(self.ptr))
+            {
+                pyobject_call_method_no_args(self.ptr, (*self.state).geturl_str)
+            } else {
+                pyo3::ffi::PyObject_Str(self.ptr)
+            }
+        };
+        if unlikely!(str_obj.is_null()) {
+            unsafe { pyo3::ffi::PyErr_Clear() };
+            return Err(serde::ser::Error::custom("str conversion failed"));
+        }
+        if unlikely!(unsafe { pyo3::ffi::PyUnicode_CheckExact(str_obj) } == 0) {
+            unsafe { pyo3::ffi::Py_DECREF(str_obj) };
+            return Err(serde::ser::Error::custom("str conversion failed"));
+        }
+        let res = Str::new(str_obj, self.opts).serialize(serializer);
+        unsafe { pyo3::ffi::Py_DECREF(str_obj) };
+        res
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::msgpack;
+use crate::timestamp::PyTimestamp;
+use serde::ser::{Serialize, Serializer};
+use serde_bytes::Bytes;
+
+#[repr(transparent)]
+pub struct Timestamp {
+    ptr: *mut pyo3::ffi::PyObject,
+}
+
+impl Timestamp {
+    pub fn new(ptr: *mut pyo3::ffi::PyObject) -> Self {
+        Timestamp { ptr: ptr }
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let timestamp = self.ptr.cast::<PyTimestamp>();
+        let mut cursor = std::io::Cursor::new([0u8; 12]);
+        unsafe {
+            msgpack::write_timestamp_parts(
+                &mut cursor,
+                (*timestamp).seconds,
+                (*timestamp).nanoseconds,
+            )
+            .unwrap();
+        }
+        let len = cursor.position() as usize;
+        serializer.serialize_newtype_variant("", 128, "", Bytes::new(&cursor.get_ref()[0..len]))
+    }
+}
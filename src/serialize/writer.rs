@@ -5,7 +5,7 @@ use crate::io::WriteSlices;
 use pyo3::ffi::*;
 use std::ptr::NonNull;
 
-const BUFFER_LENGTH: usize = 1024;
+pub const BUFFER_LENGTH: usize = 1024;
 
 pub struct BytesWriter {
     cap: usize,
@@ -15,15 +15,35 @@ pub struct BytesWriter {
 
 impl BytesWriter {
     pub fn default() -> Self {
+        BytesWriter::with_capacity(BUFFER_LENGTH)
+    }
+
+    /// Creates a writer whose buffer initially holds `cap` bytes, at least
+    /// `BUFFER_LENGTH`.
+    pub fn with_capacity(cap: usize) -> Self {
+        let cap = cap.max(BUFFER_LENGTH);
         BytesWriter {
-            cap: BUFFER_LENGTH,
+            cap: cap,
             len: 0,
-            bytes: unsafe {
-                PyBytes_FromStringAndSize(std::ptr::null_mut(), BUFFER_LENGTH as isize)
-            },
+            bytes: unsafe { PyBytes_FromStringAndSize(std::ptr::null_mut(), cap as isize) },
         }
     }
 
+    /// Creates a writer whose first `len` bytes are left uninitialized, to be
+    /// filled in later through `as_mut_slice`.
+    pub fn with_prefix(len: usize) -> Self {
+        let mut writer = BytesWriter::default();
+        if len >= writer.cap {
+            writer.grow(len);
+        }
+        writer.len = len;
+        writer
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(pybytes_as_mut_u8(self.bytes), self.len) }
+    }
+
     pub fn finish(&mut self) -> NonNull<PyObject> {
         unsafe {
             std::ptr::write(self.buffer_ptr(), 0);
@@ -97,3 +117,212 @@ impl WriteSlices for BytesWriter {
         Ok(())
     }
 }
+
+/// Writes to a caller-supplied buffer of fixed size. Data that does not fit in
+/// the buffer is kept in an overflow vector if the buffer can be grown
+/// afterwards, and otherwise discarded, but its length is accounted for so
+/// that the required size can be reported.
+pub struct BufferWriter {
+    cap: usize,
+    len: usize,
+    buf: *mut u8,
+    growable: bool,
+    overflow: Vec<u8>,
+}
+
+impl BufferWriter {
+    pub fn new(buf: *mut u8, cap: usize, growable: bool) -> Self {
+        BufferWriter {
+            cap: cap,
+            len: 0,
+            buf: buf,
+            growable: growable,
+            overflow: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the data that did not fit in the buffer, if it is growable.
+    pub fn overflow(&self) -> &[u8] {
+        &self.overflow
+    }
+
+    fn insert_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) {
+        for buf in bufs {
+            let n = buf.len().min(self.cap.saturating_sub(self.len));
+            if n != 0 {
+                // The object being serialized may share memory with the
+                // buffer.
+                unsafe { std::ptr::copy(buf.as_ptr(), self.buf.add(self.len), n) };
+            }
+            if self.growable && n < buf.len() {
+                self.overflow.extend_from_slice(&buf[n..]);
+            }
+            self.len += buf.len();
+        }
+    }
+}
+
+impl std::io::Write for BufferWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        self.insert_slices([buf]);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), std::io::Error> {
+        self.insert_slices([buf]);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl WriteSlices for BufferWriter {
+    fn write_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) -> Result<(), std::io::Error> {
+        self.insert_slices(bufs);
+        Ok(())
+    }
+}
+
+const STREAM_CHUNK_LENGTH: usize = 65536;
+
+/// Writes to a binary file object through its `write` method in chunks of
+/// bounded size. If `write` raises an exception, the exception is left set and
+/// all subsequent writes fail.
+pub struct StreamWriter {
+    buf: Vec<u8>,
+    fp: *mut PyObject,
+    write_str: *mut PyObject,
+    failed: bool,
+}
+
+impl StreamWriter {
+    pub fn new(fp: *mut PyObject, write_str: *mut PyObject) -> Self {
+        StreamWriter {
+            buf: Vec::with_capacity(STREAM_CHUNK_LENGTH),
+            fp: fp,
+            write_str: write_str,
+            failed: false,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+
+    pub fn finish(&mut self) -> Result<(), std::io::Error> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            self.flush_buffer()
+        }
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), std::io::Error> {
+        let chunk = std::mem::take(&mut self.buf);
+        let ret = self.write_chunk(&chunk);
+        self.buf = chunk;
+        self.buf.clear();
+        ret
+    }
+
+    fn write_chunk(&mut self, mut chunk: &[u8]) -> Result<(), std::io::Error> {
+        while !chunk.is_empty() {
+            match self.write_once(chunk) {
+                Some(n) => chunk = &chunk[n..],
+                None => {
+                    self.failed = true;
+                    return Err(std::io::Error::from(std::io::ErrorKind::Other));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls `write` once with `chunk` and returns the number of bytes
+    /// written, or `None` with an exception set. `write` returning `None` is
+    /// taken to mean that the whole chunk was written.
+    fn write_once(&mut self, chunk: &[u8]) -> Option<usize> {
+        unsafe {
+            let bytes = PyBytes_FromStringAndSize(
+                chunk.as_ptr().cast::<std::os::raw::c_char>(),
+                chunk.len() as isize,
+            );
+            if bytes.is_null() {
+                return None;
+            }
+            let ret = pyobject_call_method_one_arg(self.fp, self.write_str, bytes);
+            Py_DECREF(bytes);
+            if ret.is_null() {
+                return None;
+            }
+            if ret == Py_None() {
+                Py_DECREF(ret);
+                return Some(chunk.len());
+            }
+            let n = PyLong_AsSsize_t(ret);
+            Py_DECREF(ret);
+            if n == -1 && !PyErr_Occurred().is_null() {
+                return None;
+            }
+            if n <= 0 || n as usize > chunk.len() {
+                PyErr_Format(
+                    PyExc_OSError,
+                    c"write() returned invalid length %zd (should have been between 1 and %zd)"
+                        .as_ptr(),
+                    n,
+                    chunk.len() as Py_ssize_t,
+                );
+                return None;
+            }
+            Some(n as usize)
+        }
+    }
+
+    fn insert_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) -> Result<(), std::io::Error> {
+        if unlikely!(self.failed) {
+            return Err(std::io::Error::from(std::io::ErrorKind::Other));
+        }
+        for buf in bufs {
+            if self.buf.len() + buf.len() > STREAM_CHUNK_LENGTH {
+                if !self.buf.is_empty() {
+                    self.flush_buffer()?;
+                }
+                if buf.len() > STREAM_CHUNK_LENGTH {
+                    for chunk in buf.chunks(STREAM_CHUNK_LENGTH) {
+                        self.write_chunk(chunk)?;
+                    }
+                    continue;
+                }
+            }
+            self.buf.extend_from_slice(buf);
+        }
+        Ok(())
+    }
+}
+
+impl std::io::Write for StreamWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        self.insert_slices([buf])?;
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), std::io::Error> {
+        self.insert_slices([buf])
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl WriteSlices for StreamWriter {
+    fn write_slices<const N: usize>(&mut self, bufs: [&[u8]; N]) -> Result<(), std::io::Error> {
+        self.insert_slices(bufs)
+    }
+}
@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use crate::codec::create_codec_type;
 use crate::deserialize::KeyMap;
 use crate::ext::create_ext_type;
 use crate::fragment::create_fragment_type;
+use crate::timestamp::create_timestamp_type;
+use crate::unpacker::create_unpacker_type;
+use crate::view::{create_view_subtype, create_view_type};
 use pyo3::ffi::*;
 use std::ffi::CStr;
 use std::ptr::null_mut;
@@ -10,6 +14,7 @@ use std::sync::OnceLock;
 
 pub struct NumpyTypes {
     pub array: *mut PyTypeObject,
+    pub empty: *mut PyObject,
     pub float64: *mut PyTypeObject,
     pub float32: *mut PyTypeObject,
     pub float16: *mut PyTypeObject,
@@ -25,6 +30,20 @@ pub struct NumpyTypes {
     pub datetime64: *mut PyTypeObject,
 }
 
+/// The `typing` objects used to compile the `type` argument of `unpackb`.
+pub struct TypingObjects {
+    pub any: *mut PyObject,
+    pub get_args: *mut PyObject,
+    pub get_origin: *mut PyObject,
+    pub get_type_hints: *mut PyObject,
+    pub is_typeddict: *mut PyObject,
+    pub literal: *mut PyObject,
+    pub missing: *mut PyObject,
+    pub none_type: *mut PyObject,
+    pub union: *mut PyObject,
+    pub union_type: *mut PyObject,
+}
+
 #[inline]
 unsafe fn get_type(module_dict: *mut PyObject, type_name: &CStr) -> *mut PyTypeObject {
     PyMapping_GetItemString(module_dict, type_name.as_ptr()).cast::<PyTypeObject>()
@@ -40,6 +59,22 @@ unsafe fn load_type(module_name: &CStr, type_name: &CStr) -> *mut PyTypeObject {
     ptr
 }
 
+/// Creates `MsgpackDecodeError`, a subclass of `ValueError` whose `pos` and
+/// `path` attributes default to `None`.
+#[cold]
+unsafe fn create_decode_error_type() -> *mut PyObject {
+    let dict = PyDict_New();
+    PyDict_SetItemString(dict, c"pos".as_ptr(), Py_None());
+    PyDict_SetItemString(dict, c"path".as_ptr(), Py_None());
+    let ptr = PyErr_NewException(
+        c"ormsgpack.MsgpackDecodeError".as_ptr(),
+        PyExc_ValueError,
+        dict,
+    );
+    Py_DECREF(dict);
+    ptr
+}
+
 #[cold]
 fn load_numpy_types() -> Option<NumpyTypes> {
     unsafe {
@@ -52,6 +87,7 @@ fn load_numpy_types() -> Option<NumpyTypes> {
         let numpy_dict = PyObject_GenericGetDict(numpy, null_mut());
         let types = NumpyTypes {
             array: get_type(numpy_dict, c"ndarray"),
+            empty: PyMapping_GetItemString(numpy_dict, c"empty".as_ptr()),
             float16: get_type(numpy_dict, c"half"),
             float32: get_type(numpy_dict, c"float32"),
             float64: get_type(numpy_dict, c"float64"),
@@ -72,70 +108,302 @@ fn load_numpy_types() -> Option<NumpyTypes> {
     }
 }
 
+#[cold]
+unsafe fn load_object(module_name: &CStr, name: &CStr) -> *mut PyObject {
+    let module = PyImport_ImportModule(module_name.as_ptr());
+    let module_dict = PyObject_GenericGetDict(module, null_mut());
+    let ptr = PyMapping_GetItemString(module_dict, name.as_ptr());
+    Py_DECREF(module_dict);
+    Py_DECREF(module);
+    ptr
+}
+
+/// Loads the types `type_names` of the module `module_name`.
+#[cold]
+fn load_types<const N: usize>(
+    module_name: &CStr,
+    type_names: [&CStr; N],
+) -> [*mut PyTypeObject; N] {
+    unsafe {
+        let module = PyImport_ImportModule(module_name.as_ptr());
+        let module_dict = PyObject_GenericGetDict(module, null_mut());
+        let types = type_names.map(|type_name| get_type(module_dict, type_name));
+        Py_DECREF(module_dict);
+        Py_DECREF(module);
+        types
+    }
+}
+
+/// Returns the types in `cell`, loading them first if the module named
+/// `module_str` has been imported, or an empty slice otherwise. Instances of
+/// the types cannot exist before their module is imported, so that checking
+/// for them need not import it.
+#[inline]
+fn imported_types<const N: usize>(
+    cell: &OnceLock<[*mut PyTypeObject; N]>,
+    module_str: *mut PyObject,
+    load: fn() -> [*mut PyTypeObject; N],
+) -> &[*mut PyTypeObject] {
+    if let Some(types) = cell.get() {
+        return types;
+    }
+    unsafe {
+        let module = PyImport_GetModule(module_str);
+        if module.is_null() {
+            PyErr_Clear();
+            return &[];
+        }
+        Py_DECREF(module);
+    }
+    cell.get_or_init(load)
+}
+
+#[cold]
+fn load_decimal_type() -> [*mut PyTypeObject; 1] {
+    load_types(c"decimal", [c"Decimal"])
+}
+
+#[cold]
+fn load_fraction_type() -> [*mut PyTypeObject; 1] {
+    load_types(c"fractions", [c"Fraction"])
+}
+
+#[cold]
+fn load_ipaddress_types() -> [*mut PyTypeObject; 6] {
+    load_types(
+        c"ipaddress",
+        [
+            c"IPv4Address",
+            c"IPv6Address",
+            c"IPv4Network",
+            c"IPv6Network",
+            c"IPv4Interface",
+            c"IPv6Interface",
+        ],
+    )
+}
+
+#[cold]
+fn load_path_types() -> [*mut PyTypeObject; 4] {
+    load_types(
+        c"pathlib",
+        [
+            c"PurePosixPath",
+            c"PureWindowsPath",
+            c"PosixPath",
+            c"WindowsPath",
+        ],
+    )
+}
+
+#[cold]
+fn load_url_types() -> [*mut PyTypeObject; 3] {
+    load_types(
+        c"urllib.parse",
+        [c"DefragResult", c"ParseResult", c"SplitResult"],
+    )
+}
+
+#[cold]
+fn load_typing_objects() -> TypingObjects {
+    unsafe {
+        TypingObjects {
+            any: load_object(c"typing", c"Any"),
+            get_args: load_object(c"typing", c"get_args"),
+            get_origin: load_object(c"typing", c"get_origin"),
+            get_type_hints: load_object(c"typing", c"get_type_hints"),
+            is_typeddict: load_object(c"typing", c"is_typeddict"),
+            literal: load_object(c"typing", c"Literal"),
+            missing: load_object(c"dataclasses", c"MISSING"),
+            none_type: load_object(c"types", c"NoneType"),
+            union: load_object(c"typing", c"Union"),
+            union_type: load_object(c"types", c"UnionType"),
+        }
+    }
+}
+
 #[allow(non_snake_case)]
 #[repr(C)]
 pub struct State {
     numpy_types: OnceLock<Option<NumpyTypes>>,
+    typing_objects: OnceLock<TypingObjects>,
+    decimal_type: OnceLock<[*mut PyTypeObject; 1]>,
+    fraction_type: OnceLock<[*mut PyTypeObject; 1]>,
+    /// The address, network and interface types of `ipaddress`.
+    ipaddress_types: OnceLock<[*mut PyTypeObject; 6]>,
+    /// The concrete path types of `pathlib`.
+    path_types: OnceLock<[*mut PyTypeObject; 4]>,
+    /// The result types of `urllib.parse` holding strings.
+    url_types: OnceLock<[*mut PyTypeObject; 3]>,
+    pub codec_type: *mut PyTypeObject,
     pub dataclass_field_type: *mut PyTypeObject,
     pub enum_type: *mut PyTypeObject,
     pub ext_type: *mut PyTypeObject,
     pub fragment_type: *mut PyTypeObject,
+    pub timestamp_type: *mut PyTypeObject,
+    pub unpacker_type: *mut PyTypeObject,
+    pub view_type: *mut PyTypeObject,
+    /// The subclasses of `View` for the views of maps and arrays.
+    pub map_view_type: *mut PyTypeObject,
+    pub array_view_type: *mut PyTypeObject,
     pub uuid_type: *mut PyTypeObject,
     pub array_struct_str: *mut PyObject,
+    pub associativity_str: *mut PyObject,
+    pub cast_str: *mut PyObject,
+    pub checksum_str: *mut PyObject,
     pub dataclass_fields_str: *mut PyObject,
+    pub decimal_str: *mut PyObject,
     pub default_str: *mut PyObject,
+    pub denominator_str: *mut PyObject,
     pub descr_str: *mut PyObject,
     pub dict_str: *mut PyObject,
     pub dtype_str: *mut PyObject,
     pub ext_hook_str: *mut PyObject,
+    pub ext_hooks_str: *mut PyObject,
+    pub ext_types_str: *mut PyObject,
     pub field_type_str: *mut PyObject,
     pub fields_str: *mut PyObject,
+    pub fractions_str: *mut PyObject,
+    pub geturl_str: *mut PyObject,
     pub int_str: *mut PyObject,
+    pub ipaddress_str: *mut PyObject,
+    pub map_type_str: *mut PyObject,
+    pub max_array_len_str: *mut PyObject,
+    pub max_bin_len_str: *mut PyObject,
+    pub max_depth_str: *mut PyObject,
+    pub max_elements_str: *mut PyObject,
+    pub max_ext_len_str: *mut PyObject,
+    pub max_key_len_str: *mut PyObject,
+    pub max_map_len_str: *mut PyObject,
+    pub max_str_len_str: *mut PyObject,
     pub normalize_str: *mut PyObject,
+    pub numerator_str: *mut PyObject,
+    pub object_hook_str: *mut PyObject,
+    pub object_pairs_hook_str: *mut PyObject,
+    pub offset_str: *mut PyObject,
     pub option_str: *mut PyObject,
+    pub pack_option_str: *mut PyObject,
+    pub path_str: *mut PyObject,
+    pub pathlib_str: *mut PyObject,
+    pub pos_str: *mut PyObject,
     pub pydantic_extra_str: *mut PyObject,
     pub pydantic_validator_str: *mut PyObject,
+    pub raw_str: *mut PyObject,
+    pub readinto_str: *mut PyObject,
+    pub seek_str: *mut PyObject,
+    pub seekable_str: *mut PyObject,
+    pub size_str: *mut PyObject,
     pub slots_str: *mut PyObject,
+    pub start_str: *mut PyObject,
+    pub type_str: *mut PyObject,
+    pub toreadonly_str: *mut PyObject,
+    pub uint8_format_str: *mut PyObject,
+    pub unicode_errors_str: *mut PyObject,
+    pub unpack_option_str: *mut PyObject,
+    pub urllib_parse_str: *mut PyObject,
     pub utcoffset_str: *mut PyObject,
     pub value_str: *mut PyObject,
+    pub write_str: *mut PyObject,
     pub MsgpackEncodeError: *mut PyObject,
     pub MsgpackDecodeError: *mut PyObject,
-    pub key_map: KeyMap<512>,
+    pub MsgpackDecodeLimitError: *mut PyObject,
+    pub key_map: KeyMap,
+    pub type_plans: *mut PyObject,
 }
 
 impl State {
     #[cold]
-    pub fn new() -> Self {
+    pub fn new(module: *mut PyObject) -> Self {
         unsafe {
+            let decode_error = create_decode_error_type();
+            let view_type = create_view_type(module);
             Self {
                 numpy_types: OnceLock::new(),
+                typing_objects: OnceLock::new(),
+                decimal_type: OnceLock::new(),
+                fraction_type: OnceLock::new(),
+                ipaddress_types: OnceLock::new(),
+                path_types: OnceLock::new(),
+                url_types: OnceLock::new(),
+                codec_type: create_codec_type(module),
                 dataclass_field_type: load_type(c"dataclasses", c"_FIELD"),
                 enum_type: load_type(c"enum", c"EnumMeta"),
                 ext_type: create_ext_type(),
                 fragment_type: create_fragment_type(),
+                timestamp_type: create_timestamp_type(),
+                unpacker_type: create_unpacker_type(module),
+                view_type: view_type,
+                map_view_type: create_view_subtype(module, view_type, c"ormsgpack.MapView"),
+                array_view_type: create_view_subtype(module, view_type, c"ormsgpack.ArrayView"),
                 uuid_type: load_type(c"uuid", c"UUID"),
                 array_struct_str: PyUnicode_InternFromString(c"__array_struct__".as_ptr()),
+                associativity_str: PyUnicode_InternFromString(c"associativity".as_ptr()),
+                cast_str: PyUnicode_InternFromString(c"cast".as_ptr()),
+                checksum_str: PyUnicode_InternFromString(c"checksum".as_ptr()),
                 dataclass_fields_str: PyUnicode_InternFromString(c"__dataclass_fields__".as_ptr()),
+                decimal_str: PyUnicode_InternFromString(c"decimal".as_ptr()),
                 default_str: PyUnicode_InternFromString(c"default".as_ptr()),
+                denominator_str: PyUnicode_InternFromString(c"denominator".as_ptr()),
                 descr_str: PyUnicode_InternFromString(c"descr".as_ptr()),
                 dict_str: PyUnicode_InternFromString(c"__dict__".as_ptr()),
                 dtype_str: PyUnicode_InternFromString(c"dtype".as_ptr()),
                 ext_hook_str: PyUnicode_InternFromString(c"ext_hook".as_ptr()),
+                ext_hooks_str: PyUnicode_InternFromString(c"ext_hooks".as_ptr()),
+                ext_types_str: PyUnicode_InternFromString(c"ext_types".as_ptr()),
                 field_type_str: PyUnicode_InternFromString(c"_field_type".as_ptr()),
                 fields_str: PyUnicode_InternFromString(c"__fields__".as_ptr()),
+                fractions_str: PyUnicode_InternFromString(c"fractions".as_ptr()),
+                geturl_str: PyUnicode_InternFromString(c"geturl".as_ptr()),
                 int_str: PyUnicode_InternFromString(c"int".as_ptr()),
+                ipaddress_str: PyUnicode_InternFromString(c"ipaddress".as_ptr()),
+                map_type_str: PyUnicode_InternFromString(c"map_type".as_ptr()),
+                max_array_len_str: PyUnicode_InternFromString(c"max_array_len".as_ptr()),
+                max_bin_len_str: PyUnicode_InternFromString(c"max_bin_len".as_ptr()),
+                max_depth_str: PyUnicode_InternFromString(c"max_depth".as_ptr()),
+                max_elements_str: PyUnicode_InternFromString(c"max_elements".as_ptr()),
+                max_ext_len_str: PyUnicode_InternFromString(c"max_ext_len".as_ptr()),
+                max_key_len_str: PyUnicode_InternFromString(c"max_key_len".as_ptr()),
+                max_map_len_str: PyUnicode_InternFromString(c"max_map_len".as_ptr()),
+                max_str_len_str: PyUnicode_InternFromString(c"max_str_len".as_ptr()),
                 normalize_str: PyUnicode_InternFromString(c"normalize".as_ptr()),
+                numerator_str: PyUnicode_InternFromString(c"numerator".as_ptr()),
+                object_hook_str: PyUnicode_InternFromString(c"object_hook".as_ptr()),
+                object_pairs_hook_str: PyUnicode_InternFromString(c"object_pairs_hook".as_ptr()),
+                offset_str: PyUnicode_InternFromString(c"offset".as_ptr()),
                 option_str: PyUnicode_InternFromString(c"option".as_ptr()),
+                pack_option_str: PyUnicode_InternFromString(c"pack_option".as_ptr()),
+                path_str: PyUnicode_InternFromString(c"path".as_ptr()),
+                pathlib_str: PyUnicode_InternFromString(c"pathlib".as_ptr()),
+                pos_str: PyUnicode_InternFromString(c"pos".as_ptr()),
                 pydantic_extra_str: PyUnicode_InternFromString(c"__pydantic_extra__".as_ptr()),
                 pydantic_validator_str: PyUnicode_InternFromString(
                     c"__pydantic_validator__".as_ptr(),
                 ),
+                raw_str: PyUnicode_InternFromString(c"raw".as_ptr()),
+                readinto_str: PyUnicode_InternFromString(c"readinto".as_ptr()),
+                seek_str: PyUnicode_InternFromString(c"seek".as_ptr()),
+                seekable_str: PyUnicode_InternFromString(c"seekable".as_ptr()),
+                size_str: PyUnicode_InternFromString(c"size".as_ptr()),
                 slots_str: PyUnicode_InternFromString(c"__slots__".as_ptr()),
+                start_str: PyUnicode_InternFromString(c"start".as_ptr()),
+                type_str: PyUnicode_InternFromString(c"type".as_ptr()),
+                toreadonly_str: PyUnicode_InternFromString(c"toreadonly".as_ptr()),
+                uint8_format_str: PyUnicode_InternFromString(c"B".as_ptr()),
+                unicode_errors_str: PyUnicode_InternFromString(c"unicode_errors".as_ptr()),
+                unpack_option_str: PyUnicode_InternFromString(c"unpack_option".as_ptr()),
+                urllib_parse_str: PyUnicode_InternFromString(c"urllib.parse".as_ptr()),
                 utcoffset_str: PyUnicode_InternFromString(c"utcoffset".as_ptr()),
                 value_str: PyUnicode_InternFromString(c"value".as_ptr()),
+                write_str: PyUnicode_InternFromString(c"write".as_ptr()),
                 MsgpackEncodeError: Py_NewRef(PyExc_TypeError),
-                MsgpackDecodeError: Py_NewRef(PyExc_ValueError),
+                MsgpackDecodeError: decode_error,
+                MsgpackDecodeLimitError: PyErr_NewException(
+                    c"ormsgpack.MsgpackDecodeLimitError".as_ptr(),
+                    decode_error,
+                    null_mut(),
+                ),
                 key_map: KeyMap::new(),
+                type_plans: PyDict_New(),
             }
         }
     }
@@ -143,4 +411,44 @@ impl State {
     pub fn get_numpy_types(&self) -> &Option<NumpyTypes> {
         self.numpy_types.get_or_init(load_numpy_types)
     }
+
+    pub fn get_typing_objects(&self) -> &TypingObjects {
+        self.typing_objects.get_or_init(load_typing_objects)
+    }
+
+    /// Returns `decimal.Decimal`, importing `decimal` if needed.
+    pub fn get_decimal_type(&self) -> *mut PyTypeObject {
+        self.decimal_type.get_or_init(load_decimal_type)[0]
+    }
+
+    /// Returns `decimal.Decimal`, or null if `decimal` has not been imported.
+    pub fn imported_decimal_type(&self) -> *mut PyTypeObject {
+        imported_types(&self.decimal_type, self.decimal_str, load_decimal_type)
+            .first()
+            .map_or(null_mut(), |&tp| tp)
+    }
+
+    /// Returns `fractions.Fraction`, or null if `fractions` has not been
+    /// imported.
+    pub fn imported_fraction_type(&self) -> *mut PyTypeObject {
+        imported_types(&self.fraction_type, self.fractions_str, load_fraction_type)
+            .first()
+            .map_or(null_mut(), |&tp| tp)
+    }
+
+    pub fn imported_ipaddress_types(&self) -> &[*mut PyTypeObject] {
+        imported_types(
+            &self.ipaddress_types,
+            self.ipaddress_str,
+            load_ipaddress_types,
+        )
+    }
+
+    pub fn imported_path_types(&self) -> &[*mut PyTypeObject] {
+        imported_types(&self.path_types, self.pathlib_str, load_path_types)
+    }
+
+    pub fn imported_url_types(&self) -> &[*mut PyTypeObject] {
+        imported_types(&self.url_types, self.urllib_parse_str, load_url_types)
+    }
 }
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::ffi::*;
+use pyo3::ffi::*;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr::null_mut;
+
+pub const NANOSECONDS_PER_SECOND: u32 = 1_000_000_000;
+
+#[repr(C)]
+pub struct PyTimestamp {
+    pub ob_base: PyObject,
+    pub seconds: i64,
+    pub nanoseconds: u32,
+}
+
+pub unsafe fn timestamp_from_parts(
+    timestamp_type: *mut PyTypeObject,
+    seconds: i64,
+    nanoseconds: u32,
+) -> *mut PyObject {
+    let obj = (*timestamp_type).tp_alloc.unwrap()(timestamp_type, 0);
+    if !obj.is_null() {
+        (*obj.cast::<PyTimestamp>()).seconds = seconds;
+        (*obj.cast::<PyTimestamp>()).nanoseconds = nanoseconds;
+    }
+    obj
+}
+
+#[no_mangle]
+unsafe extern "C" fn timestamp_new(
+    subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {
+    let nargs = Py_SIZE(args);
+    if !(1..=2).contains(&nargs) || (!kwds.is_null() && pydict_size(kwds) != 0) {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"Timestamp.__new__() takes 1 or 2 positional arguments".as_ptr(),
+        );
+        return null_mut();
+    }
+    let seconds = pytuple_get_item(args, 0);
+    let nanoseconds = if nargs == 2 {
+        pytuple_get_item(args, 1)
+    } else {
+        null_mut()
+    };
+    if PyLong_Check(seconds) == 0 || !(nanoseconds.is_null() || PyLong_Check(nanoseconds) != 0) {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"Timestamp.__new__() arguments must be int".as_ptr(),
+        );
+        return null_mut();
+    }
+    let seconds = PyLong_AsLongLong(seconds);
+    if seconds == -1 && !PyErr_Occurred().is_null() {
+        return null_mut();
+    }
+    let nanoseconds = if nanoseconds.is_null() {
+        0
+    } else {
+        let value = PyLong_AsLongLong(nanoseconds);
+        if !(0..i64::from(NANOSECONDS_PER_SECOND)).contains(&value) {
+            PyErr_Clear();
+            PyErr_SetString(
+                PyExc_ValueError,
+                c"Timestamp.__new__() nanoseconds must be in the range 0..999999999".as_ptr(),
+            );
+            return null_mut();
+        }
+        value as u32
+    };
+    timestamp_from_parts(subtype, seconds, nanoseconds)
+}
+
+#[no_mangle]
+unsafe extern "C" fn timestamp_dealloc(op: *mut PyObject) {
+    (*ob_type!(op)).tp_free.unwrap()(op.cast::<c_void>());
+}
+
+#[no_mangle]
+unsafe extern "C" fn timestamp_repr(op: *mut PyObject) -> *mut PyObject {
+    let timestamp = op.cast::<PyTimestamp>();
+    let repr = format!(
+        "Timestamp(seconds={}, nanoseconds={})",
+        (*timestamp).seconds,
+        (*timestamp).nanoseconds
+    );
+    PyUnicode_FromStringAndSize(repr.as_ptr().cast::<c_char>(), repr.len() as Py_ssize_t)
+}
+
+#[no_mangle]
+unsafe extern "C" fn timestamp_hash(op: *mut PyObject) -> Py_hash_t {
+    let timestamp = op.cast::<PyTimestamp>();
+    let hash = (*timestamp)
+        .seconds
+        .wrapping_mul(i64::from(NANOSECONDS_PER_SECOND))
+        .wrapping_add(i64::from((*timestamp).nanoseconds)) as Py_hash_t;
+    if hash == -1 {
+        -2
+    } else {
+        hash
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn timestamp_richcompare(
+    op: *mut PyObject,
+    other: *mut PyObject,
+    cmp: c_int,
+) -> *mut PyObject {
+    if ob_type!(other) != ob_type!(op) {
+        return Py_NewRef(Py_NotImplemented());
+    }
+    let lhs = op.cast::<PyTimestamp>();
+    let rhs = other.cast::<PyTimestamp>();
+    let ordering = ((*lhs).seconds, (*lhs).nanoseconds).cmp(&((*rhs).seconds, (*rhs).nanoseconds));
+    let res = if cmp == Py_LT {
+        ordering.is_lt()
+    } else if cmp == Py_LE {
+        ordering.is_le()
+    } else if cmp == Py_EQ {
+        ordering.is_eq()
+    } else if cmp == Py_NE {
+        ordering.is_ne()
+    } else if cmp == Py_GT {
+        ordering.is_gt()
+    } else {
+        ordering.is_ge()
+    };
+    Py_NewRef(if res { Py_True() } else { Py_False() })
+}
+
+pub unsafe fn create_timestamp_type() -> *mut PyTypeObject {
+    let members: Box<[PyMemberDef; 3]> = Box::new([
+        PyMemberDef {
+            name: c"seconds".as_ptr(),
+            type_code: Py_T_LONGLONG,
+            offset: std::mem::offset_of!(PyTimestamp, seconds) as Py_ssize_t,
+            flags: Py_READONLY,
+            doc: null_mut(),
+        },
+        PyMemberDef {
+            name: c"nanoseconds".as_ptr(),
+            type_code: Py_T_UINT,
+            offset: std::mem::offset_of!(PyTimestamp, nanoseconds) as Py_ssize_t,
+            flags: Py_READONLY,
+            doc: null_mut(),
+        },
+        PyMemberDef {
+            name: null_mut(),
+            type_code: 0,
+            offset: 0,
+            flags: 0,
+            doc: null_mut(),
+        },
+    ]);
+    let mut slots: [PyType_Slot; 7] = [
+        PyType_Slot {
+            slot: Py_tp_new,
+            pfunc: timestamp_new as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_dealloc,
+            pfunc: timestamp_dealloc as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_repr,
+            pfunc: timestamp_repr as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_hash,
+            pfunc: timestamp_hash as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_richcompare,
+            pfunc: timestamp_richcompare as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_members,
+            pfunc: Box::into_raw(members).cast::<c_void>(),
+        },
+        PyType_Slot {
+            slot: 0,
+            pfunc: null_mut(),
+        },
+    ];
+    let mut spec = PyType_Spec {
+        name: c"ormsgpack.Timestamp".as_ptr(),
+        basicsize: std::mem::size_of::<PyTimestamp>() as c_int,
+        itemsize: 0,
+        flags: Py_TPFLAGS_DEFAULT as c_uint,
+        slots: slots.as_mut_ptr(),
+    };
+    PyType_FromSpec(&mut spec).cast::<PyTypeObject>()
+}
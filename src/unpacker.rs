@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::deserialize::{deserialize_from_buffer, input_as_bytes, Config, Scanner};
+use crate::ffi::*;
+use crate::io::ReadBuffer;
+use crate::opt::*;
+use crate::state::State;
+use crate::{
+    parse_config_kwarg, parse_option_arg, raise_deserialize_exception, raise_unpackb_exception,
+};
+use pyo3::ffi::*;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_uint, c_void};
+use std::ptr::null_mut;
+
+const FEED_DOC: &CStr =
+    c"feed(data, /)\n--\n\nAppend data to the internal buffer and return the unpacker.";
+
+#[repr(C)]
+pub struct PyUnpacker {
+    pub ob_base: PyObject,
+    pub state: *mut State,
+    pub config: Config,
+    pub opts: Opt,
+    pub buffer: ReadBuffer,
+    pub scanner: Scanner,
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_new(
+    subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut State = PyType_GetModuleState(subtype).cast();
+    if Py_SIZE(args) != 0 {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"Unpacker.__new__() accepts no positional arguments".as_ptr(),
+        );
+        return null_mut();
+    }
+    let mut config = Config::default();
+    let mut opts: Opt = 0;
+    if !kwds.is_null() {
+        for (key, value) in PyDictIter::from_pyobject(kwds) {
+            if PyUnicode_Compare(key.as_ptr(), (*state).option_str) == 0 {
+                match parse_option_arg(value.as_ptr(), UNPACKB_PARTIAL_OPT_MASK) {
+                    Ok(val) => opts = val as Opt,
+                    Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+                }
+            } else {
+                match parse_config_kwarg(state, &mut config, key.as_ptr(), value.as_ptr()) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        PyErr_SetString(
+                            PyExc_TypeError,
+                            c"Unpacker.__new__() got an unexpected keyword argument".as_ptr(),
+                        );
+                        return null_mut();
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let obj = (*subtype).tp_alloc.unwrap()(subtype, 0);
+    if obj.is_null() {
+        return null_mut();
+    }
+    let unpacker = obj.cast::<PyUnpacker>();
+    config.incref();
+    (*unpacker).state = state;
+    (*unpacker).config = config;
+    (*unpacker).opts = opts;
+    std::ptr::write(&raw mut (*unpacker).buffer, ReadBuffer::new());
+    std::ptr::write(&raw mut (*unpacker).scanner, Scanner::new());
+    obj
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_traverse(
+    op: *mut PyObject,
+    visit: visitproc,
+    arg: *mut c_void,
+) -> c_int {
+    let unpacker = op.cast::<PyUnpacker>();
+    let ret = visit(ob_type!(op).cast::<PyObject>(), arg);
+    if ret != 0 {
+        return ret;
+    }
+    (*unpacker).config.traverse(visit, arg)
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_clear(op: *mut PyObject) -> c_int {
+    let unpacker = op.cast::<PyUnpacker>();
+    (*unpacker).config.clear();
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_dealloc(op: *mut PyObject) {
+    let unpacker = op.cast::<PyUnpacker>();
+    let tp = ob_type!(op);
+    PyObject_GC_UnTrack(op.cast::<c_void>());
+    (*unpacker).config.clear();
+    std::ptr::drop_in_place(&raw mut (*unpacker).buffer);
+    std::ptr::drop_in_place(&raw mut (*unpacker).scanner);
+    (*tp).tp_free.unwrap()(op.cast::<c_void>());
+    Py_DECREF(tp.cast::<PyObject>());
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_feed(op: *mut PyObject, data: *mut PyObject) -> *mut PyObject {
+    let unpacker = op.cast::<PyUnpacker>();
+    let contents = match input_as_bytes(data) {
+        Ok(val) => val,
+        Err(err) => return raise_unpackb_exception((*unpacker).state, &err.message),
+    };
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    (*unpacker).buffer.extend(contents);
+    Py_NewRef(op)
+}
+
+#[no_mangle]
+unsafe extern "C" fn unpacker_iternext(op: *mut PyObject) -> *mut PyObject {
+    let unpacker = op.cast::<PyUnpacker>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    match deserialize_from_buffer(
+        &mut (*unpacker).buffer,
+        &mut (*unpacker).scanner,
+        (*unpacker).state,
+        &(*unpacker).config,
+        (*unpacker).opts,
+    ) {
+        Ok(Some(val)) => val.as_ptr(),
+        Ok(None) => null_mut(),
+        Err(err) => raise_deserialize_exception((*unpacker).state, &err),
+    }
+}
+
+pub unsafe fn create_unpacker_type(module: *mut PyObject) -> *mut PyTypeObject {
+    let methods: Box<[PyMethodDef; 2]> = Box::new([
+        PyMethodDef {
+            ml_name: c"feed".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: unpacker_feed,
+            },
+            ml_flags: METH_O,
+            ml_doc: FEED_DOC.as_ptr(),
+        },
+        PyMethodDef::zeroed(),
+    ]);
+    let mut slots: [PyType_Slot; 8] = [
+        PyType_Slot {
+            slot: Py_tp_new,
+            pfunc: unpacker_new as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_dealloc,
+            pfunc: unpacker_dealloc as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_traverse,
+            pfunc: unpacker_traverse as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_clear,
+            pfunc: unpacker_clear as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_iter,
+            pfunc: PyObject_SelfIter as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_iternext,
+            pfunc: unpacker_iternext as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_methods,
+            pfunc: Box::into_raw(methods).cast::<c_void>(),
+        },
+        PyType_Slot {
+            slot: 0,
+            pfunc: null_mut(),
+        },
+    ];
+    let mut spec = PyType_Spec {
+        name: c"ormsgpack.Unpacker".as_ptr(),
+        basicsize: std::mem::size_of::<PyUnpacker>() as c_int,
+        itemsize: 0,
+        flags: (Py_TPFLAGS_DEFAULT | Py_TPFLAGS_HAVE_GC) as c_uint,
+        slots: slots.as_mut_ptr(),
+    };
+    PyType_FromModuleAndSpec(module, &mut spec, null_mut()).cast::<PyTypeObject>()
+}
@@ -0,0 +1,808 @@
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use crate::deserialize::{deserialize_at, input_as_bytes, Config, DeserializeError};
+use crate::ffi::*;
+use crate::msgpack::{Header, Key, Marker, Validator};
+use crate::opt::*;
+use crate::state::State;
+use crate::{
+    parse_config_kwarg, parse_option_arg, raise_deserialize_exception, raise_unpackb_exception,
+};
+use pyo3::ffi::*;
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr::{null_mut, NonNull};
+
+const GET_DOC: &CStr =
+    c"get(key, default=None, /)\n--\n\nReturn the value for key if the map has it, else default.";
+const ITEMS_DOC: &CStr = c"items()\n--\n\nReturn a list of the key-value pairs of the map.";
+const KEYS_DOC: &CStr = c"keys()\n--\n\nReturn a list of the keys of the map.";
+const VALUES_DOC: &CStr = c"values()\n--\n\nReturn a list of the values of the map.";
+const BYTES_DOC: &CStr = c"__bytes__()\n--\n\nReturn the serialized object.";
+
+enum IndexKey {
+    /// The range of the UTF-8 data of a string key.
+    Str(usize, usize),
+    Int(i64),
+    Other,
+}
+
+struct Entry {
+    /// The offset of the key, or of the value for an array element.
+    key_start: usize,
+    key: IndexKey,
+    /// The offset of the value.
+    start: usize,
+}
+
+/// The offsets of the elements of an array or the entries of a map.
+struct Index {
+    is_map: bool,
+    entries: Vec<Entry>,
+}
+
+#[repr(C)]
+pub struct PyView {
+    pub ob_base: PyObject,
+    /// The view of the whole input, which owns the fields below, or null if
+    /// this view is the one.
+    pub root: *mut PyObject,
+    pub state: *mut State,
+    pub config: Config,
+    pub opts: Opt,
+    pub buffer: Py_buffer,
+    /// The range of the object in the input. The end of the root view is
+    /// known once its index is built.
+    pub start: usize,
+    pub end: usize,
+    index: *mut Index,
+}
+
+unsafe fn view_root(view: *mut PyView) -> *mut PyView {
+    if (*view).root.is_null() {
+        view
+    } else {
+        (*view).root.cast::<PyView>()
+    }
+}
+
+unsafe fn view_contents(view: *mut PyView) -> &'static [u8] {
+    let buffer = &(*view_root(view)).buffer;
+    std::slice::from_raw_parts(buffer.buf.cast::<u8>(), buffer.len as usize)
+}
+
+/// Builds the index of a view, if not already built.
+unsafe fn view_index(view: *mut PyView) -> Result<&'static Index, DeserializeError<'static>> {
+    if !(*view).index.is_null() {
+        return Ok(&*(*view).index);
+    }
+    let contents = view_contents(view);
+    let origin = contents.as_ptr() as usize;
+    let mut validator = Validator::new(&contents[(*view).start..]);
+    let mut entries = Vec::new();
+    let header = validator.read_header();
+    let is_map = match header {
+        Ok(Header::Array(len)) => {
+            for i in 0..len {
+                let start = validator.position() - origin;
+                entries.push(Entry {
+                    key_start: start,
+                    key: IndexKey::Other,
+                    start: start,
+                });
+                validator
+                    .skip()
+                    .map_err(|err| view_error(err, start, PyLong_FromUnsignedLong(i.into())))?;
+            }
+            false
+        }
+        Ok(Header::Map(len)) => {
+            for _ in 0..len {
+                let key_start = validator.position() - origin;
+                let key = match validator
+                    .read_key()
+                    .map_err(|err| view_error(err, key_start, null_mut()))?
+                {
+                    Key::Str(data) => IndexKey::Str(data.as_ptr() as usize - origin, data.len()),
+                    Key::Int(value) => IndexKey::Int(value),
+                    Key::Other => IndexKey::Other,
+                };
+                let start = validator.position() - origin;
+                validator
+                    .skip()
+                    .map_err(|err| view_error(err, start, index_key_object(contents, &key)))?;
+                entries.push(Entry {
+                    key_start: key_start,
+                    key: key,
+                    start: start,
+                });
+            }
+            true
+        }
+        Ok(Header::Other) => unreachable!(),
+        Err(err) => return Err(view_error(err, (*view).start, null_mut())),
+    };
+    (*view).end = validator.position() - origin;
+    (*view).index = Box::into_raw(Box::new(Index {
+        is_map: is_map,
+        entries: entries,
+    }));
+    Ok(&*(*view).index)
+}
+
+/// Returns the error for the object at `position` that could not be
+/// indexed, with `key`, its map key or array index, as the path unless it
+/// is null. The reference to `key` is consumed.
+#[cold]
+unsafe fn view_error<E: std::fmt::Display>(
+    err: E,
+    position: usize,
+    key: *mut PyObject,
+) -> DeserializeError<'static> {
+    PyErr_Clear();
+    let mut res = DeserializeError::new(Cow::Owned(err.to_string()));
+    res.locate(position, NonNull::new(key).into_iter());
+    res
+}
+
+/// Returns a string or an int holding an indexed map key, or null if it is
+/// of another type.
+#[cold]
+unsafe fn index_key_object(contents: &[u8], key: &IndexKey) -> *mut PyObject {
+    match *key {
+        IndexKey::Str(start, len) => PyUnicode_FromStringAndSize(
+            contents[start..].as_ptr().cast::<c_char>(),
+            len as Py_ssize_t,
+        ),
+        IndexKey::Int(value) => PyLong_FromLongLong(value),
+        IndexKey::Other => null_mut(),
+    }
+}
+
+/// Returns the position of the entry of a map view with the key `key`.
+unsafe fn view_lookup(view: *mut PyView, index: &Index, key: *mut PyObject) -> Option<usize> {
+    let ob_type = ob_type!(key);
+    if ob_type == &raw mut PyUnicode_Type {
+        let contents = view_contents(view);
+        let key = unicode_to_str(key).ok()?.as_bytes();
+        index.entries.iter().position(|entry| match entry.key {
+            IndexKey::Str(start, len) => &contents[start..start + len] == key,
+            _ => false,
+        })
+    } else if ob_type == &raw mut PyLong_Type {
+        let key = PyLong_AsLongLong(key);
+        if key == -1 && !PyErr_Occurred().is_null() {
+            PyErr_Clear();
+            return None;
+        }
+        index.entries.iter().position(|entry| match entry.key {
+            IndexKey::Int(value) => value == key,
+            _ => false,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the type of the views of an array or map with the marker `marker`.
+unsafe fn view_subtype(state: *mut State, marker: Marker) -> *mut PyTypeObject {
+    match marker {
+        Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => (*state).map_view_type,
+        _ => (*state).array_view_type,
+    }
+}
+
+/// Returns a view of an array or map that starts at `start`, or else the
+/// deserialized object.
+unsafe fn view_get(view: *mut PyView, start: usize, end: usize) -> *mut PyObject {
+    let root = view_root(view);
+    let contents = view_contents(view);
+    match Marker::from_u8(contents[start]) {
+        Marker::FixArray(_)
+        | Marker::Array16
+        | Marker::Array32
+        | Marker::FixMap(_)
+        | Marker::Map16
+        | Marker::Map32 => {
+            let subtype = view_subtype((*view).state, Marker::from_u8(contents[start]));
+            let obj = (*subtype).tp_alloc.unwrap()(subtype, 0);
+            if obj.is_null() {
+                return null_mut();
+            }
+            let child = obj.cast::<PyView>();
+            (*child).root = Py_NewRef(root.cast::<PyObject>());
+            (*child).state = (*view).state;
+            (*child).start = start;
+            (*child).end = end;
+            obj
+        }
+        _ => view_deserialize(view, start, false),
+    }
+}
+
+unsafe fn view_deserialize(view: *mut PyView, start: usize, key: bool) -> *mut PyObject {
+    let root = view_root(view);
+    match deserialize_at(
+        (*root).buffer.obj,
+        view_contents(view),
+        start,
+        key,
+        (*root).state,
+        &(*root).config,
+        (*root).opts,
+    ) {
+        Ok(val) => val.as_ptr(),
+        Err(err) => raise_deserialize_exception((*root).state, &err),
+    }
+}
+
+unsafe fn view_get_entry(view: *mut PyView, index: &Index, i: usize) -> *mut PyObject {
+    let end = match index.entries.get(i + 1) {
+        Some(entry) => entry.key_start,
+        None => (*view).end,
+    };
+    view_get(view, index.entries[i].start, end)
+}
+
+unsafe fn view_get_key(view: *mut PyView, index: &Index, i: usize) -> *mut PyObject {
+    view_deserialize(view, index.entries[i].key_start, true)
+}
+
+/// Returns a list of the keys, values or key-value pairs of the entries of
+/// a view.
+unsafe fn view_list(view: *mut PyView, keys: bool, values: bool) -> *mut PyObject {
+    let index = match view_index(view) {
+        Ok(index) => index,
+        Err(err) => return raise_deserialize_exception((*view).state, &err),
+    };
+    if keys && !index.is_map {
+        PyErr_SetString(PyExc_TypeError, c"View of an array has no keys".as_ptr());
+        return null_mut();
+    }
+    let list = PyList_New(index.entries.len() as Py_ssize_t);
+    if list.is_null() {
+        return null_mut();
+    }
+    for i in 0..index.entries.len() {
+        let item = if keys && values {
+            let key = view_get_key(view, index, i);
+            let value = if key.is_null() {
+                null_mut()
+            } else {
+                view_get_entry(view, index, i)
+            };
+            let item = if value.is_null() {
+                null_mut()
+            } else {
+                PyTuple_New(2)
+            };
+            if item.is_null() {
+                Py_XDECREF(key);
+                Py_XDECREF(value);
+            } else {
+                pytuple_set_item(item, 0, key);
+                pytuple_set_item(item, 1, value);
+            }
+            item
+        } else if keys {
+            view_get_key(view, index, i)
+        } else {
+            view_get_entry(view, index, i)
+        };
+        if item.is_null() {
+            Py_DECREF(list);
+            return null_mut();
+        }
+        PyList_SET_ITEM(list, i as Py_ssize_t, item);
+    }
+    list
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_new(
+    subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {
+    let state: *mut State = PyType_GetModuleState(subtype).cast();
+    if Py_SIZE(args) != 1 {
+        let msg = if Py_SIZE(args) > 1 {
+            "View() accepts only 1 positional argument"
+        } else {
+            "View() missing 1 required positional argument: 'obj'"
+        };
+        return raise_unpackb_exception(state, msg);
+    }
+    let mut config = Config::default();
+    let mut opts: Opt = 0;
+    if !kwds.is_null() {
+        for (key, value) in PyDictIter::from_pyobject(kwds) {
+            if PyUnicode_Compare(key.as_ptr(), (*state).option_str) == 0 {
+                match parse_option_arg(value.as_ptr(), UNPACKB_OPT_MASK) {
+                    Ok(val) => opts = val as Opt,
+                    Err(()) => return raise_unpackb_exception(state, "Invalid opts"),
+                }
+            } else {
+                match parse_config_kwarg(state, &mut config, key.as_ptr(), value.as_ptr()) {
+                    Ok(true) if config.type_.is_none() => {}
+                    Ok(_) => {
+                        return raise_unpackb_exception(
+                            state,
+                            "View() got an unexpected keyword argument",
+                        )
+                    }
+                    Err(msg) => return raise_unpackb_exception(state, &msg),
+                }
+            }
+        }
+    }
+
+    let data = pytuple_get_item(args, 0);
+    let contents = match input_as_bytes(data) {
+        Ok(val) => val,
+        Err(err) => return raise_unpackb_exception(state, &err.message),
+    };
+    let marker = match contents.first().map(|byte| Marker::from_u8(*byte)) {
+        Some(
+            marker @ (Marker::FixArray(_)
+            | Marker::Array16
+            | Marker::Array32
+            | Marker::FixMap(_)
+            | Marker::Map16
+            | Marker::Map32),
+        ) => marker,
+        _ => return raise_unpackb_exception(state, "View() input must be an array or a map"),
+    };
+    // Instances of the View type itself are created with the subtype that
+    // matches the object, but those of subclasses defined in Python are not.
+    let subtype = if subtype == (*state).view_type
+        || subtype == (*state).map_view_type
+        || subtype == (*state).array_view_type
+    {
+        view_subtype(state, marker)
+    } else {
+        subtype
+    };
+
+    let mut buffer = Py_buffer::new();
+    if PyObject_GetBuffer(data, &mut buffer, PyBUF_SIMPLE) != 0 {
+        return null_mut();
+    }
+
+    let obj = (*subtype).tp_alloc.unwrap()(subtype, 0);
+    if obj.is_null() {
+        PyBuffer_Release(&mut buffer);
+        return null_mut();
+    }
+    let view = obj.cast::<PyView>();
+    config.incref();
+    (*view).state = state;
+    (*view).config = config;
+    (*view).opts = opts;
+    (*view).start = 0;
+    (*view).end = buffer.len as usize;
+    std::ptr::write(&raw mut (*view).buffer, buffer);
+    if opts & FORBID_TRAILING_DATA != 0 {
+        // Building the index finds the end of the object.
+        let err = match view_index(view) {
+            Ok(_) if (*view).end == contents.len() => None,
+            Ok(_) => {
+                let mut err = DeserializeError::new(Cow::Borrowed("trailing data"));
+                err.locate((*view).end, std::iter::empty());
+                Some(err)
+            }
+            Err(err) => Some(err),
+        };
+        if let Some(err) = err {
+            Py_DECREF(obj);
+            return raise_deserialize_exception(state, &err);
+        }
+    }
+    obj
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_traverse(op: *mut PyObject, visit: visitproc, arg: *mut c_void) -> c_int {
+    let view = op.cast::<PyView>();
+    let ret = visit(ob_type!(op).cast::<PyObject>(), arg);
+    if ret != 0 {
+        return ret;
+    }
+    if !(*view).root.is_null() {
+        return visit((*view).root, arg);
+    }
+    if !(*view).buffer.obj.is_null() {
+        let ret = visit((*view).buffer.obj, arg);
+        if ret != 0 {
+            return ret;
+        }
+    }
+    (*view).config.traverse(visit, arg)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_clear(op: *mut PyObject) -> c_int {
+    // The root view and the buffer are kept, as they are needed to access
+    // the view, and can't be part of a reference cycle without the config.
+    let view = op.cast::<PyView>();
+    if (*view).root.is_null() {
+        (*view).config.clear();
+    }
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_dealloc(op: *mut PyObject) {
+    let view = op.cast::<PyView>();
+    let tp = ob_type!(op);
+    PyObject_GC_UnTrack(op.cast::<c_void>());
+    if (*view).root.is_null() {
+        (*view).config.clear();
+        PyBuffer_Release(&raw mut (*view).buffer);
+    } else {
+        Py_DECREF((*view).root);
+    }
+    if !(*view).index.is_null() {
+        drop(Box::from_raw((*view).index));
+    }
+    (*tp).tp_free.unwrap()(op.cast::<c_void>());
+    Py_DECREF(tp.cast::<PyObject>());
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_repr(op: *mut PyObject) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    let mut validator = Validator::new(&view_contents(view)[(*view).start..]);
+    let repr = match validator.read_header() {
+        Ok(Header::Array(len)) => format!("<ormsgpack.View of an array of length {len}>"),
+        Ok(Header::Map(len)) => format!("<ormsgpack.View of a map of length {len}>"),
+        _ => String::from("<ormsgpack.View>"),
+    };
+    PyUnicode_FromStringAndSize(repr.as_ptr().cast::<c_char>(), repr.len() as Py_ssize_t)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_length(op: *mut PyObject) -> Py_ssize_t {
+    let view = op.cast::<PyView>();
+    let mut validator = Validator::new(&view_contents(view)[(*view).start..]);
+    match validator.read_header() {
+        Ok(Header::Array(len) | Header::Map(len)) => len as Py_ssize_t,
+        _ => {
+            raise_unpackb_exception((*view).state, "unexpected end of file");
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_subscript(op: *mut PyObject, key: *mut PyObject) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    let index = match view_index(view) {
+        Ok(index) => index,
+        Err(err) => return raise_deserialize_exception((*view).state, &err),
+    };
+    if index.is_map {
+        match view_lookup(view, index, key) {
+            Some(i) => view_get_entry(view, index, i),
+            None => {
+                PyErr_SetObject(PyExc_KeyError, key);
+                null_mut()
+            }
+        }
+    } else {
+        if PyIndex_Check(key) == 0 {
+            PyErr_SetString(PyExc_TypeError, c"View indices must be integers".as_ptr());
+            return null_mut();
+        }
+        let mut i = PyNumber_AsSsize_t(key, PyExc_IndexError);
+        if i == -1 && !PyErr_Occurred().is_null() {
+            return null_mut();
+        }
+        if i < 0 {
+            i += index.entries.len() as Py_ssize_t;
+        }
+        view_item_at(view, index, i)
+    }
+}
+
+unsafe fn view_item_at(view: *mut PyView, index: &Index, i: Py_ssize_t) -> *mut PyObject {
+    if i < 0 || i as usize >= index.entries.len() {
+        PyErr_SetString(PyExc_IndexError, c"View index out of range".as_ptr());
+        return null_mut();
+    }
+    view_get_entry(view, index, i as usize)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_item(op: *mut PyObject, i: Py_ssize_t) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    let index = match view_index(view) {
+        Ok(index) => index,
+        Err(err) => return raise_deserialize_exception((*view).state, &err),
+    };
+    if index.is_map {
+        PyErr_SetString(PyExc_TypeError, c"View of a map is not a sequence".as_ptr());
+        return null_mut();
+    }
+    view_item_at(view, index, i)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_contains(op: *mut PyObject, value: *mut PyObject) -> c_int {
+    let view = op.cast::<PyView>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    let index = match view_index(view) {
+        Ok(index) => index,
+        Err(err) => {
+            raise_deserialize_exception((*view).state, &err);
+            return -1;
+        }
+    };
+    if index.is_map {
+        return view_lookup(view, index, value).is_some() as c_int;
+    }
+    for i in 0..index.entries.len() {
+        let item = view_get_entry(view, index, i);
+        if item.is_null() {
+            return -1;
+        }
+        let res = PyObject_RichCompareBool(item, value, Py_EQ);
+        Py_DECREF(item);
+        if res != 0 {
+            return res;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_iter(op: *mut PyObject) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    let is_map = match view_index(view) {
+        Ok(index) => index.is_map,
+        Err(err) => return raise_deserialize_exception((*view).state, &err),
+    };
+    let list = view_list(view, is_map, !is_map);
+    if list.is_null() {
+        return null_mut();
+    }
+    let iter = PyObject_GetIter(list);
+    Py_DECREF(list);
+    iter
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_keys(op: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    view_list(op.cast::<PyView>(), true, false)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_values(op: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    view_list(op.cast::<PyView>(), false, true)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_items(op: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    view_list(op.cast::<PyView>(), true, true)
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_get_method(
+    op: *mut PyObject,
+    args: *mut *mut PyObject,
+    nargs: Py_ssize_t,
+) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    if !(1..=2).contains(&nargs) {
+        PyErr_SetString(
+            PyExc_TypeError,
+            c"get() takes 1 or 2 positional arguments".as_ptr(),
+        );
+        return null_mut();
+    }
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    let index = match view_index(view) {
+        Ok(index) => index,
+        Err(err) => return raise_deserialize_exception((*view).state, &err),
+    };
+    if !index.is_map {
+        PyErr_SetString(PyExc_TypeError, c"View of an array has no keys".as_ptr());
+        return null_mut();
+    }
+    match view_lookup(view, index, *args) {
+        Some(i) => view_get_entry(view, index, i),
+        None if nargs == 2 => Py_NewRef(*args.offset(1)),
+        None => Py_NewRef(Py_None()),
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn view_bytes(op: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    let view = op.cast::<PyView>();
+    let mut critical_section = CriticalSection::new();
+    critical_section.begin(op);
+    if let Err(err) = view_index(view) {
+        return raise_deserialize_exception((*view).state, &err);
+    }
+    let data = &view_contents(view)[(*view).start..(*view).end];
+    PyBytes_FromStringAndSize(data.as_ptr().cast::<c_char>(), data.len() as Py_ssize_t)
+}
+
+pub unsafe fn create_view_type(module: *mut PyObject) -> *mut PyTypeObject {
+    let methods: Box<[PyMethodDef; 6]> = Box::new([
+        PyMethodDef {
+            ml_name: c"get".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunctionFast: view_get_method,
+            },
+            ml_flags: METH_FASTCALL,
+            ml_doc: GET_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"items".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: view_items,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: ITEMS_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"keys".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: view_keys,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: KEYS_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"values".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: view_values,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: VALUES_DOC.as_ptr(),
+        },
+        PyMethodDef {
+            ml_name: c"__bytes__".as_ptr(),
+            ml_meth: PyMethodDefPointer {
+                PyCFunction: view_bytes,
+            },
+            ml_flags: METH_NOARGS,
+            ml_doc: BYTES_DOC.as_ptr(),
+        },
+        PyMethodDef::zeroed(),
+    ]);
+    let mut slots: [PyType_Slot; 13] = [
+        PyType_Slot {
+            slot: Py_tp_new,
+            pfunc: view_new as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_dealloc,
+            pfunc: view_dealloc as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_traverse,
+            pfunc: view_traverse as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_clear,
+            pfunc: view_clear as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_repr,
+            pfunc: view_repr as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_iter,
+            pfunc: view_iter as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_mp_length,
+            pfunc: view_length as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_mp_subscript,
+            pfunc: view_subscript as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_sq_length,
+            pfunc: view_length as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_sq_item,
+            pfunc: view_item as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_sq_contains,
+            pfunc: view_contains as *mut c_void,
+        },
+        PyType_Slot {
+            slot: Py_tp_methods,
+            pfunc: Box::into_raw(methods).cast::<c_void>(),
+        },
+        PyType_Slot {
+            slot: 0,
+            pfunc: null_mut(),
+        },
+    ];
+    let mut spec = PyType_Spec {
+        name: c"ormsgpack.View".as_ptr(),
+        basicsize: std::mem::size_of::<PyView>() as c_int,
+        itemsize: 0,
+        flags: (Py_TPFLAGS_DEFAULT | Py_TPFLAGS_HAVE_GC | Py_TPFLAGS_BASETYPE) as c_uint,
+        slots: slots.as_mut_ptr(),
+    };
+    PyType_FromModuleAndSpec(module, &mut spec, null_mut()).cast::<PyTypeObject>()
+}
+
+/// Creates the subclass of `View` named `name`, for the views of either
+/// arrays or maps, so that they can be registered with the matching abstract
+/// base class of `collections.abc`.
+pub unsafe fn create_view_subtype(
+    module: *mut PyObject,
+    base: *mut PyTypeObject,
+    name: &'static CStr,
+) -> *mut PyTypeObject {
+    let mut slots: [PyType_Slot; 1] = [PyType_Slot {
+        slot: 0,
+        pfunc: null_mut(),
+    }];
+    let mut spec = PyType_Spec {
+        name: name.as_ptr(),
+        basicsize: std::mem::size_of::<PyView>() as c_int,
+        itemsize: 0,
+        flags: Py_TPFLAGS_DEFAULT as c_uint,
+        slots: slots.as_mut_ptr(),
+    };
+    PyType_FromModuleAndSpec(module, &mut spec, base.cast::<PyObject>()).cast::<PyTypeObject>()
+}
+
+/// Registers the subclasses of `View` as virtual subclasses of
+/// `collections.abc.Mapping` and `collections.abc.Sequence`.
+pub unsafe fn register_view_subtypes(state: *mut State) -> c_int {
+    let module = PyImport_ImportModule(c"collections.abc".as_ptr());
+    if module.is_null() {
+        return -1;
+    }
+    let registrations = [
+        (c"Mapping", (*state).map_view_type),
+        (c"Sequence", (*state).array_view_type),
+    ];
+    for (name, tp) in registrations {
+        let abc = PyObject_GetAttrString(module, name.as_ptr());
+        if abc.is_null() {
+            Py_DECREF(module);
+            return -1;
+        }
+        let ret = PyObject_CallMethod(
+            abc,
+            c"register".as_ptr(),
+            c"O".as_ptr(),
+            tp.cast::<PyObject>(),
+        );
+        Py_DECREF(abc);
+        if ret.is_null() {
+            Py_DECREF(module);
+            return -1;
+        }
+        Py_DECREF(ret);
+    }
+    Py_DECREF(module);
+    0
+}
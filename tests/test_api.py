@@ -242,11 +242,11 @@ def test_packb_invalid_option(option: int) -> None:
 @pytest.mark.parametrize(
     "option",
     (
-        ormsgpack.OPT_NAIVE_UTC,
         ormsgpack.OPT_OMIT_MICROSECONDS,
         ormsgpack.OPT_PASSTHROUGH_BIG_INT,
         ormsgpack.OPT_PASSTHROUGH_DATACLASS,
         ormsgpack.OPT_PASSTHROUGH_DATETIME,
+        ormsgpack.OPT_PASSTHROUGH_NUMBER,
         ormsgpack.OPT_PASSTHROUGH_SUBCLASS,
         ormsgpack.OPT_PASSTHROUGH_TUPLE,
         ormsgpack.OPT_SERIALIZE_NUMPY,
@@ -282,7 +282,8 @@ def test_packb_signature() -> None:
     packb() valid __text_signature__
     """
     assert (
-        str(inspect.signature(ormsgpack.packb)) == "(obj, /, default=None, option=None)"
+        str(inspect.signature(ormsgpack.packb))
+        == "(obj, /, default=None, option=None, *, ext_types=None)"
     )
     inspect.signature(ormsgpack.packb).bind("str")
     inspect.signature(ormsgpack.packb).bind("str", default=None, option=1)
@@ -298,7 +299,11 @@ def test_unpackb_signature() -> None:
     """
     assert (
         str(inspect.signature(ormsgpack.unpackb))
-        == "(obj, /, *, ext_hook=None, option=None)"
+        == "(obj, /, *, ext_hook=None, ext_hooks=None, object_hook=None, "
+        "object_pairs_hook=None, map_type=None, max_array_len=None, "
+        "max_bin_len=None, max_depth=None, max_elements=None, max_ext_len=None, "
+        "max_map_len=None, max_str_len=None, raw=False, type=None, "
+        "unicode_errors='strict', option=None)"
     )
     inspect.signature(ormsgpack.unpackb).bind("[]")
 
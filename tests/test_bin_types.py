@@ -0,0 +1,97 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+
+import pytest
+
+import ormsgpack
+
+OBJ = {"a": b"xyz", "b": [b"", b"\x00" * 1024]}
+
+
+def test_bin_as_memoryview() -> None:
+    data = ormsgpack.packb(OBJ)
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    assert isinstance(obj["a"], memoryview)
+    assert obj["a"].readonly
+    assert obj["a"].obj is data
+    assert obj == OBJ
+
+
+def test_bin_as_memoryview_bytearray() -> None:
+    data = bytearray(ormsgpack.packb(OBJ))
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    assert obj["a"].readonly
+    assert obj == OBJ
+    data[-1] = 1
+    assert obj["b"][1][-1] == 1
+    with pytest.raises(BufferError):
+        data.clear()
+    del obj
+    data.clear()
+
+
+@pytest.mark.parametrize("fmt", ("B", "i"))
+def test_bin_as_memoryview_memoryview(fmt: str) -> None:
+    data = ormsgpack.packb(OBJ)
+    view = memoryview(bytearray(data + bytes(-len(data) % 4))).cast(fmt)
+    obj = ormsgpack.unpackb(view, option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    assert obj["a"].readonly
+    assert obj["a"].format == "B"
+    assert obj == OBJ
+
+
+def test_bin_as_memoryview_offset() -> None:
+    data = b"\xc0" + ormsgpack.packb(OBJ)
+    obj, end = ormsgpack.unpackb_with_offset(
+        data, 1, option=ormsgpack.OPT_BIN_AS_MEMORYVIEW
+    )
+    assert end == len(data)
+    assert obj == OBJ
+    frame = ormsgpack.pack_frame(OBJ)
+    obj = ormsgpack.unpack_frame(frame, option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    assert obj["a"].obj is frame
+    assert obj == OBJ
+
+
+def test_bin_as_memoryview_codec() -> None:
+    data = ormsgpack.packb(OBJ)
+    codec = ormsgpack.Codec(unpack_option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    obj = codec.unpackb(data)
+    assert obj["a"].obj is data
+    assert obj == OBJ
+
+
+def test_bin_as_memoryview_copy() -> None:
+    data = ormsgpack.packb(OBJ)
+    unpacker = ormsgpack.Unpacker(option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    (obj,) = unpacker.feed(data)
+    assert isinstance(obj["a"], memoryview)
+    assert obj["a"].readonly
+    assert obj == OBJ
+    obj = ormsgpack.unpack(io.BytesIO(data), option=ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+    assert isinstance(obj["a"], memoryview)
+    assert obj == OBJ
+
+
+def test_bin_as_bytearray() -> None:
+    data = ormsgpack.packb(OBJ)
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_BIN_AS_BYTEARRAY)
+    assert isinstance(obj["a"], bytearray)
+    assert obj == OBJ
+    obj = ormsgpack.unpackb(
+        data,
+        option=ormsgpack.OPT_BIN_AS_BYTEARRAY | ormsgpack.OPT_BIN_AS_MEMORYVIEW,
+    )
+    assert isinstance(obj["a"], memoryview)
+
+
+@pytest.mark.parametrize(
+    "option", (ormsgpack.OPT_BIN_AS_BYTEARRAY, ormsgpack.OPT_BIN_AS_MEMORYVIEW)
+)
+def test_bin_map_key(option: int) -> None:
+    data = ormsgpack.packb({b"a": b"b"}, option=ormsgpack.OPT_NON_STR_KEYS)
+    obj = ormsgpack.unpackb(data, option=option | ormsgpack.OPT_NON_STR_KEYS)
+    (key,) = obj
+    assert type(key) is bytes
+    assert obj == {b"a": b"b"}
@@ -0,0 +1,114 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import typing
+
+import pytest
+
+import ormsgpack
+
+
+@pytest.fixture(autouse=True)
+def cache() -> typing.Iterator[None]:
+    ormsgpack.cache_configure(size=512, max_key_len=64, associativity=1)
+    yield
+    ormsgpack.cache_configure(size=512, max_key_len=64, associativity=1)
+
+
+def test_cache_info() -> None:
+    assert ormsgpack.cache_info() == {
+        "hits": 0,
+        "misses": 0,
+        "evictions": 0,
+        "entries": 0,
+        "size": 512,
+        "max_key_len": 64,
+        "associativity": 1,
+    }
+    data = ormsgpack.packb([{"a": 1, "b": 2}, {"a": 3, "b": 4}])
+    ormsgpack.unpackb(data)
+    info = ormsgpack.cache_info()
+    assert info["hits"] == 2
+    assert info["misses"] == 2
+    assert info["entries"] == 2
+
+
+def test_cache_shared_key() -> None:
+    data = ormsgpack.packb([{"key": 1}, {"key": 2}])
+    first, second = ormsgpack.unpackb(data)
+    assert next(iter(first)) is next(iter(second))
+
+
+def test_cache_clear() -> None:
+    ormsgpack.unpackb(ormsgpack.packb({"a": 1}))
+    ormsgpack.cache_clear()
+    info = ormsgpack.cache_info()
+    assert (info["hits"], info["misses"], info["entries"]) == (0, 0, 0)
+    assert info["size"] == 512
+
+
+def test_cache_evictions() -> None:
+    ormsgpack.cache_configure(size=4, associativity=4)
+    obj = {key: 1 for key in "abcde"}
+    assert ormsgpack.unpackb(ormsgpack.packb(obj)) == obj
+    info = ormsgpack.cache_info()
+    assert (info["misses"], info["evictions"], info["entries"]) == (5, 1, 4)
+    ormsgpack.unpackb(ormsgpack.packb({"e": 1, "b": 2, "a": 3}))
+    info = ormsgpack.cache_info()
+    assert (info["hits"], info["misses"], info["evictions"]) == (2, 6, 2)
+
+
+def test_cache_associativity() -> None:
+    ormsgpack.cache_configure(size=64, associativity=64)
+    obj = {f"key{i}": i for i in range(64)}
+    data = ormsgpack.packb(obj)
+    assert ormsgpack.unpackb(data) == obj
+    assert ormsgpack.unpackb(data) == obj
+    info = ormsgpack.cache_info()
+    assert (info["hits"], info["misses"], info["evictions"]) == (64, 64, 0)
+
+
+def test_cache_max_key_len() -> None:
+    ormsgpack.cache_configure(max_key_len=3)
+    obj = {"abc": 1, "abcd": 2}
+    assert ormsgpack.unpackb(ormsgpack.packb(obj)) == obj
+    info = ormsgpack.cache_info()
+    assert (info["misses"], info["entries"], info["max_key_len"]) == (1, 1, 3)
+
+
+def test_cache_disabled() -> None:
+    ormsgpack.cache_configure(size=0)
+    obj = {"a": 1, "b": 2}
+    assert ormsgpack.unpackb(ormsgpack.packb(obj)) == obj
+    info = ormsgpack.cache_info()
+    assert (info["hits"], info["misses"], info["entries"]) == (0, 0, 0)
+
+
+def test_cache_invalid_utf8() -> None:
+    data = b"\x81\xa1\xff\x01"
+    assert ormsgpack.unpackb(data, unicode_errors="replace") == {"�": 1}
+    assert ormsgpack.cache_info()["entries"] == 0
+
+
+@pytest.mark.parametrize(
+    ("kwargs", "msg"),
+    (
+        ({"size": -1}, "^Invalid size$"),
+        ({"size": 2**20 + 1}, "^Invalid size$"),
+        ({"size": 2**64}, "^Invalid size$"),
+        ({"size": True}, "^Invalid size$"),
+        ({"max_key_len": "1"}, "^Invalid max_key_len$"),
+        ({"associativity": 0}, "^Invalid associativity$"),
+        ({"size": 6, "associativity": 4}, "multiple of associativity"),
+    ),
+)
+def test_cache_configure_invalid(kwargs: dict[str, typing.Any], msg: str) -> None:
+    with pytest.raises(ValueError, match=msg):
+        ormsgpack.cache_configure(**kwargs)
+    assert ormsgpack.cache_info()["size"] == 512
+
+
+def test_cache_configure_unexpected_argument() -> None:
+    with pytest.raises(TypeError):
+        ormsgpack.cache_configure(512)  # type: ignore[misc]
+    with pytest.raises(TypeError):
+        ormsgpack.cache_configure(maxsize=512)  # type: ignore[call-arg]
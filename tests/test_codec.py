@@ -0,0 +1,82 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import datetime
+import gc
+import weakref
+
+import pytest
+
+import ormsgpack
+
+
+def test_codec() -> None:
+    codec = ormsgpack.Codec()
+    for obj in (1, "a", [1, 2], {"a": [True, None]}, b"\x00" * 4096):
+        packed = codec.packb(obj)
+        assert packed == ormsgpack.packb(obj)
+        assert codec.unpackb(packed) == obj
+
+
+def test_codec_large_object() -> None:
+    codec = ormsgpack.Codec()
+    obj = ["a" * 1024] * 2048
+    assert codec.packb(obj) == ormsgpack.packb(obj)
+    assert codec.packb(1) == ormsgpack.packb(1)
+
+
+def test_codec_default() -> None:
+    codec = ormsgpack.Codec(default=str)
+    assert codec.unpackb(codec.packb(datetime.timezone.utc)) == "UTC"
+
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.Codec().packb(datetime.timezone.utc)
+
+
+def test_codec_default_reentrant() -> None:
+    def default(obj: object) -> object:
+        if isinstance(obj, range):
+            return codec.packb(list(obj))
+        raise TypeError
+
+    codec = ormsgpack.Codec(default=default)
+    packed = codec.packb({"a": range(1, 3), "b": [3]})
+    assert codec.unpackb(packed) == {"a": ormsgpack.packb([1, 2]), "b": [3]}
+
+
+def test_codec_ext_hook() -> None:
+    codec = ormsgpack.Codec(ext_hook=lambda tag, data: (tag, data))
+    assert codec.unpackb(codec.packb(ormsgpack.Ext(1, b"a"))) == (1, b"a")
+
+
+def test_codec_option() -> None:
+    codec = ormsgpack.Codec(
+        pack_option=ormsgpack.OPT_NON_STR_KEYS,
+        unpack_option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert codec.unpackb(codec.packb({1: 2})) == {1: 2}
+
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.Codec(pack_option=1 << 30)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.Codec(unpack_option=ormsgpack.OPT_SORT_KEYS)
+
+
+def test_codec_invalid_argument() -> None:
+    with pytest.raises(TypeError):
+        ormsgpack.Codec(1)  # type: ignore[call-arg]
+    with pytest.raises(TypeError):
+        ormsgpack.Codec(option=None)  # type: ignore[call-arg]
+
+
+def test_codec_reference_cycle() -> None:
+    class Default:
+        def __call__(self, obj: object) -> object:
+            return str(obj)
+
+    default = Default()
+    codec = ormsgpack.Codec(default=default)
+    default.codec = codec  # type: ignore[attr-defined]
+    ref = weakref.ref(default)
+    del default, codec
+    gc.collect()
+    assert ref() is None
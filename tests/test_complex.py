@@ -0,0 +1,46 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import math
+
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize("value", (1 + 2j, -1.5j, complex(math.inf, -0.0)))
+def test_complex(value: complex) -> None:
+    packed = ormsgpack.packb(value)
+    assert packed == ormsgpack.packb([value.real, value.imag])
+    assert ormsgpack.unpackb(packed, type=complex) == value
+
+
+def test_complex_as_ext() -> None:
+    packed = ormsgpack.packb(1.5 - 2j, option=ormsgpack.OPT_COMPLEX_AS_EXT)
+    assert packed == ormsgpack.packb(
+        ormsgpack.Ext(125, b"\x3f\xf8\x00\x00\x00\x00\x00\x00\xc0\x00" + b"\x00" * 6)
+    )
+    assert ormsgpack.unpackb(packed, option=ormsgpack.OPT_COMPLEX_AS_EXT) == 1.5 - 2j
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(packed[:-1], option=ormsgpack.OPT_COMPLEX_AS_EXT)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(b"\xd4\x7d\x00", option=ormsgpack.OPT_COMPLEX_AS_EXT)
+
+
+def test_complex_type() -> None:
+    assert ormsgpack.unpackb(ormsgpack.packb((1, 2)), type=complex) == 1 + 2j
+    for value in ([1], [1, "a"], [1, True], 1.5):
+        with pytest.raises(ormsgpack.MsgpackDecodeError, match="^expected"):
+            ormsgpack.unpackb(ormsgpack.packb(value), type=complex)
+
+
+def test_complex_passthrough() -> None:
+    obj = 1 + 2j
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER)
+
+
+def test_complex_passthrough_default() -> None:
+    obj = 1 + 2j
+    assert ormsgpack.packb(
+        obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER, default=str
+    ) == ormsgpack.packb("(1+2j)")
@@ -0,0 +1,120 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import collections
+import types
+
+import pytest
+
+import ormsgpack
+
+
+class FrozenDict(dict):  # type: ignore[type-arg]
+    def __hash__(self) -> int:  # type: ignore[override]
+        return hash(frozenset(self.items()))
+
+
+def test_array_as_tuple() -> None:
+    data = ormsgpack.packb([1, [2, [3]], {"a": [4]}, []])
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_ARRAY_AS_TUPLE)
+    assert obj == (1, (2, (3,)), {"a": (4,)}, ())
+
+
+def test_array_as_tuple_hashable() -> None:
+    data = ormsgpack.packb([1, [2, "a"], None])
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_ARRAY_AS_TUPLE)
+    assert {obj: True}[(1, (2, "a"), None)]
+
+
+def test_array_as_tuple_invalid() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(b"\x92\x01", option=ormsgpack.OPT_ARRAY_AS_TUPLE)
+
+
+def test_array_as_tuple_packb() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb([], option=ormsgpack.OPT_ARRAY_AS_TUPLE)
+
+
+@pytest.mark.parametrize(
+    "map_type",
+    (collections.OrderedDict, types.MappingProxyType, FrozenDict),
+)
+def test_map_type(map_type: type) -> None:
+    data = ormsgpack.packb({"b": {"c": 1}, "a": 2})
+    obj = ormsgpack.unpackb(data, map_type=map_type)
+    assert type(obj) is map_type
+    assert type(obj["b"]) is map_type
+    assert list(obj) == ["b", "a"]
+    assert obj == {"b": {"c": 1}, "a": 2}
+
+
+def test_map_type_dict() -> None:
+    assert type(ormsgpack.unpackb(b"\x80", map_type=dict)) is dict
+    assert type(ormsgpack.unpackb(b"\x80", map_type=None)) is dict
+
+
+def test_map_type_hashable() -> None:
+    data = ormsgpack.packb([{"a": [1, 2]}, {"b": {"c": None}}])
+    obj = ormsgpack.unpackb(
+        data, map_type=FrozenDict, option=ormsgpack.OPT_ARRAY_AS_TUPLE
+    )
+    assert hash(obj) == hash(
+        (FrozenDict({"a": (1, 2)}), FrozenDict({"b": FrozenDict({"c": None})}))
+    )
+
+
+def test_map_type_non_str_keys() -> None:
+    data = ormsgpack.packb({1: 2}, option=ormsgpack.OPT_NON_STR_KEYS)
+    obj = ormsgpack.unpackb(
+        data,
+        map_type=types.MappingProxyType,
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert isinstance(obj, types.MappingProxyType)
+    assert obj[1] == 2
+
+
+def test_map_type_object_hook() -> None:
+    obj = ormsgpack.unpackb(
+        b"\x81\xa1a\x01",
+        map_type=collections.OrderedDict,
+        object_hook=lambda obj: type(obj).__name__,
+    )
+    assert obj == "OrderedDict"
+
+
+def test_map_type_object_pairs_hook() -> None:
+    obj = ormsgpack.unpackb(
+        b"\x81\xa1a\x01",
+        map_type=collections.OrderedDict,
+        object_pairs_hook=list,
+    )
+    assert obj == [("a", 1)]
+
+
+def test_map_type_invalid() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid map_type"):
+        ormsgpack.unpackb(b"\x80", map_type=1)  # type: ignore[arg-type]
+
+
+def test_map_type_exception() -> None:
+    def map_type(obj: dict) -> object:  # type: ignore[type-arg]
+        raise ValueError
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="map_type failed"):
+        ormsgpack.unpackb(b"\x80", map_type=map_type)
+
+
+def test_container_types_codec_unpacker() -> None:
+    data = ormsgpack.packb({"a": [1]})
+    codec = ormsgpack.Codec(
+        map_type=types.MappingProxyType,
+        unpack_option=ormsgpack.OPT_ARRAY_AS_TUPLE,
+    )
+    obj = codec.unpackb(data)
+    assert isinstance(obj, types.MappingProxyType)
+    assert obj["a"] == (1,)
+    unpacker = ormsgpack.Unpacker(
+        map_type=collections.OrderedDict, option=ormsgpack.OPT_ARRAY_AS_TUPLE
+    )
+    assert list(unpacker.feed(data)) == [collections.OrderedDict(a=(1,))]
@@ -0,0 +1,111 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import decimal
+
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize(
+    "value",
+    ("1.10", "-0.00123", "1E+5", "0", "-0", "NaN", "-Infinity", "1E-999999"),
+)
+def test_decimal(value: str) -> None:
+    packed = ormsgpack.packb(decimal.Decimal(value))
+    assert packed == ormsgpack.packb(value)
+    result = ormsgpack.unpackb(packed, type=decimal.Decimal)
+    assert str(result) == value
+
+
+@pytest.mark.parametrize(
+    ("value", "data"),
+    (
+        ("1.10", b"\xff\xff\xff\xfe\x00\x6e"),
+        ("-0.00123", b"\xff\xff\xff\xfb\x01\x7b"),
+        ("1E+5", b"\x00\x00\x00\x05\x00\x01"),
+        ("0", b"\x00\x00\x00\x00\x00"),
+        ("-0", b"\x00\x00\x00\x00\x01"),
+        ("-0E+5", b"\x00\x00\x00\x05\x01"),
+        (
+            "12345678901234567890.5",
+            b"\xff\xff\xff\xff\x00" + (123456789012345678905).to_bytes(9, "big"),
+        ),
+        ("1E-999999", b"\xff\xf0\xbd\xc1\x00\x01"),
+    ),
+)
+def test_decimal_as_ext(value: str, data: bytes) -> None:
+    packed = ormsgpack.packb(
+        decimal.Decimal(value), option=ormsgpack.OPT_DECIMAL_AS_EXT
+    )
+    assert packed == ormsgpack.packb(ormsgpack.Ext(126, data))
+    result = ormsgpack.unpackb(packed, option=ormsgpack.OPT_DECIMAL_AS_EXT)
+    assert type(result) is decimal.Decimal
+    assert result.as_tuple() == decimal.Decimal(value).as_tuple()
+
+
+@pytest.mark.parametrize("value", ("NaN", "sNaN", "Infinity", "-Infinity"))
+def test_decimal_as_ext_not_finite(value: str) -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="^Decimal is not finite$"):
+        ormsgpack.packb(decimal.Decimal(value), option=ormsgpack.OPT_DECIMAL_AS_EXT)
+
+
+def test_decimal_as_ext_exponent() -> None:
+    context = decimal.Context(Emax=decimal.MAX_EMAX)
+    value = context.create_decimal(f"1E+{2**31}")
+    with pytest.raises(
+        ormsgpack.MsgpackEncodeError, match="^Decimal exponent exceeds 32-bit range$"
+    ):
+        ormsgpack.packb(value, option=ormsgpack.OPT_DECIMAL_AS_EXT)
+
+
+@pytest.mark.parametrize("sign", (0, 1))
+@pytest.mark.parametrize("digits", (19, 20, 5000, 10000))
+def test_decimal_as_ext_large(sign: int, digits: int) -> None:
+    value = decimal.Decimal((sign, (9,) * digits, -5))
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_DECIMAL_AS_EXT)
+    result = ormsgpack.unpackb(packed, option=ormsgpack.OPT_DECIMAL_AS_EXT)
+    assert result.as_tuple() == value.as_tuple()
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        b"\xd4\x7e\x00",
+        b"\xc7\x04\x7e\x00\x00\x00\x00",
+        b"\xc7\x05\x7e\x00\x00\x00\x00\x02",
+    ),
+)
+def test_decimal_as_ext_invalid(data: bytes) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_DECIMAL_AS_EXT)
+
+
+def test_decimal_subclass() -> None:
+    class MyDecimal(decimal.Decimal):
+        pass
+
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(MyDecimal("1.5"))
+
+
+def test_decimal_type() -> None:
+    assert ormsgpack.unpackb(ormsgpack.packb(3), type=decimal.Decimal) == 3
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(ormsgpack.packb("a"), type=decimal.Decimal)
+    assert isinstance(exc_info.value.__cause__, decimal.InvalidOperation)
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="^expected"):
+        ormsgpack.unpackb(ormsgpack.packb(1.5), type=decimal.Decimal)
+
+
+def test_decimal_passthrough() -> None:
+    obj = decimal.Decimal("1.5")
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER)
+
+
+def test_decimal_passthrough_default() -> None:
+    obj = decimal.Decimal("1.5")
+    assert ormsgpack.packb(
+        obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER, default=str
+    ) == ormsgpack.packb("1.5")
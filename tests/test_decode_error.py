@@ -0,0 +1,105 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+import typing
+
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize(
+    ("data", "pos", "path"),
+    (
+        (b"\xc1", 0, []),
+        (b"\x93\x01\x02\xc1", 3, [2]),
+        (b"\x81\xa5items\x92\x01\xc1", 9, ["items", 1]),
+        (b"\x81\xa1a\x81\xa1b\x91\xa1\xff", 7, ["a", "b", 0]),
+        (b"\x92\x01", 2, [1]),
+        (b"\x91\x81\xa1a", 4, [0, "a"]),
+        (b"\x82\xa1a\x01\x01\x02", 4, []),
+        (b"\x81\xa1\xff\x01", 1, []),
+    ),
+)
+def test_decode_error_position(data: bytes, pos: int, path: list[typing.Any]) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(data)
+    assert exc_info.value.pos == pos
+    assert exc_info.value.path == path
+
+
+def test_decode_error_message() -> None:
+    data = ormsgpack.packb({"items": [{"tags": [1, 2]}] * 18})[:-3]
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=(
+            r"^unexpected end of file at position 169, "
+            r"path \['items', 17, 'tags'\]$"
+        ),
+    ):
+        ormsgpack.unpackb(data)
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError, match="^trailing data at position 1$"
+    ):
+        ormsgpack.unpackb(b"\x01\x02", option=ormsgpack.OPT_FORBID_TRAILING_DATA)
+
+
+def test_decode_error_non_str_keys() -> None:
+    data = b"\x81\x01\x81\x92\x01\x02\xc1"
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert exc_info.value.pos == 6
+    assert exc_info.value.path == [1, (1, 2)]
+
+
+def test_decode_error_hook() -> None:
+    def object_hook(obj: dict[str, typing.Any]) -> typing.Any:
+        raise ValueError
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(b"\x91\x81\xa1a\x01", object_hook=object_hook)
+    assert exc_info.value.pos == 1
+    assert exc_info.value.path == [0]
+    assert isinstance(exc_info.value.__cause__, ValueError)
+
+
+def test_decode_error_limit() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError) as exc_info:
+        ormsgpack.unpackb(b"\x91\x92\x01\x02", max_array_len=1)
+    assert exc_info.value.pos == 1
+    assert exc_info.value.path == [0]
+
+
+def test_decode_error_offset() -> None:
+    data = b"\x01\x92\x01\xc1"
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb_with_offset(data, 1)
+    assert exc_info.value.pos == 3
+    assert exc_info.value.path == [1]
+
+
+def test_decode_error_stream() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpack(io.BytesIO(b"\x92\x01\xc1"))
+    assert exc_info.value.pos == 2
+    assert exc_info.value.path == [1]
+
+
+def test_decode_error_unknown_position() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb("a")  # type: ignore[arg-type]
+    assert exc_info.value.pos is None
+    assert exc_info.value.path is None
+
+
+def test_decode_error_conversion_position() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(b"\x92\x01\xa1a", type=list[int])
+    assert exc_info.value.pos == 2
+    assert exc_info.value.path == [1]
+
+
+def test_decode_error_type() -> None:
+    assert issubclass(ormsgpack.MsgpackDecodeError, ValueError)
+    assert ormsgpack.MsgpackDecodeError is not ValueError
+    assert issubclass(ormsgpack.MsgpackDecodeLimitError, ormsgpack.MsgpackDecodeError)
@@ -1,13 +1,22 @@
 # SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-from decimal import Decimal
-
 import msgpack
 import pytest
 
 import ormsgpack
 
 
+class Countdown:
+    def __init__(self, value: int) -> None:
+        self.value = value
+
+    def __sub__(self, other: int) -> "Countdown":
+        return Countdown(self.value - other)
+
+    def __gt__(self, other: int) -> bool:
+        return self.value > other
+
+
 def test_default_not_callable() -> None:
     """
     packb() default not callable
@@ -21,7 +30,7 @@ def test_default_function() -> None:
     """
     packb() default function
     """
-    ref = {1, 2}
+    ref = range(1, 3)
 
     def default(obj: object) -> object:
         return str(obj)
@@ -59,7 +68,7 @@ def test_default_lambda() -> None:
     """
     packb() default lambda
     """
-    ref = {1, 2}
+    ref = range(1, 3)
     assert ormsgpack.packb(ref, default=lambda x: str(x)) == msgpack.packb(str(ref))
 
 
@@ -67,7 +76,7 @@ def test_default_callable() -> None:
     """
     packb() default callable
     """
-    ref = {1, 2}
+    ref = range(1, 3)
 
     class Default:
         def __call__(self, obj: object) -> object:
@@ -82,7 +91,7 @@ def test_default_recursion() -> None:
     """
 
     assert ormsgpack.packb(
-        Decimal(254),
+        Countdown(254),
         default=lambda x: x - 1 if x > 0 else 0,
     ) == msgpack.packb(0)
 
@@ -92,7 +101,7 @@ def test_default_recursion_reset() -> None:
     packb() default recursion limit reset
     """
     assert ormsgpack.packb(
-        [Decimal(254), {"a": "b"}, Decimal(254), Decimal(254)],
+        [Countdown(254), {"a": "b"}, Countdown(254), Countdown(254)],
         default=lambda x: x - 1 if x > 0 else 0,
     ) == msgpack.packb([0, {"a": "b"}, 0, 0])
 
@@ -0,0 +1,118 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+
+import pytest
+
+import ormsgpack
+
+
+def test_duplicate_keys() -> None:
+    data = b"\x83\xa1a\x01\xa1b\x02\xa1a\x03"
+    assert ormsgpack.unpackb(data) == {"a": 3, "b": 2}
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="duplicate map key 'a' at position 7",
+    ):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+
+
+def test_duplicate_keys_nested() -> None:
+    data = b"\x92\x80\x81\xa1x\x82\xa1y\x01\xa1y\x02"
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="duplicate map key 'y' at position 9",
+    ):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+
+
+def test_duplicate_keys_long() -> None:
+    key = ormsgpack.packb("k" * 100)
+    data = b"\x82" + key + b"\x01" + key + b"\x02"
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError, match=f"at position {len(key) + 2}"
+    ):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+
+
+def test_duplicate_keys_non_str_keys() -> None:
+    data = b"\x82\x01\xc0\x01\xc2"
+    option = ormsgpack.OPT_FORBID_DUPLICATE_KEYS | ormsgpack.OPT_NON_STR_KEYS
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="duplicate map key 1 at position 3",
+    ):
+        ormsgpack.unpackb(data, option=option)
+    data = b"\x82\x92\x01\xa1a\xc0\x92\x01\xa1a\xc2"
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"duplicate map key \(1, 'a'\) at position 6",
+    ):
+        ormsgpack.unpackb(data, option=option)
+
+
+def test_duplicate_keys_equal() -> None:
+    data = b"\x82\x01\xc0\xcb\x3f\xf0\x00\x00\x00\x00\x00\x00\xc2"
+    option = ormsgpack.OPT_FORBID_DUPLICATE_KEYS | ormsgpack.OPT_NON_STR_KEYS
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="key 1.0 at position 3"):
+        ormsgpack.unpackb(data, option=option)
+
+
+def test_duplicate_keys_none() -> None:
+    data = ormsgpack.packb({"a": 1, "b": {"a": 2}})
+    obj = ormsgpack.unpackb(data, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+    assert obj == {"a": 1, "b": {"a": 2}}
+
+
+def test_duplicate_keys_offset() -> None:
+    data = b"\xc0\xc0\x82\xa1a\x01\xa1a\x02"
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="at position 6"):
+        ormsgpack.unpackb_with_offset(
+            data, 2, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS
+        )
+
+
+def test_duplicate_keys_unpacker() -> None:
+    data = b"\xc0\x82\xa1a\x01\xa1a\x02"
+    unpacker = ormsgpack.Unpacker(option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+    unpacker.feed(data)
+    assert next(unpacker) is None
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="at position 4"):
+        next(unpacker)
+    fp = io.BytesIO(data)
+    assert ormsgpack.unpack(fp) is None
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="at position 4"):
+        ormsgpack.unpack(fp, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS)
+
+
+def test_duplicate_keys_object_pairs_hook() -> None:
+    data = b"\x82\xa1a\x01\xa1a\x02"
+    assert ormsgpack.unpackb(data, object_pairs_hook=list) == [("a", 1), ("a", 2)]
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="duplicate map key 'a' at position 4",
+    ):
+        ormsgpack.unpackb(
+            data, object_pairs_hook=list, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS
+        )
+    data = b"\x82\xa1a\x01\xa1b\x02"
+    obj = ormsgpack.unpackb(
+        data, object_pairs_hook=list, option=ormsgpack.OPT_FORBID_DUPLICATE_KEYS
+    )
+    assert obj == [("a", 1), ("b", 2)]
+
+
+def test_duplicate_keys_object_pairs_hook_unhashable() -> None:
+    data = b"\x81\x91\x01\x02"
+    option = ormsgpack.OPT_NON_STR_KEYS
+    obj = ormsgpack.unpackb(data, object_pairs_hook=list, option=option)
+    assert obj == [([1], 2)]
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="unhashable map key of type list at position 1",
+    ):
+        ormsgpack.unpackb(
+            data,
+            object_pairs_hook=list,
+            option=option | ormsgpack.OPT_FORBID_DUPLICATE_KEYS,
+        )
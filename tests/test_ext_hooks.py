@@ -0,0 +1,202 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import dataclasses
+import datetime
+import decimal
+import fractions
+import io
+import typing
+
+import msgpack
+import pytest
+
+import ormsgpack
+
+EXT_TYPES = {
+    decimal.Decimal: (1, lambda obj: str(obj).encode()),
+    fractions.Fraction: (2, lambda obj: f"{obj.numerator}/{obj.denominator}".encode()),
+}
+EXT_HOOKS = {
+    1: lambda data: decimal.Decimal(data.decode()),
+    2: lambda data: fractions.Fraction(data.decode()),
+}
+
+
+def test_ext_types() -> None:
+    obj = [decimal.Decimal("1.5"), fractions.Fraction(1, 3)]
+    packed = ormsgpack.packb(obj, ext_types=EXT_TYPES)
+    assert packed == msgpack.packb(
+        [msgpack.ExtType(1, b"1.5"), msgpack.ExtType(2, b"1/3")]
+    )
+    assert ormsgpack.unpackb(packed, ext_hooks=EXT_HOOKS) == obj
+
+
+def test_ext_types_precedence() -> None:
+    @dataclasses.dataclass
+    class Point:
+        x: int
+
+    ext_types = {
+        Point: (3, lambda obj: obj.x.to_bytes(1, "big")),
+        datetime.date: (4, lambda obj: obj.isoformat().encode()),
+    }
+    obj = [Point(1), datetime.date(2000, 1, 1)]
+    packed = ormsgpack.packb(obj, default=str, ext_types=ext_types)
+    assert packed == msgpack.packb(
+        [msgpack.ExtType(3, b"\x01"), msgpack.ExtType(4, b"2000-01-01")]
+    )
+    packed = ormsgpack.packb(
+        datetime.datetime(2000, 1, 1), default=str, ext_types=ext_types
+    )
+    assert ormsgpack.unpackb(packed) == "2000-01-01T00:00:00"
+
+
+def test_ext_types_map_key() -> None:
+    obj = {decimal.Decimal("1.5"): 1, (fractions.Fraction(1, 3), 2): 2}
+    packed = ormsgpack.packb(
+        obj, ext_types=EXT_TYPES, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+    unpacked = ormsgpack.unpackb(
+        packed, ext_hooks=EXT_HOOKS, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+    assert unpacked == obj
+
+
+def test_ext_hooks_fallback() -> None:
+    packed = ormsgpack.packb(
+        [decimal.Decimal("1.5"), fractions.Fraction(1, 3)], ext_types=EXT_TYPES
+    )
+    unpacked = ormsgpack.unpackb(
+        packed, ext_hooks={1: EXT_HOOKS[1]}, ext_hook=lambda tag, data: (tag, data)
+    )
+    assert unpacked == [decimal.Decimal("1.5"), (2, b"1/3")]
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="^ext_hook missing"):
+        ormsgpack.unpackb(packed, ext_hooks={1: EXT_HOOKS[1]})
+
+
+def test_ext_hooks_timestamp() -> None:
+    value = datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc)
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT)
+    ext_hooks = {-1: lambda data: data}
+    assert ormsgpack.unpackb(packed, ext_hooks=ext_hooks) == packed[2:]
+    unpacked = ormsgpack.unpackb(
+        packed,
+        ext_hooks=ext_hooks,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT,
+    )
+    assert unpacked == packed[2:]
+    unpacked = ormsgpack.unpackb(
+        packed,
+        ext_hook=lambda tag, data: data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT,
+    )
+    assert unpacked == value
+
+
+@pytest.mark.parametrize(
+    ("obj", "option"),
+    (
+        (2**70, ormsgpack.OPT_BIG_INT_AS_EXT),
+        (decimal.Decimal("1.5"), ormsgpack.OPT_DECIMAL_AS_EXT),
+        (1 + 2j, ormsgpack.OPT_COMPLEX_AS_EXT),
+    ),
+)
+def test_ext_hooks_builtin_types(obj: typing.Any, option: int) -> None:
+    packed = ormsgpack.packb(obj, option=option)
+    tag, data = ormsgpack.unpackb(packed, ext_hook=lambda tag, data: (tag, data))
+    assert ormsgpack.unpackb(packed, option=option) == obj
+    unpacked = ormsgpack.unpackb(packed, ext_hooks={tag: bytes}, option=option)
+    assert unpacked == data
+
+
+def test_ext_hooks_map_key_missing() -> None:
+    packed = ormsgpack.packb(
+        {decimal.Decimal("1.5"): 1},
+        ext_types=EXT_TYPES,
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(packed, option=ormsgpack.OPT_NON_STR_KEYS)
+
+
+def test_ext_hooks_map_key_unhashable() -> None:
+    packed = b"\x82\x01\x02\xd4\x01x\x03"
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match="^unhashable map key of type list at position 3$",
+    ) as exc_info:
+        ormsgpack.unpackb(
+            packed,
+            ext_hooks={1: lambda data: [data]},
+            option=ormsgpack.OPT_NON_STR_KEYS,
+        )
+    assert exc_info.value.pos == 3
+    assert isinstance(exc_info.value.__cause__, TypeError)
+
+
+def test_ext_hooks_raises() -> None:
+    def hook(data: bytes) -> typing.Any:
+        raise ValueError
+
+    packed = ormsgpack.packb(decimal.Decimal("1.5"), ext_types=EXT_TYPES)
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(packed, ext_hooks={1: hook})
+    assert isinstance(exc_info.value.__cause__, ValueError)
+
+
+def test_ext_types_encoder_error() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="must return bytes"):
+        ormsgpack.packb(
+            decimal.Decimal("1.5"), ext_types={decimal.Decimal: (1, str)}
+        )
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="encoder failed"):
+        ormsgpack.packb(
+            decimal.Decimal("1.5"),
+            ext_types={decimal.Decimal: (1, lambda obj: 1 / 0)},
+        )
+
+
+def test_ext_types_entry_points() -> None:
+    value = decimal.Decimal("1.5")
+    packed = ormsgpack.packb(value, ext_types=EXT_TYPES)
+    fp = io.BytesIO()
+    ormsgpack.pack(value, fp, ext_types=EXT_TYPES)
+    assert fp.getvalue() == packed
+    buffer = bytearray()
+    ormsgpack.packb_into(value, buffer, ext_types=EXT_TYPES)
+    assert buffer == packed
+    frame = ormsgpack.pack_frame(value, ext_types=EXT_TYPES)
+    assert ormsgpack.unpack_frame(frame, ext_hooks=EXT_HOOKS) == value
+    codec = ormsgpack.Codec(ext_types=EXT_TYPES, ext_hooks=EXT_HOOKS)
+    assert codec.packb(value) == packed
+    assert codec.unpackb(packed) == value
+    unpacker = ormsgpack.Unpacker(ext_hooks=EXT_HOOKS)
+    unpacker.feed(packed)
+    assert list(unpacker) == [value]
+
+
+@pytest.mark.parametrize(
+    "ext_hooks",
+    ([1], {1: 1}, {128: str}, {-129: str}, {2**64: str}, {"1": str}, {True: str}),
+)
+def test_ext_hooks_invalid(ext_hooks: typing.Any) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="^Invalid ext_hooks$"):
+        ormsgpack.unpackb(b"\xc0", ext_hooks=ext_hooks)
+
+
+@pytest.mark.parametrize(
+    "ext_types",
+    (
+        [1],
+        {1: (1, str)},
+        {decimal.Decimal: [1, str]},
+        {decimal.Decimal: (1,)},
+        {decimal.Decimal: (1, 1)},
+        {decimal.Decimal: (-1, str)},
+        {decimal.Decimal: (128, str)},
+        {decimal.Decimal: (True, str)},
+    ),
+)
+def test_ext_types_invalid(ext_types: typing.Any) -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="^Invalid ext_types$"):
+        ormsgpack.packb(None, ext_types=ext_types)
@@ -0,0 +1,55 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import fractions
+
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize(
+    "value",
+    (
+        fractions.Fraction(1, 3),
+        fractions.Fraction(-5, 2),
+        fractions.Fraction(0),
+    ),
+)
+def test_fraction(value: fractions.Fraction) -> None:
+    packed = ormsgpack.packb(value)
+    assert packed == ormsgpack.packb([value.numerator, value.denominator])
+    assert ormsgpack.unpackb(packed, type=fractions.Fraction) == value
+
+
+def test_fraction_big_int() -> None:
+    value = fractions.Fraction(2**64, 3)
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(value)
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_BIG_INT_AS_EXT)
+    result = ormsgpack.unpackb(
+        packed, option=ormsgpack.OPT_BIG_INT_AS_EXT, type=fractions.Fraction
+    )
+    assert result == value
+
+
+def test_fraction_type() -> None:
+    assert ormsgpack.unpackb(ormsgpack.packb(3), type=fractions.Fraction) == 3
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(ormsgpack.packb([1, 0]), type=fractions.Fraction)
+    assert isinstance(exc_info.value.__cause__, ZeroDivisionError)
+    for value in ([1, 2, 3], [1, 0.5], [True, 1], "1/3"):
+        with pytest.raises(ormsgpack.MsgpackDecodeError, match="^expected"):
+            ormsgpack.unpackb(ormsgpack.packb(value), type=fractions.Fraction)
+
+
+def test_fraction_passthrough() -> None:
+    obj = fractions.Fraction(1, 3)
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER)
+
+
+def test_fraction_passthrough_default() -> None:
+    obj = fractions.Fraction(1, 3)
+    assert ormsgpack.packb(
+        obj, option=ormsgpack.OPT_PASSTHROUGH_NUMBER, default=str
+    ) == ormsgpack.packb("1/3")
@@ -0,0 +1,171 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import struct
+import zlib
+
+import pytest
+
+import ormsgpack
+
+
+def test_pack_frame() -> None:
+    obj = {"a": [1, 2, 3]}
+    payload = ormsgpack.packb(obj)
+    frame = ormsgpack.pack_frame(obj)
+    assert frame == (
+        struct.pack(">I", len(payload))
+        + payload
+        + struct.pack(">I", zlib.crc32(payload))
+    )
+
+
+def test_pack_frame_no_checksum() -> None:
+    obj = {"a": [1, 2, 3]}
+    payload = ormsgpack.packb(obj)
+    frame = ormsgpack.pack_frame(obj, checksum=False)
+    assert frame == struct.pack(">I", len(payload)) + payload
+
+
+@pytest.mark.parametrize("size", (0, 1019, 1020, 1021, 100000))
+def test_pack_frame_size(size: int) -> None:
+    obj = b"x" * size
+    payload = ormsgpack.packb(obj)
+    frame = ormsgpack.pack_frame(obj)
+    assert frame[4:-4] == payload
+    assert ormsgpack.unpack_frame(frame) == obj
+
+
+@pytest.mark.parametrize("size", range(20))
+def test_pack_frame_checksum(size: int) -> None:
+    obj = bytes(range(size))
+    payload = ormsgpack.packb(obj)
+    frame = ormsgpack.pack_frame(obj)
+    assert frame[-4:] == struct.pack(">I", zlib.crc32(payload))
+
+
+def test_pack_frame_default_option() -> None:
+    frame = ormsgpack.pack_frame(
+        {1: object()},
+        default=lambda obj: "default",
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert ormsgpack.unpack_frame(frame, option=ormsgpack.OPT_NON_STR_KEYS) == {
+        1: "default"
+    }
+
+
+def test_pack_frame_error() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.pack_frame(object())
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.pack_frame(1, checksum=1)  # type: ignore[arg-type]
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.pack_frame(1, 2)  # type: ignore[misc]
+
+
+@pytest.mark.parametrize("checksum", (True, False))
+@pytest.mark.parametrize("buffer_type", (bytes, bytearray, memoryview))
+def test_unpack_frame(checksum: bool, buffer_type: type) -> None:
+    obj = ["a", 1, None, {"b": 2.5}]
+    frame = buffer_type(ormsgpack.pack_frame(obj, checksum=checksum))
+    assert ormsgpack.unpack_frame(frame, checksum=checksum) == obj
+
+
+def test_unpack_frame_ext_hook() -> None:
+    frame = ormsgpack.pack_frame(ormsgpack.Ext(1, b"data"))
+    assert ormsgpack.unpack_frame(frame, ext_hook=lambda tag, data: (tag, data)) == (
+        1,
+        b"data",
+    )
+
+
+def test_unpack_frame_invalid_checksum() -> None:
+    frame = bytearray(ormsgpack.pack_frame([1, 2, 3]))
+    frame[-1] ^= 1
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid frame checksum"):
+        ormsgpack.unpack_frame(frame)
+
+
+@pytest.mark.parametrize(
+    "frame",
+    (
+        b"",
+        b"\x00\x00",
+        b"\x00\x00\x00\x02\x01",
+        b"\x00\x00\x00\x01\x01\x02",
+    ),
+)
+def test_unpack_frame_invalid_length(frame: bytes) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid frame length"):
+        ormsgpack.unpack_frame(frame, checksum=False)
+
+
+def test_unpack_frame_missing_checksum() -> None:
+    frame = ormsgpack.pack_frame(1, checksum=False)
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid frame length"):
+        ormsgpack.unpack_frame(frame)
+
+
+def test_unpack_frame_payload_trailing_data() -> None:
+    frame = b"\x00\x00\x00\x02\x01\x02"
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="trailing data"):
+        ormsgpack.unpack_frame(frame, checksum=False)
+
+
+def test_unpack_frame_payload_truncated() -> None:
+    frame = b"\x00\x00\x00\x01\x92"
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpack_frame(frame, checksum=False)
+
+
+@pytest.mark.parametrize("checksum", (True, False))
+def test_unpack_frame_with_offset(checksum: bool) -> None:
+    objs = [{"a": 1}, [1, 2, 3], "b" * 100]
+    data = b"".join(ormsgpack.pack_frame(obj, checksum=checksum) for obj in objs)
+    start = 0
+    result = []
+    while start < len(data):
+        obj, start = ormsgpack.unpack_frame_with_offset(  # type: ignore[misc]
+            data, start, checksum=checksum
+        )
+        result.append(obj)
+    assert result == objs
+    assert start == len(data)
+
+
+def test_unpack_frame_with_offset_incomplete() -> None:
+    frame = ormsgpack.pack_frame([1, 2, 3])
+    for size in range(len(frame)):
+        assert ormsgpack.unpack_frame_with_offset(frame[:size]) is None
+    data = b"xx" + frame + frame[:-1]
+    assert ormsgpack.unpack_frame_with_offset(data, 2) == ([1, 2, 3], 2 + len(frame))
+    assert ormsgpack.unpack_frame_with_offset(data, 2 + len(frame)) is None
+
+
+def test_unpack_frame_with_offset_stream() -> None:
+    objs = [{"i": i, "data": b"x" * i * 100} for i in range(10)]
+    data = b"".join(ormsgpack.pack_frame(obj) for obj in objs)
+    buffer = bytearray()
+    result = []
+    for i in range(0, len(data), 7):
+        buffer += data[i : i + 7]
+        start = 0
+        while frame := ormsgpack.unpack_frame_with_offset(buffer, start):
+            obj, start = frame
+            result.append(obj)
+        del buffer[:start]
+    assert result == objs
+    assert buffer == b""
+
+
+@pytest.mark.parametrize("start", (-1, 4))
+def test_unpack_frame_with_offset_invalid_start(start: int) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid start"):
+        ormsgpack.unpack_frame_with_offset(b"\x00\x00\x00", start)
+
+
+def test_unpack_frame_with_offset_invalid_checksum() -> None:
+    frame = bytearray(ormsgpack.pack_frame([1, 2, 3]))
+    frame[-1] ^= 1
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid frame checksum"):
+        ormsgpack.unpack_frame_with_offset(frame)
@@ -70,3 +70,65 @@ def test_int_128_passthrough(value: int) -> None:
     )
     assert list(result.keys()) == ["int"]
     assert int.from_bytes(result["int"], "little", signed=True) == value
+
+
+@pytest.mark.parametrize(
+    ("value", "data"),
+    (
+        (-9223372036854775809, b"\xff\x7f\xff\xff\xff\xff\xff\xff\xff"),
+        (18446744073709551616, b"\x01\x00\x00\x00\x00\x00\x00\x00\x00"),
+        (-18446744073709551616, b"\xff\x00\x00\x00\x00\x00\x00\x00\x00"),
+        (2**127 - 1, b"\x7f" + b"\xff" * 15),
+        (-(2**127), b"\x80" + b"\x00" * 15),
+        (2**127, b"\x00\x80" + b"\x00" * 15),
+        (-(2**127) - 1, b"\xff\x7f" + b"\xff" * 15),
+    ),
+)
+def test_int_big_int_as_ext(value: int, data: bytes) -> None:
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_BIG_INT_AS_EXT)
+    assert packed == msgpack.packb(msgpack.ExtType(127, data))
+    assert ormsgpack.unpackb(packed, option=ormsgpack.OPT_BIG_INT_AS_EXT) == value
+
+
+def test_int_big_int_as_ext_64() -> None:
+    obj = [-9223372036854775808, 18446744073709551615]
+    packed = ormsgpack.packb(obj, option=ormsgpack.OPT_BIG_INT_AS_EXT)
+    assert packed == msgpack.packb(obj)
+
+
+def test_int_big_int_as_ext_subclass_and_key() -> None:
+    class BigInt(int):
+        pass
+
+    obj = {2**64: BigInt(2**64), BigInt(-(2**64)): 1}
+    option = ormsgpack.OPT_BIG_INT_AS_EXT | ormsgpack.OPT_NON_STR_KEYS
+    assert ormsgpack.unpackb(ormsgpack.packb(obj, option=option), option=option) == {
+        2**64: 2**64,
+        -(2**64): 1,
+    }
+
+
+def test_int_big_int_as_ext_passthrough() -> None:
+    packed = ormsgpack.packb(
+        2**64,
+        default=str,
+        option=ormsgpack.OPT_BIG_INT_AS_EXT | ormsgpack.OPT_PASSTHROUGH_BIG_INT,
+    )
+    assert ormsgpack.unpackb(packed) == "18446744073709551616"
+
+
+def test_int_big_int_as_ext_unpackb() -> None:
+    packed = msgpack.packb(
+        [msgpack.ExtType(127, b""), msgpack.ExtType(127, b"\x00\x00\x01")]
+    )
+    assert ormsgpack.unpackb(packed, option=ormsgpack.OPT_BIG_INT_AS_EXT) == [0, 1]
+    ext_hooks = {127: lambda data: data}
+    assert ormsgpack.unpackb(
+        packed, ext_hooks=ext_hooks, option=ormsgpack.OPT_BIG_INT_AS_EXT
+    ) == [b"", b"\x00\x00\x01"]
+    assert ormsgpack.unpackb(
+        packed,
+        ext_hook=lambda tag, data: data,
+        option=ormsgpack.OPT_BIG_INT_AS_EXT,
+    ) == [0, 1]
+    assert ormsgpack.unpackb(packed, ext_hooks=ext_hooks) == [b"", b"\x00\x00\x01"]
@@ -0,0 +1,103 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+
+import pytest
+
+import ormsgpack
+
+
+def ext_hook(tag: int, data: bytes) -> tuple[int, bytes]:
+    return (tag, data)
+
+
+@pytest.mark.parametrize(
+    ("limit", "value", "obj"),
+    (
+        ("max_str_len", 4, "abcd"),
+        ("max_str_len", 4, {"abcd": 1}),
+        ("max_str_len", 65, {"a" * 65: 1}),
+        ("max_bin_len", 4, b"abcd"),
+        ("max_ext_len", 4, ormsgpack.Ext(1, b"abcd")),
+        ("max_array_len", 4, [1, 2, 3, 4]),
+        ("max_map_len", 4, {"a": 1, "b": 2, "c": 3, "d": 4}),
+        ("max_depth", 4, [[[[]]]]),
+        ("max_depth", 4, {"a": {"b": {"c": {}}}}),
+        ("max_elements", 5, [[1], {"a": 2}, 3]),
+    ),
+)
+def test_limit(limit: str, value: int, obj: object) -> None:
+    data = ormsgpack.packb(obj)
+    assert ormsgpack.unpackb(
+        data, ext_hook=ext_hook, **{limit: value}
+    ) == ormsgpack.unpackb(data, ext_hook=ext_hook)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError, match=f"{limit} exceeded"):
+        ormsgpack.unpackb(data, ext_hook=ext_hook, **{limit: value - 1})
+
+
+def test_limit_exception() -> None:
+    assert issubclass(ormsgpack.MsgpackDecodeLimitError, ormsgpack.MsgpackDecodeError)
+    assert ormsgpack.MsgpackDecodeLimitError.__module__ == "ormsgpack"
+
+
+def test_limit_zero() -> None:
+    assert ormsgpack.unpackb(b"\x01", max_depth=0) == 1
+    assert ormsgpack.unpackb(b"\x90", max_array_len=0, max_elements=0) == []
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb(b"\x90", max_depth=0)
+
+
+def test_limit_before_allocation() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb(b"\xdd\xff\xff\xff\xff", max_array_len=1024)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb(b"\xdf\xff\xff\xff\xff", max_elements=1024)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb(b"\xdb\xff\xff\xff\xff", max_str_len=1024)
+
+
+def test_limit_non_str_keys() -> None:
+    data = ormsgpack.packb(
+        {(1, 2, 3): True, b"abc": False}, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+    ormsgpack.unpackb(data, max_array_len=3, option=ormsgpack.OPT_NON_STR_KEYS)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError, match="max_array_len"):
+        ormsgpack.unpackb(data, max_array_len=2, option=ormsgpack.OPT_NON_STR_KEYS)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError, match="max_bin_len"):
+        ormsgpack.unpackb(data, max_bin_len=2, option=ormsgpack.OPT_NON_STR_KEYS)
+
+
+def test_limit_none() -> None:
+    assert ormsgpack.unpackb(b"\x91\xa1a", max_str_len=None, max_depth=None) == ["a"]
+
+
+def test_limit_large() -> None:
+    assert ormsgpack.unpackb(b"\xa1a", max_str_len=2**64) == "a"
+
+
+@pytest.mark.parametrize("value", (-1, 1.0, "1", True))
+def test_limit_invalid(value: object) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid max_str_len"):
+        ormsgpack.unpackb(b"\xa1a", max_str_len=value)  # type: ignore[arg-type]
+
+
+def test_limit_codec_unpacker() -> None:
+    data = ormsgpack.packb("abc")
+    codec = ormsgpack.Codec(max_str_len=2)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        codec.unpackb(data)
+    unpacker = ormsgpack.Unpacker(max_str_len=2)
+    unpacker.feed(ormsgpack.packb("ab") + data)
+    assert next(unpacker) == "ab"
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        next(unpacker)
+
+
+def test_limit_unpack() -> None:
+    data = ormsgpack.packb([1, 2, 3])
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpack(io.BytesIO(data), max_array_len=2)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpack_frame(ormsgpack.pack_frame(data), max_bin_len=2)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb_with_offset(data, max_elements=2)
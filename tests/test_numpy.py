@@ -632,3 +632,111 @@ def test_numpy_datetime64_unsupported_unit() -> None:
             numpy.datetime64("NaT"),
             option=ormsgpack.OPT_SERIALIZE_NUMPY,
         )
+
+
+@pytest.mark.parametrize(
+    ("value", "dtype"),
+    (
+        ([1, 2, 3], numpy.int8),
+        ([200], numpy.uint8),
+        ([-1, 200], numpy.int16),
+        ([40000], numpy.uint16),
+        ([-1, 40000], numpy.int32),
+        ([2**32 - 1], numpy.uint32),
+        ([-(2**63)], numpy.int64),
+        ([2**64 - 1], numpy.uint64),
+        ([0.5, 1.5], numpy.float64),
+        ([True, False], numpy.bool_),
+    ),
+)
+def test_numpy_array_as_numpy(value: list[object], dtype: type) -> None:
+    array = ormsgpack.unpackb(
+        ormsgpack.packb(value), option=ormsgpack.OPT_ARRAY_AS_NUMPY
+    )
+    assert isinstance(array, numpy.ndarray)
+    assert array.dtype == dtype
+    assert array.tolist() == value
+
+
+def test_numpy_array_as_numpy_float32() -> None:
+    value = numpy.array([0.5, 1.5], dtype=numpy.float32)
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_SERIALIZE_NUMPY)
+    array = ormsgpack.unpackb(packed, option=ormsgpack.OPT_ARRAY_AS_NUMPY)
+    assert array.dtype == numpy.float32
+    assert array.tolist() == [0.5, 1.5]
+
+
+def test_numpy_array_as_numpy_nested() -> None:
+    value = numpy.arange(24, dtype=numpy.int16).reshape(2, 3, 4) * 100
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_SERIALIZE_NUMPY)
+    array = ormsgpack.unpackb(packed, option=ormsgpack.OPT_ARRAY_AS_NUMPY)
+    assert array.shape == (2, 3, 4)
+    assert array.dtype == numpy.int16
+    assert array.flags.writeable
+    assert (array == value).all()
+
+
+@pytest.mark.parametrize(
+    "value",
+    ([], [[], []], [1, True], [1, 2.5], [1, None], [-1, 2**63], [1, [2, 3]]),
+)
+def test_numpy_array_as_numpy_list(value: list[object]) -> None:
+    result = ormsgpack.unpackb(
+        ormsgpack.packb(value), option=ormsgpack.OPT_ARRAY_AS_NUMPY
+    )
+    assert isinstance(result, list)
+    assert len(result) == len(value)
+    for item, expected in zip(result, value):
+        if isinstance(expected, list) and expected:
+            assert isinstance(item, numpy.ndarray)
+            assert item.tolist() == expected
+        else:
+            assert item == expected
+
+
+@pytest.mark.parametrize(
+    "value",
+    ([[1, 2], [3]], [[1, 2], [0.5, 1.5]], [[1, 2], [True, False]]),
+)
+def test_numpy_array_as_numpy_ragged(value: list[list[object]]) -> None:
+    result = ormsgpack.unpackb(
+        ormsgpack.packb(value), option=ormsgpack.OPT_ARRAY_AS_NUMPY
+    )
+    assert isinstance(result, list)
+    assert all(isinstance(item, numpy.ndarray) for item in result)
+    assert [item.tolist() for item in result] == value
+
+
+def test_numpy_array_as_numpy_map() -> None:
+    result = ormsgpack.unpackb(
+        ormsgpack.packb({"a": [1, 2], "b": "c"}),
+        option=ormsgpack.OPT_ARRAY_AS_NUMPY,
+    )
+    assert isinstance(result["a"], numpy.ndarray)
+    assert result["a"].tolist() == [1, 2]
+    assert result["b"] == "c"
+
+
+def test_numpy_array_as_numpy_tuple() -> None:
+    result = ormsgpack.unpackb(
+        ormsgpack.packb([[1, "a"], [1, 2]]),
+        option=ormsgpack.OPT_ARRAY_AS_NUMPY | ormsgpack.OPT_ARRAY_AS_TUPLE,
+    )
+    assert isinstance(result, tuple)
+    assert result[0] == (1, "a")
+    assert isinstance(result[1], numpy.ndarray)
+
+
+def test_numpy_array_as_numpy_error() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.unpackb(
+            b"\x92\x92\x01\x02\x92\x01\xc1", option=ormsgpack.OPT_ARRAY_AS_NUMPY
+        )
+    assert exc_info.value.pos == 6
+    assert exc_info.value.path == [1, 1]
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        ormsgpack.unpackb(
+            ormsgpack.packb([[1, 2]] * 3),
+            option=ormsgpack.OPT_ARRAY_AS_NUMPY,
+            max_elements=5,
+        )
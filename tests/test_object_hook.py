@@ -0,0 +1,117 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import collections
+import io
+
+import pytest
+
+import ormsgpack
+
+
+class Point:
+    def __init__(self, x: int, y: int) -> None:
+        self.x = x
+        self.y = y
+
+
+def point_hook(obj: dict) -> object:  # type: ignore[type-arg]
+    if obj.keys() == {"x", "y"}:
+        return Point(obj["x"], obj["y"])
+    return obj
+
+
+def test_object_hook() -> None:
+    data = ormsgpack.packb({"points": [{"x": 1, "y": 2}, {"x": 3, "y": 4}]})
+    obj = ormsgpack.unpackb(data, object_hook=point_hook)
+    assert isinstance(obj, dict)
+    assert [(p.x, p.y) for p in obj["points"]] == [(1, 2), (3, 4)]
+
+
+def test_object_hook_order() -> None:
+    calls = []
+
+    def hook(obj: dict) -> object:  # type: ignore[type-arg]
+        calls.append(dict(obj))
+        return len(calls)
+
+    data = ormsgpack.packb({"a": {"b": {}}, "c": {}})
+    assert ormsgpack.unpackb(data, object_hook=hook) == 4
+    assert calls == [{}, {"b": 1}, {}, {"a": 2, "c": 3}]
+
+
+def test_object_hook_non_str_keys() -> None:
+    data = ormsgpack.packb({1: {(1, 2): 3}}, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert ormsgpack.unpackb(
+        data,
+        object_hook=lambda obj: sorted(obj.items()),
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    ) == [(1, [((1, 2), 3)])]
+
+
+def test_object_hook_none() -> None:
+    assert ormsgpack.unpackb(b"\x80", object_hook=None) == {}
+
+
+def test_object_hook_exception() -> None:
+    def hook(obj: dict) -> object:  # type: ignore[type-arg]
+        raise ValueError
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="object_hook failed"):
+        ormsgpack.unpackb(b"\x91\x80", object_hook=hook)
+
+
+def test_object_pairs_hook() -> None:
+    data = ormsgpack.packb({"b": 1, "a": {"c": 2}})
+    assert ormsgpack.unpackb(data, object_pairs_hook=list) == [
+        ("b", 1),
+        ("a", [("c", 2)]),
+    ]
+    obj = ormsgpack.unpackb(data, object_pairs_hook=collections.OrderedDict)
+    assert isinstance(obj, collections.OrderedDict)
+    assert list(obj) == ["b", "a"]
+
+
+def test_object_pairs_hook_duplicate_keys() -> None:
+    data = b"\x82\xa1a\x01\xa1a\x02"
+    assert ormsgpack.unpackb(data) == {"a": 2}
+    assert ormsgpack.unpackb(data, object_pairs_hook=list) == [("a", 1), ("a", 2)]
+
+
+def test_object_pairs_hook_unhashable_keys() -> None:
+    data = b"\x82\x92\x01\x02\xa1a\x81\xa1b\x01\xa1c"
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert ormsgpack.unpackb(
+        data, object_pairs_hook=list, option=ormsgpack.OPT_NON_STR_KEYS
+    ) == [([1, 2], "a"), ([("b", 1)], "c")]
+
+
+def test_object_pairs_hook_str_keys() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(b"\x81\x01\x02", object_pairs_hook=list)
+
+
+def test_object_pairs_hook_precedence() -> None:
+    data = b"\x81\xa1a\x01"
+    obj = ormsgpack.unpackb(data, object_hook=dict, object_pairs_hook=list)
+    assert obj == [("a", 1)]
+
+
+def test_object_pairs_hook_exception() -> None:
+    def hook(obj: list) -> object:  # type: ignore[type-arg]
+        raise ValueError
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="object_pairs_hook failed"):
+        ormsgpack.unpackb(b"\x81\xa1a\x80", object_pairs_hook=hook)
+
+
+def test_object_hook_other_entry_points() -> None:
+    data = ormsgpack.packb({"x": 1, "y": 2})
+    frame = ormsgpack.pack_frame({"x": 1, "y": 2})
+    assert isinstance(ormsgpack.unpack(io.BytesIO(data), object_hook=point_hook), Point)
+    assert isinstance(ormsgpack.unpack_frame(frame, object_hook=point_hook), Point)
+    obj, end = ormsgpack.unpackb_with_offset(data, object_pairs_hook=list)
+    assert obj == [("x", 1), ("y", 2)]
+    assert isinstance(ormsgpack.Codec(object_hook=point_hook).unpackb(data), Point)
+    unpacker = ormsgpack.Unpacker(object_pairs_hook=list)
+    assert list(unpacker.feed(data)) == [[("x", 1), ("y", 2)]]
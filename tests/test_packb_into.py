@@ -0,0 +1,141 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import pytest
+
+import ormsgpack
+
+
+def test_packb_into_bytearray() -> None:
+    buffer = bytearray()
+    n = ormsgpack.packb_into({"a": [1, 2, 3]}, buffer)
+    assert n == len(buffer)
+    assert bytes(buffer) == ormsgpack.packb({"a": [1, 2, 3]})
+
+
+def test_packb_into_bytearray_offset() -> None:
+    buffer = bytearray(b"header")
+    n = ormsgpack.packb_into("value", buffer, 6)
+    assert buffer[:6] == b"header"
+    assert bytes(buffer[6:]) == ormsgpack.packb("value")
+    assert n == len(ormsgpack.packb("value"))
+
+
+def test_packb_into_bytearray_no_shrink() -> None:
+    buffer = bytearray(b"xxxx")
+    n = ormsgpack.packb_into(1, buffer, offset=2)
+    assert n == 1
+    assert buffer == bytearray(b"xx\x01x")
+
+
+def test_packb_into_bytearray_reuse() -> None:
+    buffer = bytearray()
+    for obj in ("a" * 1000, [1, 2], None):
+        n = ormsgpack.packb_into(obj, buffer)
+        assert bytes(buffer[:n]) == ormsgpack.packb(obj)
+
+
+def test_packb_into_bytearray_self() -> None:
+    for _ in range(4):
+        buffer = bytearray(b"x" * 1000000)
+        n = ormsgpack.packb_into(buffer, buffer, len(buffer))
+        assert bytes(buffer[1000000:]) == ormsgpack.packb(b"x" * 1000000)
+        assert n == len(buffer) - 1000000
+
+
+def test_packb_into_memoryview_self() -> None:
+    buffer = bytearray(b"abcdef" + bytes(8))
+    view = memoryview(buffer)
+    n = ormsgpack.packb_into(view[:6], view, 6)
+    assert n == 8
+    assert buffer == b"abcdef\xc4\x06abcdef"
+
+
+@pytest.mark.parametrize("buffer", (bytearray(b"data"), memoryview(bytearray(b"data"))))
+def test_packb_into_error_size_unchanged(buffer: bytearray | memoryview) -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(["a" * 100, object()], buffer)
+    assert len(buffer) == 4
+
+
+@pytest.mark.parametrize("size", (0, 8, 4096))
+def test_packb_into_default_resize(size: int) -> None:
+    buffer = bytearray(size)
+    errors = []
+
+    def default(obj: object) -> str:
+        for resize in (buffer.clear, lambda: buffer.extend(b"x" * 100000)):
+            try:
+                resize()
+            except BufferError as exc:
+                errors.append(exc)
+        return "default"
+
+    obj = ["a" * 1000, object(), "b" * 1000]
+    n = ormsgpack.packb_into(obj, buffer, default=default)
+    assert errors
+    assert bytes(buffer[:n]) == ormsgpack.packb(["a" * 1000, "default", "b" * 1000])
+
+
+def test_packb_into_memoryview() -> None:
+    buffer = bytearray(16)
+    view = memoryview(buffer)
+    n = ormsgpack.packb_into([1, 2, 3], view[4:])
+    assert n == 4
+    assert bytes(buffer[4 : 4 + n]) == ormsgpack.packb([1, 2, 3])
+    assert len(buffer) == 16
+
+
+def test_packb_into_memoryview_offset() -> None:
+    buffer = bytearray(8)
+    n = ormsgpack.packb_into(True, memoryview(buffer), 7)
+    assert n == 1
+    assert buffer == bytearray(b"\x00" * 7 + b"\xc3")
+
+
+def test_packb_into_buffer_too_small() -> None:
+    buffer = bytearray(4)
+    with pytest.raises(
+        ormsgpack.MsgpackEncodeError,
+        match="Buffer too small: 6 bytes required, 4 bytes available",
+    ):
+        ormsgpack.packb_into("abcde", memoryview(buffer))
+
+
+def test_packb_into_readonly() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1, b"\x00\x00")
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1, memoryview(bytearray(2)).toreadonly())
+
+
+@pytest.mark.parametrize("offset", (-1, 1.0, "1", 100))
+def test_packb_into_invalid_offset(offset: object) -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1, bytearray(4), offset)  # type: ignore[arg-type]
+
+
+def test_packb_into_memoryview_offset_out_of_range() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1, memoryview(bytearray(4)), 5)
+
+
+def test_packb_into_default_option() -> None:
+    buffer = bytearray()
+    n = ormsgpack.packb_into(
+        {1: object()},
+        buffer,
+        default=lambda obj: "default",
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert bytes(buffer[:n]) == ormsgpack.packb(
+        {1: "default"}, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+
+
+def test_packb_into_error() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(object(), bytearray())
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb_into(1, bytearray(), offset=0, unknown=1)  # type: ignore[call-arg]
@@ -0,0 +1,79 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import msgpack
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize("set_type", (set, frozenset))
+def test_set(set_type: type) -> None:
+    obj = set_type(("a", 1, None))
+    packed = ormsgpack.packb(obj)
+    assert packed == msgpack.packb(list(obj))
+    assert sorted(ormsgpack.unpackb(packed), key=repr) == sorted(obj, key=repr)
+
+
+@pytest.mark.parametrize("set_type", (set, frozenset))
+def test_set_empty(set_type: type) -> None:
+    assert ormsgpack.packb(set_type()) == msgpack.packb([])
+
+
+def test_set_nested() -> None:
+    obj = {"a": [{1}, frozenset({(2, 3)})]}
+    assert ormsgpack.packb(obj) == msgpack.packb({"a": [[1], [[2, 3]]]})
+
+
+def test_set_sort_keys() -> None:
+    obj = {b"x", None, "a", 1, 1.5}
+    packed = ormsgpack.packb(obj, option=ormsgpack.OPT_SORT_KEYS)
+    assert packed == msgpack.packb([1, "a", None, b"x", 1.5])
+
+
+def test_set_sort_keys_str() -> None:
+    values = [f"{i:03}" for i in range(100)]
+    for obj in (set(values), set(reversed(values))):
+        packed = ormsgpack.packb(obj, option=ormsgpack.OPT_SORT_KEYS)
+        assert packed == msgpack.packb(values)
+
+
+def test_set_sort_keys_nested() -> None:
+    obj = {"a": frozenset({frozenset({3, 2}), frozenset({1}), (0, "b")})}
+    packed = ormsgpack.packb(obj, option=ormsgpack.OPT_SORT_KEYS)
+    assert packed == msgpack.packb({"a": [[1], [0, "b"], [2, 3]]})
+
+
+def test_set_sort_keys_default() -> None:
+    class Value:
+        def __init__(self, value: int) -> None:
+            self.value = value
+
+    obj = {Value(2), Value(1)}
+    packed = ormsgpack.packb(
+        obj, default=lambda x: x.value, option=ormsgpack.OPT_SORT_KEYS
+    )
+    assert packed == msgpack.packb([1, 2])
+
+
+@pytest.mark.parametrize("option", (None, ormsgpack.OPT_SORT_KEYS))
+def test_set_invalid_element(option: int | None) -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb({1, object()}, option=option)
+
+
+@pytest.mark.parametrize("option", (None, ormsgpack.OPT_SORT_KEYS))
+def test_set_recursion_limit(option: int | None) -> None:
+    obj: frozenset[object] = frozenset()
+    for _ in range(254):
+        obj = frozenset({obj})
+    ormsgpack.packb(obj, option=option)
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="Recursion limit"):
+        ormsgpack.packb(frozenset({obj}), option=option)
+
+
+def test_set_subclass() -> None:
+    class Subclass(set[int]):
+        pass
+
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(Subclass({1}))
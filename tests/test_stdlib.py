@@ -0,0 +1,102 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import ipaddress
+import os
+import pathlib
+import subprocess
+import sys
+import urllib.parse
+
+import msgpack
+import pytest
+
+import ormsgpack
+
+
+@pytest.mark.parametrize(
+    ("value", "string"),
+    (
+        (pathlib.PurePosixPath("/usr/lib"), "/usr/lib"),
+        (pathlib.PureWindowsPath("C:/Windows"), "C:\\Windows"),
+        (pathlib.Path("a/b"), "a/b"),
+        (ipaddress.IPv4Address("192.0.2.1"), "192.0.2.1"),
+        (ipaddress.IPv6Address("2001:db8::1"), "2001:db8::1"),
+        (ipaddress.IPv4Network("192.0.2.0/24"), "192.0.2.0/24"),
+        (ipaddress.IPv6Network("2001:db8::/32"), "2001:db8::/32"),
+        (ipaddress.IPv4Interface("192.0.2.1/24"), "192.0.2.1/24"),
+        (ipaddress.IPv6Interface("2001:db8::1/64"), "2001:db8::1/64"),
+        (
+            urllib.parse.urlparse("https://example.com/a;p?q=1#f"),
+            "https://example.com/a;p?q=1#f",
+        ),
+        (
+            urllib.parse.urlsplit("https://example.com/a?q=1#f"),
+            "https://example.com/a?q=1#f",
+        ),
+        (
+            urllib.parse.urldefrag("https://example.com/a#f"),
+            "https://example.com/a#f",
+        ),
+    ),
+)
+def test_stdlib(value: object, string: str) -> None:
+    packed = ormsgpack.packb(value)
+    assert packed == msgpack.packb(string)
+    packed = ormsgpack.packb({value: 1}, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert packed == msgpack.packb({string: 1})
+
+
+def test_stdlib_nested() -> None:
+    obj = {"path": pathlib.PurePosixPath("/tmp"), "hosts": [ipaddress.ip_address(1)]}
+    packed = ormsgpack.packb(obj)
+    assert packed == msgpack.packb({"path": "/tmp", "hosts": ["0.0.0.1"]})
+
+
+def test_stdlib_path_surrogates() -> None:
+    value = pathlib.PurePosixPath("a\udcff")
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(value)
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_REPLACE_SURROGATES)
+    assert ormsgpack.unpackb(packed) == "a?"
+
+
+def test_stdlib_subclass() -> None:
+    class Subclass(pathlib.PurePosixPath):
+        pass
+
+    value = Subclass("/tmp")
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(value)
+    assert ormsgpack.packb(value, default=str) == msgpack.packb("/tmp")
+
+
+def test_stdlib_bytes_url() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(urllib.parse.urlparse(b"https://example.com"))
+
+
+def test_stdlib_ext_types() -> None:
+    packed = ormsgpack.packb(
+        ipaddress.IPv4Address("192.0.2.1"),
+        ext_types={ipaddress.IPv4Address: (1, lambda x: x.packed)},
+    )
+    assert packed == msgpack.packb(msgpack.ExtType(1, b"\xc0\x00\x02\x01"))
+
+
+LAZY_IMPORT_SCRIPT = """
+import sys
+modules = {"decimal", "fractions", "ipaddress", "pathlib", "urllib.parse"}
+imported = modules & set(sys.modules)
+import ormsgpack
+assert modules & set(sys.modules) == imported
+assert ormsgpack.packb(object(), default=lambda obj: None) == b"\\xc0"
+assert modules & set(sys.modules) == imported
+import decimal, pathlib
+packed = ormsgpack.packb([pathlib.PurePosixPath("/a"), decimal.Decimal("1.5")])
+assert ormsgpack.unpackb(packed) == ["/a", "1.5"]
+"""
+
+
+def test_stdlib_lazy_import() -> None:
+    env = {**os.environ, "PYTHONPATH": os.pathsep.join(sys.path)}
+    subprocess.run([sys.executable, "-c", LAZY_IMPORT_SCRIPT], check=True, env=env)
@@ -0,0 +1,192 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+import os
+from pathlib import Path
+
+import pytest
+
+import ormsgpack
+
+
+class ChunkedReader(io.RawIOBase):
+    """A raw stream that returns at most one byte per read."""
+
+    def __init__(self, data: bytes) -> None:
+        self._data = data
+        self._pos = 0
+
+    def readable(self) -> bool:
+        return True
+
+    def readinto(self, buffer: bytearray) -> int:  # type: ignore[override]
+        data = self._data[self._pos : self._pos + 1]
+        buffer[: len(data)] = data
+        self._pos += len(data)
+        return len(data)
+
+
+class CountingWriter(io.RawIOBase):
+    def __init__(self) -> None:
+        self.chunks: list[bytes] = []
+
+    def writable(self) -> bool:
+        return True
+
+    def write(self, data: bytes) -> int:  # type: ignore[override]
+        self.chunks.append(bytes(data))
+        return len(data)
+
+
+class ShortWriter(io.RawIOBase):
+    """A raw stream that writes at most 1000 bytes per call."""
+
+    def __init__(self) -> None:
+        self.data = bytearray()
+
+    def writable(self) -> bool:
+        return True
+
+    def write(self, data: bytes) -> int:  # type: ignore[override]
+        self.data += data[:1000]
+        return min(len(data), 1000)
+
+
+class FailingStream(io.RawIOBase):
+    def readable(self) -> bool:
+        return True
+
+    def writable(self) -> bool:
+        return True
+
+    def readinto(self, buffer: bytearray) -> int:  # type: ignore[override]
+        raise OSError("read failed")
+
+    def write(self, data: bytes) -> int:  # type: ignore[override]
+        raise OSError("write failed")
+
+
+def test_pack() -> None:
+    obj = {"a": [1, 2.5, "b", None, True], "c": b"d"}
+    fp = io.BytesIO()
+    assert ormsgpack.pack(obj, fp) is None
+    assert fp.getvalue() == ormsgpack.packb(obj)
+
+
+def test_pack_chunked() -> None:
+    obj = [b"x" * 100000, "y" * 50000, list(range(10000)), b"z" * 300000]
+    fp = CountingWriter()
+    ormsgpack.pack(obj, fp)
+    assert b"".join(fp.chunks) == ormsgpack.packb(obj)
+    assert len(fp.chunks) > 1
+    assert max(len(chunk) for chunk in fp.chunks) <= 65536
+
+
+def test_pack_default_option() -> None:
+    fp = io.BytesIO()
+    ormsgpack.pack(
+        {1: object()},
+        fp,
+        default=lambda obj: "default",
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert ormsgpack.unpackb(fp.getvalue(), option=ormsgpack.OPT_NON_STR_KEYS) == {
+        1: "default"
+    }
+
+
+def test_pack_short_writes() -> None:
+    obj = [b"x" * 100000, "y" * 5000, list(range(10000))]
+    fp = ShortWriter()
+    ormsgpack.pack(obj, fp)
+    assert fp.data == ormsgpack.packb(obj)
+
+
+def test_pack_write_error() -> None:
+    with pytest.raises(OSError, match="write failed"):
+        ormsgpack.pack([1, 2, 3], FailingStream())
+    with pytest.raises(OSError, match="write failed"):
+        ormsgpack.pack(b"x" * 1000000, FailingStream())
+
+
+def test_pack_invalid_type() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.pack(object(), io.BytesIO())
+    with pytest.raises(AttributeError):
+        ormsgpack.pack(1, object())
+
+
+def test_unpack() -> None:
+    obj = {"a": [1, 2.5, "b", None, True], "c": b"d"}
+    assert ormsgpack.unpack(io.BytesIO(ormsgpack.packb(obj))) == obj
+
+
+def test_unpack_large() -> None:
+    obj = [b"x" * 1000000, "y" * 200000, list(range(100000))]
+    assert ormsgpack.unpack(io.BytesIO(ormsgpack.packb(obj))) == obj
+
+
+def test_unpack_short_reads() -> None:
+    obj = {"a": [1, 2.5, "b" * 100, None], "c": b"d" * 1000}
+    assert ormsgpack.unpack(ChunkedReader(ormsgpack.packb(obj))) == obj
+
+
+def test_unpack_consecutive() -> None:
+    fp = io.BytesIO()
+    for i in range(3):
+        ormsgpack.pack({"i": i}, fp)
+    fp.write(b"tail")
+    fp.seek(0)
+    assert [ormsgpack.unpack(fp) for _ in range(3)] == [{"i": 0}, {"i": 1}, {"i": 2}]
+    assert fp.read() == b"tail"
+
+
+def test_unpack_non_seekable() -> None:
+    objs = [{"i": i, "data": b"x" * 10000 * i} for i in range(3)]
+    read_fd, write_fd = os.pipe()
+    with open(read_fd, "rb", buffering=0) as reader:
+        with open(write_fd, "wb") as writer:
+            for obj in objs:
+                ormsgpack.pack(obj, writer)
+            writer.write(b"tail")
+        assert not reader.seekable()
+        assert [ormsgpack.unpack(reader) for _ in objs] == objs
+        assert reader.read() == b"tail"
+
+
+def test_unpack_options() -> None:
+    fp = io.BytesIO(
+        ormsgpack.packb(
+            {1: ormsgpack.Ext(1, b"data")}, option=ormsgpack.OPT_NON_STR_KEYS
+        )
+    )
+    assert ormsgpack.unpack(
+        fp,
+        ext_hook=lambda tag, data: (tag, data),
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    ) == {1: (1, b"data")}
+
+
+@pytest.mark.parametrize("data", (b"", b"\x92\x01", b"\xc4\x10abc"))
+def test_unpack_truncated(data: bytes) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpack(io.BytesIO(data))
+
+
+def test_unpack_invalid_length() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpack(io.BytesIO(b"\xc6\xff\xff\xff\xff"))
+
+
+def test_unpack_read_error() -> None:
+    with pytest.raises(OSError, match="read failed"):
+        ormsgpack.unpack(FailingStream())
+
+
+def test_roundtrip_file(tmp_path: Path) -> None:
+    obj = {"key": ["value" * 1000] * 100}
+    path = tmp_path / "data.msgpack"
+    with open(path, "wb") as f:
+        ormsgpack.pack(obj, f)
+    with open(path, "rb") as f:
+        assert ormsgpack.unpack(f) == obj
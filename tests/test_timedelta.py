@@ -0,0 +1,148 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import datetime
+
+import msgpack
+import pytest
+
+import ormsgpack
+
+TIMEDELTA_PARAMS = (
+    (datetime.timedelta(), "PT0S", 0),
+    (datetime.timedelta(days=1), "P1D", 86400000000),
+    (datetime.timedelta(days=-1), "-P1D", -86400000000),
+    (datetime.timedelta(seconds=-1), "-PT1S", -1000000),
+    (datetime.timedelta(minutes=5), "PT5M", 300000000),
+    (datetime.timedelta(microseconds=500000), "PT0.5S", 500000),
+    (
+        datetime.timedelta(days=2, hours=3, minutes=4, seconds=5, microseconds=6),
+        "P2DT3H4M5.000006S",
+        183845000006,
+    ),
+    (
+        datetime.timedelta.max,
+        "P999999999DT23H59M59.999999S",
+        86399999999999999999,
+    ),
+    (datetime.timedelta.min, "-P999999999D", -86399999913600000000),
+)
+
+
+@pytest.mark.parametrize(("value", "duration", "microseconds"), TIMEDELTA_PARAMS)
+def test_timedelta(
+    value: datetime.timedelta, duration: str, microseconds: int
+) -> None:
+    packed = ormsgpack.packb(value)
+    assert packed == msgpack.packb(duration)
+    assert ormsgpack.unpackb(packed) == duration
+    assert ormsgpack.unpackb(packed, type=datetime.timedelta) == value
+
+
+@pytest.mark.parametrize(("value", "duration", "microseconds"), TIMEDELTA_PARAMS)
+def test_timedelta_as_microseconds(
+    value: datetime.timedelta, duration: str, microseconds: int
+) -> None:
+    option = ormsgpack.OPT_TIMEDELTA_AS_MICROSECONDS
+    if abs(microseconds) >= 2**63:
+        with pytest.raises(ormsgpack.MsgpackEncodeError, match="64-bit range"):
+            ormsgpack.packb(value, option=option)
+        return
+    packed = ormsgpack.packb(value, option=option)
+    assert packed == msgpack.packb(microseconds)
+    assert ormsgpack.unpackb(packed, type=datetime.timedelta) == value
+
+
+@pytest.mark.parametrize(("value", "duration", "microseconds"), TIMEDELTA_PARAMS)
+def test_timedelta_as_seconds(
+    value: datetime.timedelta, duration: str, microseconds: int
+) -> None:
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_TIMEDELTA_AS_SECONDS)
+    assert packed == msgpack.packb(value.total_seconds())
+    if abs(microseconds) < 2**53:
+        assert ormsgpack.unpackb(packed, type=datetime.timedelta) == value
+
+
+def test_timedelta_incompatible_options() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError, match="not compatible"):
+        ormsgpack.packb(
+            datetime.timedelta(),
+            option=ormsgpack.OPT_TIMEDELTA_AS_MICROSECONDS
+            | ormsgpack.OPT_TIMEDELTA_AS_SECONDS,
+        )
+
+
+def test_timedelta_dict_key() -> None:
+    obj = {datetime.timedelta(hours=1): 1}
+    packed = ormsgpack.packb(obj, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert packed == msgpack.packb({"PT1H": 1})
+
+
+def test_timedelta_passthrough() -> None:
+    value = datetime.timedelta(hours=1)
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(value, option=ormsgpack.OPT_PASSTHROUGH_DATETIME)
+    assert ormsgpack.packb(
+        value, option=ormsgpack.OPT_PASSTHROUGH_DATETIME, default=str
+    ) == msgpack.packb("1:00:00")
+
+
+@pytest.mark.parametrize(
+    ("duration", "value"),
+    (
+        ("PT36H", datetime.timedelta(hours=36)),
+        ("+P2D", datetime.timedelta(days=2)),
+        ("-PT1.5S", datetime.timedelta(seconds=-1.5)),
+        ("P1DT90M", datetime.timedelta(days=1, minutes=90)),
+        ("PT0.000001S", datetime.timedelta(microseconds=1)),
+    ),
+)
+def test_timedelta_type_duration(duration: str, value: datetime.timedelta) -> None:
+    packed = ormsgpack.packb([duration])
+    unpacked = ormsgpack.unpackb(packed, type=list[datetime.timedelta])
+    assert unpacked == [value]
+
+
+@pytest.mark.parametrize(
+    "duration",
+    (
+        "P",
+        "PT",
+        "P1",
+        "P1DT",
+        "PT1D",
+        "P1H",
+        "P1.5D",
+        "PT1.S",
+        "PT1,5S",
+        "PT1M1H",
+        "PT0.0000001S",
+        "P1Y",
+        "P1W",
+        "P1000000000D",
+        "period",
+    ),
+)
+def test_timedelta_type_duration_invalid(duration: str) -> None:
+    packed = ormsgpack.packb(duration)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(packed, type=datetime.timedelta)
+
+
+def test_duration_untyped() -> None:
+    packed = ormsgpack.packb({"code": "P1D", "PT1S": ["PT1S"]})
+    assert ormsgpack.unpackb(packed) == {"code": "P1D", "PT1S": ["PT1S"]}
+
+
+def test_timedelta_type_map_value() -> None:
+    packed = ormsgpack.packb({"PT1S": "PT1S"})
+    unpacked = ormsgpack.unpackb(packed, type=dict[str, datetime.timedelta])
+    assert unpacked == {"PT1S": datetime.timedelta(seconds=1)}
+
+
+@pytest.mark.parametrize("value", ("period", 2**63, float("nan"), True, [1]))
+def test_timedelta_type_invalid(value: object) -> None:
+    packed = ormsgpack.packb(value, option=ormsgpack.OPT_BIG_INT_AS_EXT)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(
+            packed, type=datetime.timedelta, option=ormsgpack.OPT_BIG_INT_AS_EXT
+        )
@@ -0,0 +1,134 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import datetime
+
+import pytest
+
+import ormsgpack
+
+TIMESTAMP_PARAMS = (
+    (ormsgpack.Timestamp(0), b"\xd6\xff\x00\x00\x00\x00"),
+    (ormsgpack.Timestamp(1, 5), b"\xd7\xff\x00\x00\x00\x14\x00\x00\x00\x01"),
+    (
+        ormsgpack.Timestamp(-1, 999999999),
+        b"\xc7\x0c\xff\x3b\x9a\xc9\xff\xff\xff\xff\xff\xff\xff\xff\xff",
+    ),
+    (
+        ormsgpack.Timestamp(2**40, 7),
+        b"\xc7\x0c\xff\x00\x00\x00\x07\x00\x00\x01\x00\x00\x00\x00\x00",
+    ),
+)
+
+
+@pytest.mark.parametrize(("value", "serialized_value"), TIMESTAMP_PARAMS)
+def test_timestamp_packb(value: ormsgpack.Timestamp, serialized_value: bytes) -> None:
+    assert ormsgpack.packb(value) == serialized_value
+
+
+@pytest.mark.parametrize(("value", "serialized_value"), TIMESTAMP_PARAMS)
+def test_timestamp_as_int(value: ormsgpack.Timestamp, serialized_value: bytes) -> None:
+    obj = ormsgpack.unpackb(serialized_value, option=ormsgpack.OPT_TIMESTAMP_AS_INT)
+    assert obj == value.seconds * 10**9 + value.nanoseconds
+
+
+def test_timestamp_as_int_large() -> None:
+    value = ormsgpack.Timestamp(-(2**63), 1)
+    obj = ormsgpack.unpackb(
+        ormsgpack.packb(value), option=ormsgpack.OPT_TIMESTAMP_AS_INT
+    )
+    assert obj == -(2**63) * 10**9 + 1
+
+
+def test_timestamp_as_int_precedence() -> None:
+    option = ormsgpack.OPT_TIMESTAMP_AS_INT | ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+    data = ormsgpack.packb(ormsgpack.Timestamp(1))
+    assert ormsgpack.unpackb(data, option=option) == 10**9
+
+
+def test_timestamp_naive_utc() -> None:
+    data = ormsgpack.packb(ormsgpack.Timestamp(1, 5000))
+    obj = ormsgpack.unpackb(
+        data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT | ormsgpack.OPT_NAIVE_UTC,
+    )
+    assert obj == datetime.datetime(1970, 1, 1, 0, 0, 1, 5)
+    assert obj.tzinfo is None
+
+
+@pytest.mark.parametrize(
+    "value",
+    (
+        ormsgpack.Timestamp(-62135596801),
+        ormsgpack.Timestamp(253402300800),
+        ormsgpack.Timestamp(2**63 - 1, 999999999),
+    ),
+)
+def test_timestamp_fallback(value: ormsgpack.Timestamp) -> None:
+    data = ormsgpack.packb(value)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT)
+    obj = ormsgpack.unpackb(
+        data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+        | ormsgpack.OPT_TIMESTAMP_FALLBACK,
+    )
+    assert obj == value
+
+
+def test_timestamp_fallback_in_range() -> None:
+    data = ormsgpack.packb(ormsgpack.Timestamp(-62135596800))
+    obj = ormsgpack.unpackb(
+        data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+        | ormsgpack.OPT_TIMESTAMP_FALLBACK,
+    )
+    assert obj == datetime.datetime(1, 1, 1, tzinfo=datetime.timezone.utc)
+
+
+def test_timestamp_fallback_map_key() -> None:
+    value = ormsgpack.Timestamp(2**40)
+    data = ormsgpack.packb({value: 1}, option=ormsgpack.OPT_NON_STR_KEYS)
+    obj = ormsgpack.unpackb(
+        data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+        | ormsgpack.OPT_TIMESTAMP_FALLBACK
+        | ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert obj == {value: 1}
+
+
+def test_timestamp_invalid_nanoseconds() -> None:
+    data = b"\xd7\xff" + (10**9 << 34).to_bytes(8, "big")
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_TIMESTAMP_AS_INT)
+
+
+def test_timestamp_type() -> None:
+    value = ormsgpack.Timestamp(1, 2)
+    assert value.seconds == 1
+    assert value.nanoseconds == 2
+    assert ormsgpack.Timestamp(1).nanoseconds == 0
+    assert repr(value) == "Timestamp(seconds=1, nanoseconds=2)"
+    assert value == ormsgpack.Timestamp(1, 2)
+    assert value != ormsgpack.Timestamp(1, 3)
+    assert value < ormsgpack.Timestamp(2, 0)
+    assert value != (1, 2)
+    assert hash(value) == hash(ormsgpack.Timestamp(1, 2))
+
+
+@pytest.mark.parametrize(
+    ("args", "exception"),
+    (
+        ((), TypeError),
+        ((1, 2, 3), TypeError),
+        ((1.0,), TypeError),
+        ((1, -1), ValueError),
+        ((1, 10**9), ValueError),
+        ((2**63,), OverflowError),
+    ),
+)
+def test_timestamp_type_invalid(args: tuple[object, ...], exception: type) -> None:
+    with pytest.raises(exception):
+        ormsgpack.Timestamp(*args)  # type: ignore[arg-type]
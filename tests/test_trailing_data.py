@@ -0,0 +1,51 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import io
+
+import pytest
+
+import ormsgpack
+
+
+def test_trailing_data_ignored() -> None:
+    assert ormsgpack.unpackb(b"\x01\x02") == 1
+
+
+@pytest.mark.parametrize("data", (b"\x01\x02", b"\x92\x01\x02\xc0", b"\xa1a\x00"))
+def test_trailing_data_forbidden(data: bytes) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="trailing data"):
+        ormsgpack.unpackb(data, option=ormsgpack.OPT_FORBID_TRAILING_DATA)
+
+
+def test_trailing_data_forbidden_no_trailing_data() -> None:
+    obj = {"a": [1, 2, 3]}
+    assert (
+        ormsgpack.unpackb(
+            ormsgpack.packb(obj), option=ormsgpack.OPT_FORBID_TRAILING_DATA
+        )
+        == obj
+    )
+
+
+def test_trailing_data_forbidden_codec() -> None:
+    codec = ormsgpack.Codec(unpack_option=ormsgpack.OPT_FORBID_TRAILING_DATA)
+    assert codec.unpackb(b"\x01") == 1
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="trailing data"):
+        codec.unpackb(b"\x01\x02")
+
+
+def test_trailing_data_forbidden_unpack() -> None:
+    option = ormsgpack.OPT_FORBID_TRAILING_DATA
+    assert ormsgpack.unpack(io.BytesIO(b"\x01"), option=option) == 1
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="trailing data"):
+        ormsgpack.unpack(io.BytesIO(b"\x01\x02"), option=option)
+
+
+def test_trailing_data_forbidden_packb() -> None:
+    with pytest.raises(ormsgpack.MsgpackEncodeError):
+        ormsgpack.packb(1, option=ormsgpack.OPT_FORBID_TRAILING_DATA)
+
+
+def test_trailing_data_forbidden_unpacker() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.Unpacker(option=ormsgpack.OPT_FORBID_TRAILING_DATA)
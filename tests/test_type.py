@@ -0,0 +1,292 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import dataclasses
+import gc
+import io
+import typing
+import uuid
+import weakref
+
+import pytest
+
+import ormsgpack
+
+
+@dataclasses.dataclass
+class Point:
+    x: float
+    y: float
+
+
+@dataclasses.dataclass
+class Shape:
+    name: str
+    points: list[Point]
+    color: typing.Optional[str] = None
+    tags: list[str] = dataclasses.field(default_factory=list)
+
+
+@dataclasses.dataclass
+class Tree:
+    value: int
+    children: list["Tree"]
+
+
+@dataclasses.dataclass
+class Positive:
+    value: int
+
+    def __post_init__(self) -> None:
+        if self.value <= 0:
+            raise ValueError("not positive")
+
+
+class Pair(typing.NamedTuple):
+    first: int
+    second: str = ""
+
+
+class Movie(typing.TypedDict):
+    title: str
+    year: int
+
+
+class PartialMovie(typing.TypedDict, total=False):
+    title: str
+    year: int
+
+
+def test_type_dataclass() -> None:
+    obj = Shape(name="a", points=[Point(0.5, 1.5)], tags=["x"])
+    assert ormsgpack.unpackb(ormsgpack.packb(obj), type=Shape) == obj
+
+
+def test_type_dataclass_default() -> None:
+    data = ormsgpack.packb({"name": "a", "points": []})
+    assert ormsgpack.unpackb(data, type=Shape) == Shape(name="a", points=[])
+
+
+def test_type_dataclass_unknown_key() -> None:
+    data = ormsgpack.packb({"x": 1.0, "y": 2.0, "z": 3.0})
+    assert ormsgpack.unpackb(data, type=Point) == Point(1.0, 2.0)
+
+
+def test_type_dataclass_missing_field() -> None:
+    data = ormsgpack.packb({"name": "a", "points": [{"x": 1.0}]})
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"missing required field 'y' at position 16, path \['points', 0\]$",
+    ):
+        ormsgpack.unpackb(data, type=Shape)
+
+
+def test_type_dataclass_recursive() -> None:
+    obj = Tree(1, [Tree(2, []), Tree(3, [Tree(4, [])])])
+    assert ormsgpack.unpackb(ormsgpack.packb(obj), type=Tree) == obj
+
+
+def test_type_dataclass_constructor_exception() -> None:
+    data = ormsgpack.packb({"value": 0})
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"Positive construction failed at position 0$",
+    ) as exc_info:
+        ormsgpack.unpackb(data, type=Positive)
+    assert isinstance(exc_info.value.__cause__, ValueError)
+
+
+def test_type_namedtuple() -> None:
+    data = ormsgpack.packb([[1, "a"], [2]])
+    assert ormsgpack.unpackb(data, type=list[Pair]) == [Pair(1, "a"), Pair(2)]
+
+
+def test_type_namedtuple_invalid_length() -> None:
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"expected array of length 1 to 2, got 3 at position 0$",
+    ):
+        ormsgpack.unpackb(ormsgpack.packb([1, "a", None]), type=Pair)
+
+
+def test_type_typeddict() -> None:
+    data = ormsgpack.packb({"title": "a", "year": 2000, "rating": 5})
+    obj = ormsgpack.unpackb(data, type=Movie)
+    assert obj == {"title": "a", "year": 2000}
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"missing required field 'year' at position 0$",
+    ):
+        ormsgpack.unpackb(ormsgpack.packb({"title": "a"}), type=Movie)
+    data = ormsgpack.packb({"title": "a"})
+    assert ormsgpack.unpackb(data, type=PartialMovie) == {"title": "a"}
+
+
+@pytest.mark.parametrize(
+    "tp,value",
+    [
+        (int, 1),
+        (float, 1.5),
+        (str, "a"),
+        (bytes, b"a"),
+        (bool, True),
+        (type(None), None),
+        (typing.Any, {"a": [1]}),
+        (list[int], [1, 2]),
+        (tuple[int, ...], (1, 2)),
+        (tuple[int, str], (1, "a")),
+        (dict[str, list[int]], {"a": [1]}),
+        (typing.Optional[int], None),
+        (int | str, "a"),
+        (typing.Union[int, str], 1),
+        (typing.Literal["a", "b"], "b"),
+        (list, [1, "a"]),
+        (dict, {"a": 1}),
+    ],
+)
+def test_type_valid(tp: typing.Any, value: typing.Any) -> None:
+    obj = ormsgpack.unpackb(ormsgpack.packb(value), type=tp)
+    assert obj == value
+    assert type(obj) is type(value)
+
+
+def test_type_float_from_int() -> None:
+    obj = ormsgpack.unpackb(ormsgpack.packb(1), type=float)
+    assert obj == 1.0
+    assert isinstance(obj, float)
+
+
+@pytest.mark.parametrize(
+    "tp,value,message",
+    [
+        (int, True, r"expected int, got bool at position 0$"),
+        (int, 1.5, r"expected int, got float at position 0$"),
+        (str, 1, r"expected str, got int at position 0$"),
+        (bool, 1, r"expected bool, got int at position 0$"),
+        (list[int], [1, "a"], r"expected int, got str at position 2, path \[1\]$"),
+        (tuple[int, int], [1], r"expected array of length 2, got 1 at position 0$"),
+        (
+            dict[str, int],
+            {"a": None},
+            r"expected int, got NoneType at position 3, path \['a'\]$",
+        ),
+        (typing.Optional[int], "a", r"expected int, got str at position 0$"),
+        (typing.Literal["a", 1], "b", r"got 'b' at position 0$"),
+        (typing.Literal[1], True, r"got True at position 0$"),
+        (int | str, None, r"expected int \| str, got NoneType at position 0$"),
+        (Point, [1.0, 2.0], r"expected Point, got list at position 0$"),
+    ],
+)
+def test_type_invalid(tp: typing.Any, value: typing.Any, message: str) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match=message):
+        ormsgpack.unpackb(ormsgpack.packb(value), type=tp)
+
+
+def test_type_path() -> None:
+    data = ormsgpack.packb({"name": "a", "points": [{"x": 1, "y": 2}, {"x": 1}]})
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"missing required field 'y' at position 23, path \['points', 1\]$",
+    ) as exc_info:
+        ormsgpack.unpackb(data, type=Shape)
+    assert exc_info.value.pos == 23
+    assert exc_info.value.path == ["points", 1]
+    data = ormsgpack.packb({"name": "a", "points": [], "color": 1})
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"expected str, got int at position 22, path \['color'\]$",
+    ) as exc_info:
+        ormsgpack.unpackb(data, type=Shape)
+    assert exc_info.value.pos == 22
+    assert exc_info.value.path == ["color"]
+
+
+def test_type_dict_key_path() -> None:
+    data = ormsgpack.packb({1: [1, 2], 2: [3, "a"]}, option=ormsgpack.OPT_NON_STR_KEYS)
+    with pytest.raises(
+        ormsgpack.MsgpackDecodeError,
+        match=r"expected int, got str at position 8, path \[2, 1\]$",
+    ) as exc_info:
+        ormsgpack.unpackb(
+            data, type=dict[int, list[int]], option=ormsgpack.OPT_NON_STR_KEYS
+        )
+    assert exc_info.value.pos == 8
+    assert exc_info.value.path == [2, 1]
+
+
+def test_type_instance() -> None:
+    value = uuid.UUID("00000000-0000-0000-0000-000000000001")
+    data = ormsgpack.packb(ormsgpack.Ext(1, value.bytes))
+
+    def ext_hook(tag: int, data: bytes) -> uuid.UUID:
+        return uuid.UUID(bytes=data)
+
+    assert ormsgpack.unpackb(data, ext_hook=ext_hook, type=uuid.UUID) == value
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(ormsgpack.packb("a"), type=uuid.UUID)
+
+
+def test_type_array_as_tuple() -> None:
+    data = ormsgpack.packb([[1, 2]])
+    obj = ormsgpack.unpackb(
+        data, type=list[list[int]], option=ormsgpack.OPT_ARRAY_AS_TUPLE
+    )
+    assert obj == [[1, 2]]
+
+
+@pytest.mark.parametrize("tp", [set[int], "int", 1, typing.Callable[[], int]])
+def test_type_unsupported(tp: typing.Any) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid type"):
+        ormsgpack.unpackb(b"\x90", type=tp)
+
+
+def test_type_unresolved_annotation() -> None:
+    @dataclasses.dataclass
+    class Invalid:
+        x: "Undefined"  # type: ignore[name-defined] # noqa: F821
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid type") as exc_info:
+        ormsgpack.unpackb(b"\x80", type=Invalid)
+    assert isinstance(exc_info.value.__cause__, NameError)
+
+
+def test_type_none() -> None:
+    assert ormsgpack.unpackb(b"\x01", type=None) == 1
+    assert ormsgpack.unpackb(b"\xc0", type=type(None)) is None
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(b"\x01", type=type(None))
+
+
+def test_type_unpacker() -> None:
+    unpacker = ormsgpack.Unpacker(type=Point)
+    unpacker.feed(ormsgpack.packb(Point(1.0, 2.0)) * 2)
+    assert list(unpacker) == [Point(1.0, 2.0)] * 2
+
+
+def test_type_unpack() -> None:
+    fp = io.BytesIO(ormsgpack.packb([Point(1.0, 2.0)]))
+    assert ormsgpack.unpack(fp, type=list[Point]) == [Point(1.0, 2.0)]
+
+
+def test_type_codec() -> None:
+    codec = ormsgpack.Codec(type=Shape)
+    obj = Shape(name="a", points=[Point(1.0, 2.0)])
+    assert codec.unpackb(codec.packb(obj)) == obj
+
+
+def test_type_cache_bounded() -> None:
+    def make_class() -> type:
+        @dataclasses.dataclass
+        class Local:
+            x: int
+
+        return Local
+
+    data = ormsgpack.packb({"x": 1})
+    cls = make_class()
+    assert ormsgpack.unpackb(data, type=cls).x == 1
+    ref = weakref.ref(cls)
+    del cls
+    for _ in range(1000):
+        assert ormsgpack.unpackb(data, type=make_class()).x == 1
+    gc.collect()
+    assert ref() is None
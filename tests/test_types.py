@@ -31,7 +31,7 @@ class StrSubclass(str):
 
 
 def default(obj: object) -> object:
-    if isinstance(obj, set):
+    if isinstance(obj, range):
         return str(obj)
     raise TypeError
 
@@ -165,7 +165,13 @@ TYPE_PARAMS = (
     ),
     pytest.param(
         {1, 2},
-        "{1, 2}",
+        [1, 2],
+        0,
+        id="set",
+    ),
+    pytest.param(
+        range(1, 3),
+        "range(1, 3)",
         0,
         id="unknown",
     ),
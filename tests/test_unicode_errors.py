@@ -0,0 +1,81 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import pytest
+
+import ormsgpack
+
+LATIN_1 = "caf\xe9".encode("latin-1")
+
+
+def str_obj(data: bytes) -> bytes:
+    return bytes([0xA0 | len(data)]) + data
+
+
+def test_raw() -> None:
+    data = ormsgpack.packb({"a": ["b", "é", b"c"], "x" * 100: 1})
+    assert ormsgpack.unpackb(data, raw=True) == {
+        b"a": [b"b", "é".encode(), b"c"],
+        b"x" * 100: 1,
+    }
+    assert ormsgpack.unpackb(data, raw=False) == ormsgpack.unpackb(data)
+
+
+def test_raw_invalid_utf8() -> None:
+    data = b"\x81" + str_obj(LATIN_1) + str_obj(LATIN_1)
+    assert ormsgpack.unpackb(data, raw=True) == {LATIN_1: LATIN_1}
+
+
+def test_raw_non_str_keys() -> None:
+    data = ormsgpack.packb({"a": 1, 2: "b"}, option=ormsgpack.OPT_NON_STR_KEYS)
+    obj = ormsgpack.unpackb(data, raw=True, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert obj == {b"a": 1, 2: b"b"}
+
+
+def test_raw_invalid() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid raw"):
+        ormsgpack.unpackb(b"\xa1a", raw=1)  # type: ignore[arg-type]
+
+
+def test_unicode_errors_strict() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(str_obj(LATIN_1))
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(str_obj(LATIN_1), unicode_errors="strict")
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb(b"\x81" + str_obj(LATIN_1) + b"\x01", unicode_errors=None)
+
+
+@pytest.mark.parametrize("errors", ("replace", "surrogateescape"))
+def test_unicode_errors(errors: str) -> None:
+    value = LATIN_1.decode("utf-8", errors)
+    assert ormsgpack.unpackb(str_obj(LATIN_1), unicode_errors=errors) == value
+    data = b"\x82" + str_obj(LATIN_1) + str_obj(LATIN_1) + b"\xa1a\x01"
+    for _ in range(2):
+        obj = ormsgpack.unpackb(data, unicode_errors=errors)
+        assert obj == {value: value, "a": 1}
+    long_key = b"\xe9" * 100
+    data = b"\x81\xd9\x64" + long_key + b"\x01"
+    assert ormsgpack.unpackb(data, unicode_errors=errors) == {
+        long_key.decode("utf-8", errors): 1
+    }
+
+
+def test_unicode_errors_valid() -> None:
+    data = ormsgpack.packb({"é": "\U0001f600"})
+    assert ormsgpack.unpackb(data, unicode_errors="replace") == {
+        "é": "\U0001f600"
+    }
+
+
+@pytest.mark.parametrize("errors", ("ignore", "STRICT", b"strict", 1))
+def test_unicode_errors_invalid(errors: object) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid unicode_errors"):
+        ormsgpack.unpackb(b"\xa1a", unicode_errors=errors)  # type: ignore[arg-type]
+
+
+def test_unicode_errors_codec_unpacker() -> None:
+    data = str_obj(LATIN_1)
+    codec = ormsgpack.Codec(unicode_errors="surrogateescape")
+    assert codec.unpackb(data) == "caf\udce9"
+    unpacker = ormsgpack.Unpacker(raw=True)
+    assert list(unpacker.feed(data)) == [LATIN_1]
@@ -0,0 +1,130 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import dataclasses
+import typing
+
+import pytest
+
+import ormsgpack
+
+DATA = ormsgpack.packb(
+    {
+        "version": 1,
+        "world": {
+            "tiles": [[0] * 16] * 16,
+            "characters": [{"name": f"c{i}", "hp": i} for i in range(8)],
+        },
+        "meta": {"created": "2000-01-01"},
+    }
+)
+
+
+@pytest.mark.parametrize(
+    "path,value",
+    (
+        ([], ormsgpack.unpackb(DATA)),
+        (["version"], 1),
+        (["world", "characters", 3, "name"], "c3"),
+        (("world", "characters", -1), {"name": "c7", "hp": 7}),
+        (["world", "tiles", 15, -16], 0),
+        (["meta"], {"created": "2000-01-01"}),
+    ),
+)
+def test_unpackb_path(path: list[str | int], value: typing.Any) -> None:
+    assert ormsgpack.unpackb_path(DATA, path) == value
+
+
+@pytest.mark.parametrize("buffer_type", (bytes, bytearray, memoryview))
+def test_unpackb_path_buffer_type(buffer_type: type) -> None:
+    assert ormsgpack.unpackb_path(buffer_type(DATA), ["version"]) == 1
+
+
+@pytest.mark.parametrize(
+    "path,message",
+    (
+        (["missing"], r"\$\['missing'\]$"),
+        (["world", "characters", 8], r"\$\['world'\]\['characters'\]\[8\]$"),
+        (["world", "characters", -9], r"\$\['world'\]\['characters'\]\[-9\]$"),
+        (["world", 0], r"\$\['world'\]\[0\]$"),
+        (["version", "a"], r"\$\['version'\]\['a'\]$"),
+        (["world", "tiles", "a"], r"\$\['world'\]\['tiles'\]\['a'\]$"),
+    ),
+)
+def test_unpackb_path_not_found(path: list[str | int], message: str) -> None:
+    message = r"^Path not found: " + message
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match=message):
+        ormsgpack.unpackb_path(DATA, path)
+
+
+def test_unpackb_path_int_keys() -> None:
+    data = ormsgpack.packb(
+        {-1: "a", 1: "b", 2**40: "c", "1": "d"}, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+    assert ormsgpack.unpackb_path(data, [-1]) == "a"
+    assert ormsgpack.unpackb_path(data, [1]) == "b"
+    assert ormsgpack.unpackb_path(data, [2**40]) == "c"
+    assert ormsgpack.unpackb_path(data, ["1"]) == "d"
+
+
+def test_unpackb_path_non_str_keys_skipped() -> None:
+    data = ormsgpack.packb(
+        {(1, 2): {"a": 1}, b"a": 2, None: 3, "a": 4},
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    assert ormsgpack.unpackb_path(data, ["a"]) == 4
+
+
+def test_unpackb_path_duplicate_keys() -> None:
+    data = b"\x82\xa1a\x01\xa1a\x02"
+    assert ormsgpack.unpackb_path(data, ["a"]) == 1
+
+
+def test_unpackb_path_invalid_skipped_value() -> None:
+    data = b"\x82\xa1a\xa1\xff\xa1b\x01"
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(data, ["b"])
+    data = b"\x82\xa1a\x92\x01\xa1b\x01"
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(data, ["b"])
+
+
+def test_unpackb_path_trailing_data() -> None:
+    assert ormsgpack.unpackb_path(b"\x91\x01\x02", [0]) == 1
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="Invalid opts"):
+        ormsgpack.unpackb_path(
+            b"\x91\x01", [0], option=ormsgpack.OPT_FORBID_TRAILING_DATA
+        )
+
+
+def test_unpackb_path_arguments() -> None:
+    @dataclasses.dataclass
+    class Character:
+        name: str
+        hp: int
+
+    obj = ormsgpack.unpackb_path(DATA, ["world", "characters", 2], type=Character)
+    assert obj == Character("c2", 2)
+    obj = ormsgpack.unpackb_path(
+        DATA, ["world", "characters"], object_hook=lambda obj: obj["hp"]
+    )
+    assert obj == list(range(8))
+
+
+@pytest.mark.parametrize(
+    "path",
+    ("world", {"world": 1}, None, [1.0], [True], [b"world"], [2**64], ["\ud800"]),
+)
+def test_unpackb_path_invalid_path(path: typing.Any) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(DATA, path)
+
+
+def test_unpackb_path_invalid_arguments() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path()  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(DATA)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(DATA, [], [])  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_path(DATA, [], zxc=1)  # type: ignore[call-arg]
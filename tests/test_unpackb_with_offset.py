@@ -0,0 +1,69 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import pytest
+
+import ormsgpack
+
+
+def test_unpackb_with_offset() -> None:
+    data = ormsgpack.packb({"a": 1})
+    assert ormsgpack.unpackb_with_offset(data) == ({"a": 1}, len(data))
+
+
+def test_unpackb_with_offset_concatenated() -> None:
+    objs = [1, "two", [3.0], {"four": None}, b"five"]
+    data = b"".join(ormsgpack.packb(obj) for obj in objs)
+    result = []
+    offset = 0
+    while offset < len(data):
+        obj, offset = ormsgpack.unpackb_with_offset(data, offset)
+        result.append(obj)
+    assert result == objs
+    assert offset == len(data)
+
+
+@pytest.mark.parametrize("buffer_type", (bytes, bytearray, memoryview))
+def test_unpackb_with_offset_buffer_type(buffer_type: type) -> None:
+    data = buffer_type(b"\x00\x92\x01\x02\x03")
+    assert ormsgpack.unpackb_with_offset(data, start=1) == ([1, 2], 4)
+
+
+def test_unpackb_with_offset_options() -> None:
+    data = b"\xff" + ormsgpack.packb(
+        {1: ormsgpack.Ext(1, b"data")}, option=ormsgpack.OPT_NON_STR_KEYS
+    )
+    assert ormsgpack.unpackb_with_offset(
+        data,
+        1,
+        ext_hook=lambda tag, data: (tag, data),
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    ) == ({1: (1, b"data")}, len(data))
+
+
+def test_unpackb_with_offset_forbid_trailing_data() -> None:
+    option = ormsgpack.OPT_FORBID_TRAILING_DATA
+    assert ormsgpack.unpackb_with_offset(b"\x01\x02", 1, option=option) == (2, 2)
+    with pytest.raises(ormsgpack.MsgpackDecodeError, match="trailing data"):
+        ormsgpack.unpackb_with_offset(b"\x01\x02", option=option)
+
+
+def test_unpackb_with_offset_end_of_data() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset(b"\x01", 1)
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset(b"\x01\x92\x01", 1)
+
+
+@pytest.mark.parametrize("start", (-1, 3, 1.0, "1", None))
+def test_unpackb_with_offset_invalid_start(start: object) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset(b"\x01\x02", start)  # type: ignore[arg-type]
+
+
+def test_unpackb_with_offset_invalid_arguments() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset()  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset(b"\x01", 0, 0)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.unpackb_with_offset(b"\x01", 0, start=0)  # type: ignore[misc]
@@ -0,0 +1,114 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import gc
+import weakref
+
+import pytest
+
+import ormsgpack
+
+
+def test_unpacker() -> None:
+    objects = [1, "a", [1, 2], {"a": [True, None]}, b"\x00" * 300]
+    packed = b"".join(ormsgpack.packb(obj) for obj in objects)
+    unpacker = ormsgpack.Unpacker()
+    assert list(unpacker.feed(packed)) == objects
+    assert list(unpacker) == []
+
+
+def test_unpacker_partial() -> None:
+    objects = [{"a": "b" * 40}, [1, 2, 3], 1.5, "c" * 300]
+    packed = b"".join(ormsgpack.packb(obj) for obj in objects)
+    unpacker = ormsgpack.Unpacker()
+    unpacked = []
+    for i in range(len(packed)):
+        unpacked.extend(unpacker.feed(packed[i : i + 1]))
+    assert unpacked == objects
+
+
+def test_unpacker_partial_hooks() -> None:
+    calls = []
+
+    def object_hook(obj: dict[str, object]) -> dict[str, object]:
+        calls.append(obj)
+        return obj
+
+    packed = ormsgpack.packb([{"a": ormsgpack.Ext(1, b"x")}, {"b": 2}])
+    unpacker = ormsgpack.Unpacker(
+        ext_hook=lambda tag, data: calls.append(data) or data,
+        object_hook=object_hook,
+    )
+    unpacked = []
+    for i in range(len(packed)):
+        unpacked.extend(unpacker.feed(packed[i : i + 1]))
+    assert unpacked == [[{"a": b"x"}, {"b": 2}]]
+    assert calls == [b"x", {"a": b"x"}, {"b": 2}]
+
+
+def test_unpacker_partial_limit() -> None:
+    unpacker = ormsgpack.Unpacker(max_str_len=4)
+    with pytest.raises(ormsgpack.MsgpackDecodeLimitError):
+        next(unpacker.feed(b"\x91\xa5a"))
+
+
+@pytest.mark.parametrize(
+    "data",
+    (
+        pytest.param(bytes, id="bytes"),
+        pytest.param(bytearray, id="bytearray"),
+        pytest.param(memoryview, id="memoryview"),
+    ),
+)
+def test_unpacker_input_type(data: type) -> None:
+    unpacker = ormsgpack.Unpacker()
+    assert list(unpacker.feed(data(b"\x91\x01\x02"))) == [[1], 2]
+
+
+def test_unpacker_invalid_input_type() -> None:
+    unpacker = ormsgpack.Unpacker()
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        unpacker.feed("\x01")  # type: ignore[arg-type]
+
+
+def test_unpacker_invalid_data() -> None:
+    unpacker = ormsgpack.Unpacker()
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        next(unpacker.feed(b"\xc1"))
+
+
+def test_unpacker_ext_hook() -> None:
+    packed = ormsgpack.packb([ormsgpack.Ext(1, b"a"), ormsgpack.Ext(2, b"b")])
+    unpacker = ormsgpack.Unpacker(ext_hook=lambda tag, data: (tag, data))
+    assert list(unpacker.feed(packed)) == [[(1, b"a"), (2, b"b")]]
+
+
+def test_unpacker_option() -> None:
+    packed = ormsgpack.packb({1: 2}, option=ormsgpack.OPT_NON_STR_KEYS)
+    unpacker = ormsgpack.Unpacker(option=ormsgpack.OPT_NON_STR_KEYS)
+    assert list(unpacker.feed(packed)) == [{1: 2}]
+
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.Unpacker(option=ormsgpack.OPT_SORT_KEYS)
+
+
+def test_unpacker_invalid_argument() -> None:
+    with pytest.raises(TypeError):
+        ormsgpack.Unpacker(1)  # type: ignore[call-arg]
+    with pytest.raises(TypeError):
+        ormsgpack.Unpacker(default=None)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.Unpacker(max_depth=-1)
+
+
+def test_unpacker_reference_cycle() -> None:
+    class Hook:
+        def __call__(self, tag: int, data: bytes) -> object:
+            return data
+
+    hook = Hook()
+    unpacker = ormsgpack.Unpacker(ext_hook=hook)
+    hook.unpacker = unpacker  # type: ignore[attr-defined]
+    ref = weakref.ref(hook)
+    del hook, unpacker
+    gc.collect()
+    assert ref() is None
@@ -0,0 +1,221 @@
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+import collections.abc
+import datetime
+import gc
+import weakref
+
+import pytest
+
+import ormsgpack
+
+OBJ = {
+    "version": 1,
+    "world": {
+        "characters": [{"name": f"c{i}", "hp": i} for i in range(8)],
+        "tiles": [[0] * 4] * 4,
+    },
+    "meta": None,
+}
+DATA = ormsgpack.packb(OBJ)
+
+
+def test_view_map() -> None:
+    view = ormsgpack.View(DATA)
+    assert len(view) == 3
+    assert view["version"] == 1
+    assert view["meta"] is None
+    assert isinstance(view["world"], ormsgpack.View)
+    assert view["world"]["characters"][3]["name"] == "c3"
+    assert view.get("version") == 1
+    assert view.get("missing") is None
+    assert view.get("missing", 2) == 2
+    assert "world" in view
+    assert "missing" not in view
+    assert 1 not in view
+    assert list(view) == ["version", "world", "meta"]
+    assert view.keys() == ["version", "world", "meta"]
+    assert [key for key, _ in view.items()] == ["version", "world", "meta"]
+    assert view.values()[0] == 1
+    with pytest.raises(KeyError):
+        view["missing"]
+    with pytest.raises(KeyError):
+        view[1.0]
+
+
+def test_view_array() -> None:
+    view = ormsgpack.View(DATA)["world"]["characters"]
+    assert len(view) == 8
+    assert view[0]["name"] == "c0"
+    assert view[-1]["name"] == "c7"
+    assert [character["hp"] for character in view] == list(range(8))
+    assert [character["hp"] for character in view.values()] == list(range(8))
+    tiles = ormsgpack.View(DATA)["world"]["tiles"][0]
+    assert 0 in tiles
+    assert 1 not in tiles
+    with pytest.raises(IndexError):
+        view[8]
+    with pytest.raises(IndexError):
+        view[-9]
+    with pytest.raises(TypeError):
+        view["a"]
+    with pytest.raises(TypeError):
+        view.keys()
+    with pytest.raises(TypeError):
+        view.get(0)
+
+
+def test_view_int_keys() -> None:
+    data = ormsgpack.packb(
+        {1: "a", -1: "b", 2**40: "c", "1": "d", (1, 2): "e"},
+        option=ormsgpack.OPT_NON_STR_KEYS,
+    )
+    view = ormsgpack.View(data, option=ormsgpack.OPT_NON_STR_KEYS)
+    assert view[1] == "a"
+    assert view[-1] == "b"
+    assert view[2**40] == "c"
+    assert view["1"] == "d"
+    assert view.keys() == [1, -1, 2**40, "1", (1, 2)]
+    with pytest.raises(KeyError):
+        view[True]
+    with pytest.raises(KeyError):
+        view[2**64]
+    view = ormsgpack.View(data)
+    assert view[1] == "a"
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        view.keys()
+
+
+def test_view_duplicate_keys() -> None:
+    view = ormsgpack.View(b"\x82\xa1a\x01\xa1a\x02")
+    assert view["a"] == 1
+    assert view.items() == [("a", 1), ("a", 2)]
+
+
+def test_view_bytes() -> None:
+    view = ormsgpack.View(DATA + b"\xc0")
+    assert bytes(view) == DATA
+    characters = view["world"]["characters"]
+    assert bytes(characters) == ormsgpack.packb(OBJ["world"]["characters"])
+    assert ormsgpack.unpackb(bytes(characters[2])) == {"name": "c2", "hp": 2}
+
+
+@pytest.mark.parametrize("buffer_type", (bytes, bytearray, memoryview))
+def test_view_buffer_type(buffer_type: type) -> None:
+    view = ormsgpack.View(buffer_type(DATA))
+    assert view["world"]["characters"][1]["hp"] == 1
+
+
+def test_view_bytearray_resize() -> None:
+    data = bytearray(DATA)
+    view = ormsgpack.View(data)["world"]
+    with pytest.raises(BufferError):
+        data.extend(b"\xc0")
+    assert view["characters"][0]["hp"] == 0
+    del view
+    data.extend(b"\xc0")
+
+
+def test_view_arguments() -> None:
+    value = datetime.datetime(2000, 1, 1, tzinfo=datetime.timezone.utc)
+    data = ormsgpack.packb(
+        {"a": value, "b": b"data"}, option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+    )
+    view = ormsgpack.View(
+        data,
+        option=ormsgpack.OPT_DATETIME_AS_TIMESTAMP_EXT
+        | ormsgpack.OPT_BIN_AS_MEMORYVIEW,
+    )
+    assert view["a"] == value
+    assert isinstance(view["b"], memoryview)
+    assert view["b"] == b"data"
+    view = ormsgpack.View(ormsgpack.packb({"a": "a"}), raw=True)
+    assert view["a"] == b"a"
+
+
+def test_view_repr() -> None:
+    view = ormsgpack.View(DATA)
+    assert repr(view) == "<ormsgpack.View of a map of length 3>"
+    assert repr(view["world"]["tiles"]) == "<ormsgpack.View of an array of length 4>"
+
+
+@pytest.mark.parametrize("data", (b"", b"\x01", b"\xa1a", b"\xc1"))
+def test_view_invalid_input(data: bytes) -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View(data)
+
+
+@pytest.mark.parametrize(
+    "data,pos,path",
+    (
+        (b"\x92\x01", 2, [1]),
+        (b"\x82\xa1a\x01", 4, []),
+        (b"\x82\xa1a\x01\xa1b", 6, ["b"]),
+        (b"\x91\xc1", 1, [0]),
+        (b"\x81\xa1\xff\x01", 1, []),
+    ),
+)
+def test_view_invalid_data(data: bytes, pos: int, path: list[object]) -> None:
+    view = ormsgpack.View(data)
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        view[0]
+    assert exc_info.value.pos == pos
+    assert exc_info.value.path == path
+
+
+def test_view_trailing_data() -> None:
+    view = ormsgpack.View(b"\x91\x01\x02")
+    assert list(view) == [1]
+    option = ormsgpack.OPT_FORBID_TRAILING_DATA
+    view = ormsgpack.View(b"\x91\x01", option=option)
+    assert list(view) == [1]
+    with pytest.raises(ormsgpack.MsgpackDecodeError) as exc_info:
+        ormsgpack.View(b"\x91\x01\x02", option=option)
+    assert exc_info.value.pos == 2
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View(b"\x92\x01", option=option)
+
+
+def test_view_invalid_arguments() -> None:
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View()  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View(DATA, DATA)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View(DATA, type=dict)  # type: ignore[call-arg]
+    with pytest.raises(ormsgpack.MsgpackDecodeError):
+        ormsgpack.View("a")  # type: ignore[arg-type]
+
+
+def test_view_abc() -> None:
+    view = ormsgpack.View(DATA)
+    assert isinstance(view, collections.abc.Mapping)
+    assert not isinstance(view, collections.abc.Sequence)
+    characters = view["world"]["characters"]
+    assert isinstance(characters, ormsgpack.View)
+    assert isinstance(characters, collections.abc.Sequence)
+    assert not isinstance(characters, collections.abc.Mapping)
+    array_view = ormsgpack.View(ormsgpack.packb([1, 2]))
+    assert isinstance(array_view, collections.abc.Sequence)
+
+
+def test_view_match() -> None:
+    match ormsgpack.View(DATA):
+        case {"world": {"characters": [_, {"name": name}, *_]}}:
+            assert name == "c1"
+        case _:
+            pytest.fail("no match")
+
+
+def test_view_reference_cycle() -> None:
+    class Hook:
+        def __call__(self, obj: dict[str, object]) -> object:
+            return obj
+
+    hook = Hook()
+    view = ormsgpack.View(DATA, object_hook=hook)
+    hook.view = view["world"]  # type: ignore[attr-defined]
+    ref = weakref.ref(hook)
+    del hook, view
+    gc.collect()
+    assert ref() is None